@@ -0,0 +1,4 @@
+// recompila quando uma migração é adicionada (sqlx::migrate! embute a pasta)
+fn main() {
+    println!("cargo:rerun-if-changed=../migrations");
+}
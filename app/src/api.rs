@@ -0,0 +1,87 @@
+use axum::{Json, Router, extract::State, middleware, routing::post};
+use serde::{Deserialize, Serialize};
+use shared::{AppError, ErrorResponse, JsonValidado, SharedState};
+use utoipa::{OpenApi, ToSchema};
+use validator::Validate;
+
+use crate::{
+    chamado, externo,
+    middlewares::{self, VALIDADE_TOKEN},
+    permissao::{self, UserService},
+};
+
+/*
+API JSON versionada em /api/v1, sobre os mesmos services das telas
+autenticação só por `Authorization: Bearer <token>`; o token sai de POST /api/v1/auth/token
+com as mesmas credenciais do login (usuário, senha e código OTP)
+erros no corpo {"error": ...}; validação com 422 e o detalhe por campo em "campos"
+*/
+#[derive(OpenApi)]
+#[openapi(paths(token))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TokenRequest {
+    #[validate(length(min = 1, message = "Informe o usuário"))]
+    pub username: String,
+    #[validate(length(min = 1, message = "Informe a senha"))]
+    pub password: String,
+    /// código de 6 dígitos do autenticador
+    #[validate(length(min = 1, message = "Informe o código OTP"))]
+    pub otp: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    /// sempre `Bearer`
+    pub token_type: &'static str,
+    /// validade em segundos
+    pub expires_in: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/token",
+    tag = "v1",
+    request_body = TokenRequest,
+    responses(
+        (status = 200, description = "Token para o cabeçalho Authorization", body = TokenResponse),
+        (status = 401, description = "Usuário, senha ou código inválidos", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+)]
+pub async fn token(
+    State(state): State<SharedState>,
+    JsonValidado(input): JsonValidado<TokenRequest>,
+) -> Result<Json<TokenResponse>, AppError> {
+    let user = UserService::get_by_username(&state.db, &input.username)
+        .await
+        .ok()
+        .filter(|user| UserService::credenciais_validas(user, &input.password, &input.otp))
+        .ok_or(AppError::UserNotAuthenticated)?;
+
+    Ok(Json(TokenResponse {
+        access_token: middlewares::gerar_token(&user.username),
+        token_type: "Bearer",
+        expires_in: VALIDADE_TOKEN,
+    }))
+}
+
+/// Rotas da API v1; só /auth/token é pública
+pub fn router(state: SharedState) -> Router<SharedState> {
+    let privadas = Router::new()
+        .merge(chamado::router_v1())
+        .merge(externo::router_v1())
+        .merge(permissao::router_v1())
+        .layer(middleware::from_fn_with_state(
+            state,
+            middlewares::autenticar_api,
+        ));
+
+    let v1 = Router::new()
+        .route("/auth/token", post(token))
+        .merge(privadas);
+
+    Router::new().nest("/api/v1", v1)
+}
@@ -0,0 +1,172 @@
+/// Anexos por comentário
+pub const MAX_ANEXOS: usize = 5;
+
+/// Tamanho máximo de cada anexo (5 MiB)
+pub const MAX_TAMANHO_ANEXO: usize = 5 * 1024 * 1024;
+
+/// Limite do corpo da requisição de comentário: os anexos mais o texto do editor
+pub const LIMITE_REQUISICAO: usize = MAX_ANEXOS * MAX_TAMANHO_ANEXO + 1024 * 1024;
+
+/// Tipo de arquivo aceito como anexo
+#[derive(Debug, PartialEq, Eq)]
+pub struct TipoAnexo {
+    pub extensao: &'static str,
+    pub content_type: &'static str,
+    /// bytes iniciais do formato; `None` para texto puro
+    assinatura: Option<&'static [u8]>,
+}
+
+const ZIP: &[u8] = b"PK\x03\x04";
+const OLE: &[u8] = b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1";
+
+const TIPOS: &[TipoAnexo] = &[
+    TipoAnexo {
+        extensao: "pdf",
+        content_type: "application/pdf",
+        assinatura: Some(b"%PDF-"),
+    },
+    TipoAnexo {
+        extensao: "png",
+        content_type: "image/png",
+        assinatura: Some(b"\x89PNG\r\n\x1a\n"),
+    },
+    TipoAnexo {
+        extensao: "jpg",
+        content_type: "image/jpeg",
+        assinatura: Some(b"\xFF\xD8\xFF"),
+    },
+    TipoAnexo {
+        extensao: "jpeg",
+        content_type: "image/jpeg",
+        assinatura: Some(b"\xFF\xD8\xFF"),
+    },
+    TipoAnexo {
+        extensao: "gif",
+        content_type: "image/gif",
+        assinatura: Some(b"GIF8"),
+    },
+    TipoAnexo {
+        extensao: "txt",
+        content_type: "text/plain",
+        assinatura: None,
+    },
+    TipoAnexo {
+        extensao: "csv",
+        content_type: "text/csv",
+        assinatura: None,
+    },
+    TipoAnexo {
+        extensao: "doc",
+        content_type: "application/msword",
+        assinatura: Some(OLE),
+    },
+    TipoAnexo {
+        extensao: "xls",
+        content_type: "application/vnd.ms-excel",
+        assinatura: Some(OLE),
+    },
+    TipoAnexo {
+        extensao: "docx",
+        content_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        assinatura: Some(ZIP),
+    },
+    TipoAnexo {
+        extensao: "xlsx",
+        content_type: "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        assinatura: Some(ZIP),
+    },
+    TipoAnexo {
+        extensao: "odt",
+        content_type: "application/vnd.oasis.opendocument.text",
+        assinatura: Some(ZIP),
+    },
+    TipoAnexo {
+        extensao: "ods",
+        content_type: "application/vnd.oasis.opendocument.spreadsheet",
+        assinatura: Some(ZIP),
+    },
+    TipoAnexo {
+        extensao: "zip",
+        content_type: "application/zip",
+        assinatura: Some(ZIP),
+    },
+];
+
+/// Extensões aceitas, para o `accept` do input de arquivo
+pub fn extensoes_aceitas() -> String {
+    TIPOS
+        .iter()
+        .map(|t| format!(".{}", t.extensao))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/*
+valida o anexo antes de gravar qualquer coisa
+a extensão do nome enviado precisa estar em TIPOS e o conteúdo precisa
+começar pela assinatura do formato (texto puro: UTF-8 válido);
+o content-type vem da tabela, nunca do cliente
+*/
+pub fn validar(nome: &str, data: &[u8]) -> Result<&'static TipoAnexo, String> {
+    if data.is_empty() {
+        return Err(format!("O anexo '{}' está vazio.", nome));
+    }
+    if data.len() > MAX_TAMANHO_ANEXO {
+        return Err(format!(
+            "O anexo '{}' passa de {} MB.",
+            nome,
+            MAX_TAMANHO_ANEXO / (1024 * 1024)
+        ));
+    }
+
+    let extensao = nome
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    let Some(tipo) = TIPOS.iter().find(|t| t.extensao == extensao) else {
+        return Err(format!("Tipo de arquivo não permitido: '{}'.", nome));
+    };
+
+    let conteudo_confere = match tipo.assinatura {
+        Some(assinatura) => data.starts_with(assinatura),
+        None => std::str::from_utf8(data).is_ok(),
+    };
+    if !conteudo_confere {
+        return Err(format!(
+            "O conteúdo de '{}' não é um arquivo .{} válido.",
+            nome, tipo.extensao
+        ));
+    }
+
+    Ok(tipo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validar_anexo() {
+        let tipo = validar("Relatório.PDF", b"%PDF-1.7 ...").unwrap();
+        assert_eq!(tipo.extensao, "pdf");
+        assert_eq!(tipo.content_type, "application/pdf");
+
+        assert!(validar("notas.txt", "olá".as_bytes()).is_ok());
+        assert!(validar("planilha.xlsx", b"PK\x03\x04resto").is_ok());
+    }
+
+    #[test]
+    fn test_validar_anexo_rejeita() {
+        // extensão fora da lista ou ausente
+        assert!(validar("script.sh", b"#!/bin/sh").is_err());
+        assert!(validar("pagina.html", b"<script>").is_err());
+        assert!(validar("sem_extensao", b"%PDF-1.7").is_err());
+        // extensão permitida com conteúdo de outro formato
+        assert!(validar("foto.png", b"<html>").is_err());
+        assert!(validar("texto.txt", b"\xFF\xFE\x00").is_err());
+        // vazio e grande demais
+        assert!(validar("vazio.pdf", b"").is_err());
+        let grande = vec![b'a'; MAX_TAMANHO_ANEXO + 1];
+        assert!(validar("grande.txt", &grande).is_err());
+    }
+}
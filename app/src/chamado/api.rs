@@ -0,0 +1,447 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use shared::{
+    AppError, ErrorResponse, JsonValidado, PaginatedResponse, PaginationQuery, SharedState,
+};
+
+use crate::{
+    chamado::{
+        StatusChamado,
+        model::{
+            Atribuicao, Chamado, ComentarioChamado, ComentarioThread, GerenciamentoChamado,
+            StatusHistorico,
+        },
+        schema::{
+            AlterarStatusChamado, CreateChamado, CreateComentario, CreateGerenciamentoChamado,
+            TransferirChamado, UpdateChamado, UpdateGerenciamentoChamado,
+        },
+        service::{ChamadoService, ComentarioChamadoService, FilaService, GerenciamentoChamadoService},
+        transicao::Papel,
+        view::pode_atender,
+    },
+    middlewares::CurrentUser,
+};
+
+/*
+==========================================
+
+------------- API v1 CHAMADO -----------
+==========================================
+
+mesmas regras das telas: o solicitante só vê e altera os próprios chamados;
+quem atende (chamado_admin) vê todos
+
+*/
+
+/// Confere se o usuário pode ver/alterar o chamado
+async fn verificar_acesso(
+    state: &SharedState,
+    current_user: &CurrentUser,
+    id: i64,
+) -> Result<(), AppError> {
+    if ChamadoService::can_access(&current_user.current_user, id, &state.db).await {
+        Ok(())
+    } else {
+        Err(AppError::PermissionDenied)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/chamados",
+    tag = "v1",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de chamados visíveis ao usuário", body = PaginatedResponse<Chamado>),
+        (status = 401, description = "Token ausente ou inválido", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn listar_chamados(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Query(q): Query<PaginationQuery>,
+) -> Result<Json<PaginatedResponse<Chamado>>, AppError> {
+    let service = ChamadoService::new();
+    let query = q.list_query();
+
+    let res = service
+        .list_visiveis(
+            &state.db,
+            query,
+            Papel::do_usuario(&current_user),
+            current_user.current_user.id,
+        )
+        .await?;
+
+    Ok(Json(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/chamados/{id}",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    responses(
+        (status = 200, description = "Chamado", body = Chamado),
+        (status = 403, description = "Chamado de outro usuário", body = ErrorResponse),
+        (status = 404, description = "Chamado não encontrado", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn obter_chamado(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+) -> Result<Json<Chamado>, AppError> {
+    verificar_acesso(&state, &current_user, id).await?;
+
+    let chamado = ChamadoService::new().get_by_id(&state.db, id).await?;
+    Ok(Json(chamado))
+}
+
+#[utoipa::path(
+    post,
+    path = "/chamados",
+    tag = "v1",
+    request_body = CreateChamado,
+    responses(
+        (status = 201, description = "Chamado aberto em nome do usuário do token", body = Chamado),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn criar_chamado(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    JsonValidado(mut body): JsonValidado<CreateChamado>,
+) -> Result<(StatusCode, Json<Chamado>), AppError> {
+    // mesmo que a tela: solicitante é o usuário logado e todo chamado novo começa "Aberto"
+    body.user_solic_id = Some(current_user.current_user.id);
+    body.status = Some(StatusChamado::Aberto as i32);
+
+    let chamado = ChamadoService::new().create(&*state.db, body).await?;
+    Ok((StatusCode::CREATED, Json(chamado)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/chamados/{id}",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    request_body = UpdateChamado,
+    responses(
+        (status = 200, description = "Chamado atualizado", body = Chamado),
+        (status = 403, description = "Chamado de outro usuário", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn atualizar_chamado(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    JsonValidado(input): JsonValidado<UpdateChamado>,
+) -> Result<Json<Chamado>, AppError> {
+    verificar_acesso(&state, &current_user, id).await?;
+
+    let chamado = ChamadoService::new().update(&*state.db, id, input).await?;
+    Ok(Json(chamado))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/chamados/{id}",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    responses(
+        (status = 204, description = "Chamado excluído"),
+        (status = 403, description = "Chamado de outro usuário", body = ErrorResponse),
+        (status = 409, description = "Chamado não está mais em Aberto", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn excluir_chamado(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, AppError> {
+    verificar_acesso(&state, &current_user, id).await?;
+
+    let service = ChamadoService::new();
+    let chamado = service.get_by_id(&state.db, id).await?;
+
+    // somente chamados ainda em Aberto podem ser excluídos
+    if !chamado.status.is_some_and(|s| StatusChamado::from_i32(s).pode_excluir()) {
+        return Err(AppError::Conflict(
+            "Chamado não pode ser excluído pois não está mais em Aberto".to_string(),
+        ));
+    }
+
+    service.delete(&*state.db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/chamados/{id}/status",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    request_body = AlterarStatusChamado,
+    responses(
+        (status = 200, description = "Status alterado", body = Chamado),
+        (status = 403, description = "Chamado de outro usuário", body = ErrorResponse),
+        (status = 409, description = "Transição não permitida para o usuário", body = ErrorResponse),
+        (status = 422, description = "Status inválido", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn alterar_status(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(input): Json<AlterarStatusChamado>,
+) -> Result<Json<Chamado>, AppError> {
+    verificar_acesso(&state, &current_user, id).await?;
+
+    let status = StatusChamado::try_from(input.status)
+        .map_err(|e| AppError::Unprocessable(e.to_string()))?;
+    let chamado = ChamadoService::new()
+        .alterar_status(
+            &*state.db,
+            id,
+            status,
+            Papel::do_usuario(&current_user),
+            current_user.current_user.id,
+            input.motivo,
+        )
+        .await?;
+    Ok(Json(chamado))
+}
+
+#[utoipa::path(
+    get,
+    path = "/chamados/{id}/historico",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    responses(
+        (status = 200, description = "Transições de status em ordem cronológica", body = Vec<StatusHistorico>),
+        (status = 403, description = "Chamado de outro usuário", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn listar_historico(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<StatusHistorico>>, AppError> {
+    verificar_acesso(&state, &current_user, id).await?;
+
+    let (historico, _) = ChamadoService::new().historico(&state.db, id).await?;
+    Ok(Json(historico))
+}
+
+/*
+==========================================
+
+---------- API v1 ATENDIMENTO ----------
+==========================================
+
+somente chamado_admin (validado no router)
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/chamados/{id}/atendimento",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    responses(
+        (status = 200, description = "Atendimento do chamado", body = GerenciamentoChamado),
+        (status = 403, description = "Sem a permissão chamado_admin", body = ErrorResponse),
+        (status = 404, description = "Atendimento não iniciado", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn obter_atendimento(
+    State(state): State<SharedState>,
+    Path(chamado_id): Path<i64>,
+) -> Result<Json<GerenciamentoChamado>, AppError> {
+    let atendimento = GerenciamentoChamadoService::new()
+        .get_by_chamado_id(&state.db, chamado_id)
+        .await?;
+    Ok(Json(atendimento))
+}
+
+#[utoipa::path(
+    post,
+    path = "/chamados/{id}/atendimento",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    responses(
+        (status = 200, description = "Atendimento já estava iniciado", body = GerenciamentoChamado),
+        (status = 201, description = "Atendimento iniciado pelo usuário do token", body = GerenciamentoChamado),
+        (status = 403, description = "Sem a permissão chamado_admin", body = ErrorResponse),
+        (status = 404, description = "Chamado não encontrado", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn iniciar_atendimento(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(chamado_id): Path<i64>,
+) -> Result<(StatusCode, Json<GerenciamentoChamado>), AppError> {
+    let service = GerenciamentoChamadoService::new();
+
+    ChamadoService::new()
+        .get_by_id(&state.db, chamado_id)
+        .await?;
+
+    // busca se já em atendimento
+    if let Ok(atendimento) = service.get_by_chamado_id(&state.db, chamado_id).await {
+        return Ok((StatusCode::OK, Json(atendimento)));
+    }
+
+    let gerenciamento = CreateGerenciamentoChamado {
+        descricao: None,
+        categoria_id: None,
+        chamado_id,
+        user_atend_id: current_user.current_user.id,
+        observacao_chamado: None,
+    };
+    let atendimento = service.create(&*state.db, gerenciamento).await?;
+    Ok((StatusCode::CREATED, Json(atendimento)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/chamados/{id}/atendimento",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    request_body = UpdateGerenciamentoChamado,
+    responses(
+        (status = 200, description = "Atendimento atualizado", body = GerenciamentoChamado),
+        (status = 403, description = "Sem a permissão chamado_admin", body = ErrorResponse),
+        (status = 404, description = "Atendimento não iniciado", body = ErrorResponse),
+        (status = 409, description = "Alterado por outro atendente depois de `updated_at`", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn atualizar_atendimento(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(chamado_id): Path<i64>,
+    JsonValidado(mut input): JsonValidado<UpdateGerenciamentoChamado>,
+) -> Result<Json<GerenciamentoChamado>, AppError> {
+    let service = GerenciamentoChamadoService::new();
+    let atendimento = service.get_by_chamado_id(&state.db, chamado_id).await?;
+
+    input.updated_by = Some(current_user.current_user.id);
+    let atendimento = service
+        .update(&*state.db, atendimento.id, chamado_id, input)
+        .await?;
+    Ok(Json(atendimento))
+}
+
+#[utoipa::path(
+    post,
+    path = "/chamados/{id}/transferir",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    request_body = TransferirChamado,
+    responses(
+        (status = 200, description = "Novo responsável pelo chamado", body = Atribuicao),
+        (status = 403, description = "Sem a permissão chamado_admin", body = ErrorResponse),
+        (status = 409, description = "Chamado fechado", body = ErrorResponse),
+        (status = 422, description = "Atendente fora da fila do serviço ou já responsável", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn transferir_chamado(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(chamado_id): Path<i64>,
+    JsonValidado(input): JsonValidado<TransferirChamado>,
+) -> Result<Json<Atribuicao>, AppError> {
+    let atribuicao = FilaService::new()
+        .transferir(
+            &*state.db,
+            chamado_id,
+            input.user_id,
+            current_user.current_user.id,
+            input.motivo,
+        )
+        .await?;
+    Ok(Json(atribuicao))
+}
+
+/*
+==========================================
+
+---------- API v1 COMENTÁRIOS ----------
+==========================================
+
+conversa do chamado; comentários internos só para quem atende
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/chamados/{id}/comentarios",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    responses(
+        (status = 200, description = "Conversa em ordem cronológica", body = Vec<ComentarioThread>),
+        (status = 403, description = "Chamado de outro usuário", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn listar_comentarios(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<ComentarioThread>>, AppError> {
+    verificar_acesso(&state, &current_user, id).await?;
+
+    let thread = ComentarioChamadoService::new()
+        .list_thread(&state.db, id, pode_atender(&current_user))
+        .await?;
+    Ok(Json(thread))
+}
+
+#[utoipa::path(
+    post,
+    path = "/chamados/{id}/comentarios",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do chamado")),
+    request_body = CreateComentario,
+    responses(
+        (status = 201, description = "Comentário do usuário do token", body = ComentarioChamado),
+        (status = 403, description = "Chamado de outro usuário ou interno sem chamado_admin", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn criar_comentario(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    JsonValidado(mut input): JsonValidado<CreateComentario>,
+) -> Result<(StatusCode, Json<ComentarioChamado>), AppError> {
+    verificar_acesso(&state, &current_user, id).await?;
+
+    if input.interno && !pode_atender(&current_user) {
+        return Err(AppError::PermissionDenied);
+    }
+
+    input.chamado_id = id;
+    input.autor_id = current_user.current_user.id;
+
+    let comentario = ComentarioChamadoService::new()
+        .create(&*state.db, input, Vec::new())
+        .await?;
+    Ok((StatusCode::CREATED, Json(comentario)))
+}
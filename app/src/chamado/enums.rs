@@ -2,8 +2,6 @@ use serde::{Deserialize, Serialize};
 use sqlx::postgres::{PgTypeInfo, PgValueRef};
 use sqlx::{Decode, Encode, Postgres, Type};
 
-use crate::core::StatusOpt;
-
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[repr(i32)]
 pub enum StatusChamado {
@@ -80,28 +78,3 @@ impl std::fmt::Display for StatusChamado {
         write!(f, "{}", str)
     }
 }
-
-impl StatusChamado {
-    pub fn all() -> Vec<Self> {
-        vec![
-            StatusChamado::Aberto,
-            StatusChamado::EmAtendimento,
-            StatusChamado::Pausado,
-            StatusChamado::Resolvido,
-            StatusChamado::Fechado,
-        ]
-    }
-
-    /*
-    para o select do html
-    */
-    pub fn status_options() -> Vec<StatusOpt> {
-        StatusChamado::all()
-            .into_iter()
-            .map(|s| StatusOpt {
-                value: s as i32,      // valor do <option>
-                label: s.to_string(), // usa impl Display p/ rótulo
-            })
-            .collect()
-    }
-}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use shared::generic_list::FieldOption;
+
+/// Como a fila do serviço escolhe o atendente de um chamado novo
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Estrategia {
+    /// ninguém é escolhido: o chamado aguarda alguém assumir
+    Manual = 0,
+    Rodizio = 1,
+    MenorCarga = 2,
+    /// menor carga entre quem domina o tipo do chamado
+    Habilidade = 3,
+}
+
+impl Estrategia {
+    pub const TODAS: [Estrategia; 4] = [
+        Estrategia::Manual,
+        Estrategia::Rodizio,
+        Estrategia::MenorCarga,
+        Estrategia::Habilidade,
+    ];
+
+    /// para o select do formulário da fila
+    pub fn opcoes() -> Vec<FieldOption> {
+        Self::TODAS
+            .iter()
+            .map(|e| FieldOption::new(*e as i64, e))
+            .collect()
+    }
+
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            1 => Estrategia::Rodizio,
+            2 => Estrategia::MenorCarga,
+            3 => Estrategia::Habilidade,
+            _ => Estrategia::Manual,
+        }
+    }
+}
+
+impl std::fmt::Display for Estrategia {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            Estrategia::Manual => "Manual",
+            Estrategia::Rodizio => "Rodízio",
+            Estrategia::MenorCarga => "Menor carga",
+            Estrategia::Habilidade => "Por habilidade",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// Membro ativo da fila no momento da distribuição
+#[derive(Debug, Clone, Copy)]
+pub struct Candidato {
+    pub user_id: i64,
+    /// chamados atribuídos ainda em aberto (nem Resolvido nem Fechado)
+    pub carga: i64,
+    /// domina o tipo do chamado (chamado_atendente_habilidade)
+    pub habilitado: bool,
+}
+
+/*
+escolhe o atendente entre os membros ativos da fila
+  rodízio: o próximo user_id depois de `ultimo`, voltando ao primeiro
+  menor carga: menos chamados em aberto; empate fica com o menor user_id
+  habilidade: menor carga entre os habilitados; sem nenhum, entre todos
+*/
+pub fn escolher(
+    estrategia: Estrategia,
+    candidatos: &[Candidato],
+    ultimo: Option<i64>,
+) -> Option<i64> {
+    match estrategia {
+        Estrategia::Manual => None,
+        Estrategia::Rodizio => {
+            let menor = candidatos.iter().map(|c| c.user_id).min();
+            let ultimo = ultimo.unwrap_or(i64::MIN);
+            candidatos
+                .iter()
+                .map(|c| c.user_id)
+                .filter(|id| *id > ultimo)
+                .min()
+                .or(menor)
+        }
+        Estrategia::MenorCarga => menor_carga(candidatos.iter()),
+        Estrategia::Habilidade => menor_carga(candidatos.iter().filter(|c| c.habilitado))
+            .or(menor_carga(candidatos.iter())),
+    }
+}
+
+fn menor_carga<'a>(candidatos: impl Iterator<Item = &'a Candidato>) -> Option<i64> {
+    candidatos
+        .min_by_key(|c| (c.carga, c.user_id))
+        .map(|c| c.user_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidato(user_id: i64, carga: i64, habilitado: bool) -> Candidato {
+        Candidato {
+            user_id,
+            carga,
+            habilitado,
+        }
+    }
+
+    #[test]
+    fn test_rodizio_volta_ao_primeiro() {
+        let membros = [
+            candidato(7, 0, false),
+            candidato(3, 0, false),
+            candidato(5, 0, false),
+        ];
+
+        assert_eq!(escolher(Estrategia::Rodizio, &membros, None), Some(3));
+        assert_eq!(escolher(Estrategia::Rodizio, &membros, Some(3)), Some(5));
+        assert_eq!(escolher(Estrategia::Rodizio, &membros, Some(7)), Some(3));
+        // último escolhido saiu da fila
+        assert_eq!(escolher(Estrategia::Rodizio, &membros, Some(4)), Some(5));
+    }
+
+    #[test]
+    fn test_menor_carga_e_habilidade() {
+        let membros = [
+            candidato(1, 4, true),
+            candidato(2, 1, false),
+            candidato(3, 1, false),
+        ];
+
+        assert_eq!(escolher(Estrategia::MenorCarga, &membros, None), Some(2));
+        assert_eq!(escolher(Estrategia::Habilidade, &membros, None), Some(1));
+
+        let sem_habilitados = [candidato(1, 4, false), candidato(2, 1, false)];
+        assert_eq!(
+            escolher(Estrategia::Habilidade, &sem_habilitados, None),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_manual_e_fila_vazia() {
+        let membros = [candidato(1, 0, true)];
+
+        assert_eq!(escolher(Estrategia::Manual, &membros, None), None);
+        assert_eq!(escolher(Estrategia::MenorCarga, &[], None), None);
+        assert_eq!(escolher(Estrategia::Rodizio, &[], Some(1)), None);
+    }
+}
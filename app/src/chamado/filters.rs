@@ -13,3 +13,19 @@ pub fn status_filter(value: i32) -> String {
         _ => "Desconhecido".to_string(),
     }
 }
+
+/*
+Formata segundos como duração legível (histórico de status).
+env.add_filter("duracao", duracao_filter);
+ */
+pub fn duracao_filter(segundos: i64) -> String {
+    let minutos = segundos / 60;
+    let (dias, horas, minutos) = (minutos / 1440, minutos % 1440 / 60, minutos % 60);
+
+    match (dias, horas, minutos) {
+        (0, 0, 0) => "menos de 1min".to_string(),
+        (0, 0, m) => format!("{}min", m),
+        (0, h, m) => format!("{}h {}min", h, m),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
@@ -1,13 +1,23 @@
+mod anexo;
+mod api;
 mod enums;
+mod fila;
 mod filters;
 mod model;
+mod prioridade;
 mod repository;
 mod router;
 mod schema;
 mod service;
+mod sla;
+mod tempo_real;
+mod transicao;
 mod view;
 
 pub use enums::StatusChamado;
-pub use filters::status_filter;
-pub use router::router;
+pub use filters::{duracao_filter, status_filter};
+pub use prioridade::{nivel_filter, prioridade_badge_filter};
+pub use router::{ApiDoc, ApiV1Doc, router, router_v1};
 pub use service::ChamadoService;
+pub use sla::verificador as verificador_sla;
+pub use tempo_real::{CanalChamados, ouvinte as ouvinte_chamados};
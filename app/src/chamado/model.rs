@@ -3,21 +3,23 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, from_str};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct TipoChamado {
     pub id: i64,
     pub nome: String,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct ServicoChamado {
     pub id: i64,
     pub nome: String,
     pub tipo_id: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct CategoriaChamado {
     pub id: i64,
     pub nome: String,
@@ -32,7 +34,7 @@ pub struct ImagemChamado {
     pub with_background: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone, ToSchema)]
 pub struct Chamado {
     pub id: i64,
     pub titulo: String,
@@ -43,6 +45,12 @@ pub struct Chamado {
     pub user_solic_id: i64,
     pub servico_id: i64,
     pub tipo_id: i64,
+    /// 0 baixo, 1 médio, 2 alto
+    pub impacto: i32,
+    /// 0 baixa, 1 média, 2 alta
+    pub urgencia: i32,
+    /// derivada de impacto e urgência (ver chamado::prioridade): 0 baixa a 3 crítica
+    pub prioridade: i32,
 }
 
 impl Chamado {
@@ -108,7 +116,7 @@ impl Chamado {
         Ok(imagens)
     }
 }
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct GerenciamentoChamado {
     pub id: i64,
     pub descricao: Option<String>,
@@ -118,4 +126,211 @@ pub struct GerenciamentoChamado {
     pub chamado_id: i64,
     pub user_atend_id: i64,
     pub observacao_chamado: Option<String>,
+    pub updated_by: Option<i64>,
+}
+
+/// Comentário da conversa do chamado; `interno` só aparece para quem atende
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ComentarioChamado {
+    pub id: i64,
+    pub chamado_id: i64,
+    pub autor_id: i64,
+    /// documento EditorJS
+    pub corpo: Value,
+    pub interno: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AnexoComentario {
+    pub id: i64,
+    pub comentario_id: i64,
+    /// nome original do arquivo
+    pub nome: String,
+    pub url: String,
+    pub tamanho: i64,
+    pub content_type: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Comentário com o nome do autor e os anexos, como exibido na conversa
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ComentarioThread {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub comentario: ComentarioChamado,
+    pub autor_nome: String,
+    #[sqlx(skip)]
+    pub anexos: Vec<AnexoComentario>,
+}
+
+/// Transição registrada em chamado_status_historico, com o nome de quem fez
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct StatusHistorico {
+    pub id: i64,
+    pub chamado_id: i64,
+    /// vazio na abertura do chamado
+    pub status_de: Option<i32>,
+    pub status_para: i32,
+    pub user_id: Option<i64>,
+    pub user_nome: Option<String>,
+    pub motivo: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// tempo em `status_para` até a transição seguinte (ou até agora)
+    pub segundos: Option<i64>,
+    /// parte de `segundos` dentro do expediente (ver core::calendario)
+    pub segundos_uteis: Option<i64>,
+}
+
+/// Tempo total do chamado em cada status (view chamado_tempo_status)
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct TempoStatus {
+    pub status: i32,
+    pub segundos: i64,
+    pub segundos_uteis: i64,
+}
+
+/// Política de SLA de um serviço ou, como padrão, de um tipo de chamado
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SlaPolitica {
+    pub id: i64,
+    pub servico_id: Option<i64>,
+    pub servico_nome: Option<String>,
+    pub tipo_id: Option<i64>,
+    pub tipo_nome: Option<String>,
+    pub prazo_resposta_min: i32,
+    pub prazo_resolucao_min: i32,
+    pub alerta_pct: i32,
+}
+
+/// Dados do chamado para o cálculo do SLA (ver chamado::sla::calcular)
+#[derive(Debug, FromRow)]
+pub struct BaseSla {
+    pub chamado_id: i64,
+    pub politica_id: i64,
+    pub prazo_resposta_min: i32,
+    pub prazo_resolucao_min: i32,
+    pub alerta_pct: i32,
+    pub prioridade: i32,
+    pub aberto_em: DateTime<Utc>,
+    pub respondido_em: Option<DateTime<Utc>>,
+    pub resolvido_em: Option<DateTime<Utc>>,
+    /// trechos em Pausado (início, fim); o atual termina agora
+    pub pausas_inicio: Vec<DateTime<Utc>>,
+    pub pausas_fim: Vec<DateTime<Utc>>,
+}
+
+/// Prazos e situação calculados do chamado (tabela chamado_sla)
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ChamadoSla {
+    pub chamado_id: i64,
+    pub prazo_resposta: DateTime<Utc>,
+    pub prazo_resolucao: DateTime<Utc>,
+    pub respondido_em: Option<DateTime<Utc>>,
+    pub resolvido_em: Option<DateTime<Utc>>,
+    /// 0 no prazo, 1 em risco, 2 violado
+    pub situacao: i32,
+    pub violado_em: Option<DateTime<Utc>>,
+}
+
+/// Linha do relatório de violações de SLA
+#[derive(Debug, Serialize, FromRow)]
+pub struct ViolacaoSla {
+    pub chamado_id: i64,
+    pub titulo: String,
+    pub status: i32,
+    pub servico_nome: String,
+    pub tipo_nome: String,
+    pub prazo_resposta: DateTime<Utc>,
+    pub prazo_resolucao: DateTime<Utc>,
+    pub respondido_em: Option<DateTime<Utc>>,
+    pub resolvido_em: Option<DateTime<Utc>>,
+    pub violado_em: Option<DateTime<Utc>>,
+}
+
+/// Fila de atendimento do serviço (ver chamado::fila)
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Fila {
+    pub id: i64,
+    pub nome: String,
+    pub servico_id: i64,
+    pub servico_nome: Option<String>,
+    /// 0 manual, 1 rodízio, 2 menor carga, 3 habilidade
+    pub estrategia: i32,
+    pub ativo: bool,
+    pub ultimo_user_id: Option<i64>,
+}
+
+/// Atendente que recebe os chamados da fila
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct FilaMembro {
+    pub id: i64,
+    pub fila_id: i64,
+    pub fila_nome: Option<String>,
+    pub user_id: i64,
+    pub user_nome: Option<String>,
+    pub ativo: bool,
+}
+
+/// Tipo de chamado que o atendente domina
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AtendenteHabilidade {
+    pub id: i64,
+    pub user_id: i64,
+    pub user_nome: Option<String>,
+    pub tipo_id: i64,
+    pub tipo_nome: Option<String>,
+}
+
+/// Atendente responsável pelo chamado
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct Atribuicao {
+    pub chamado_id: i64,
+    pub fila_id: Option<i64>,
+    pub user_id: i64,
+    pub user_nome: String,
+    pub atribuido_em: DateTime<Utc>,
+}
+
+/// Atribuição ou transferência registrada em chamado_atribuicao_historico
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct AtribuicaoHistorico {
+    pub id: i64,
+    pub chamado_id: i64,
+    /// vazio na primeira atribuição
+    pub user_de: Option<i64>,
+    pub de_nome: Option<String>,
+    pub user_para: i64,
+    pub para_nome: String,
+    /// vazio na distribuição automática
+    pub por_user_id: Option<i64>,
+    pub por_nome: Option<String>,
+    pub motivo: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Membro ativo com a carga atual, base da distribuição e das opções de transferência
+#[derive(Debug, Serialize, FromRow)]
+pub struct MembroCarga {
+    pub user_id: i64,
+    pub user_nome: String,
+    pub carga: i64,
+    pub habilitado: bool,
+}
+
+/// Linha de "minha fila": atribuídos ao atendente ou aguardando nas filas dele
+#[derive(Debug, Serialize, FromRow)]
+pub struct ChamadoFila {
+    pub id: i64,
+    pub titulo: String,
+    pub status: Option<i32>,
+    pub prioridade: i32,
+    pub created_at: DateTime<Utc>,
+    pub servico_nome: String,
+    pub tipo_nome: String,
+    pub fila_nome: Option<String>,
+    /// vazio quando o chamado aguarda alguém assumir
+    pub atendente_id: Option<i64>,
+    pub atendente_nome: Option<String>,
 }
@@ -0,0 +1,190 @@
+use minijinja::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::core::StatusOpt;
+
+/// Escala do impacto (quantos são afetados) e da urgência (quanto pode esperar)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Nivel {
+    Baixo = 0,
+    Medio = 1,
+    Alto = 2,
+}
+
+impl Nivel {
+    pub const TODOS: [Nivel; 3] = [Nivel::Baixo, Nivel::Medio, Nivel::Alto];
+
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            0 => Nivel::Baixo,
+            2 => Nivel::Alto,
+            _ => Nivel::Medio,
+        }
+    }
+
+    pub fn to_i32(self) -> i32 {
+        self as i32
+    }
+
+    /// para os selects de impacto e urgência
+    pub fn opcoes() -> Vec<StatusOpt> {
+        Self::TODOS
+            .iter()
+            .map(|n| StatusOpt {
+                value: n.to_i32(),
+                label: n.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Nivel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            Nivel::Baixo => "Baixo",
+            Nivel::Medio => "Médio",
+            Nivel::Alto => "Alto",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(i32)]
+pub enum Prioridade {
+    Baixa = 0,
+    Media = 1,
+    Alta = 2,
+    Critica = 3,
+}
+
+impl Prioridade {
+    /*
+    matriz impacto x urgência
+                  urgência baixa   média   alta
+      impacto baixo      Baixa     Baixa   Média
+      impacto médio      Baixa     Média   Alta
+      impacto alto       Média     Alta    Crítica
+    */
+    pub fn da_matriz(impacto: Nivel, urgencia: Nivel) -> Self {
+        match impacto.to_i32() + urgencia.to_i32() {
+            0 | 1 => Prioridade::Baixa,
+            2 => Prioridade::Media,
+            3 => Prioridade::Alta,
+            _ => Prioridade::Critica,
+        }
+    }
+
+    pub fn from_i32(value: i32) -> Self {
+        match value {
+            0 => Prioridade::Baixa,
+            2 => Prioridade::Alta,
+            3 => Prioridade::Critica,
+            _ => Prioridade::Media,
+        }
+    }
+
+    pub fn to_i32(self) -> i32 {
+        self as i32
+    }
+
+    /// Percentual aplicado aos prazos da política de SLA (ver `PoliticaSla::para_prioridade`)
+    pub fn fator_prazo_pct(self) -> i32 {
+        match self {
+            Prioridade::Baixa => 150,
+            Prioridade::Media => 100,
+            Prioridade::Alta => 50,
+            Prioridade::Critica => 25,
+        }
+    }
+
+    /// Classe do badge (daisyUI)
+    pub fn color(self) -> &'static str {
+        match self {
+            Prioridade::Baixa => "badge-ghost",
+            Prioridade::Media => "badge-info",
+            Prioridade::Alta => "badge-warning",
+            Prioridade::Critica => "badge-error",
+        }
+    }
+}
+
+impl std::fmt::Display for Prioridade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            Prioridade::Baixa => "Baixa",
+            Prioridade::Media => "Média",
+            Prioridade::Alta => "Alta",
+            Prioridade::Critica => "Crítica",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/*
+Badge colorido da prioridade do chamado.
+env.add_filter("prioridade_badge", prioridade_badge_filter);
+ */
+pub fn prioridade_badge_filter(value: i32) -> Value {
+    let prioridade = Prioridade::from_i32(value);
+    Value::from_safe_string(format!(
+        r#"<span class="badge {}">{}</span>"#,
+        prioridade.color(),
+        prioridade
+    ))
+}
+
+/*
+Nome do nível de impacto/urgência.
+env.add_filter("nivel_label", nivel_filter);
+ */
+pub fn nivel_filter(value: i32) -> String {
+    Nivel::from_i32(value).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matriz() {
+        assert_eq!(
+            Prioridade::da_matriz(Nivel::Baixo, Nivel::Baixo),
+            Prioridade::Baixa
+        );
+        assert_eq!(
+            Prioridade::da_matriz(Nivel::Baixo, Nivel::Medio),
+            Prioridade::Baixa
+        );
+        assert_eq!(
+            Prioridade::da_matriz(Nivel::Medio, Nivel::Medio),
+            Prioridade::Media
+        );
+        assert_eq!(
+            Prioridade::da_matriz(Nivel::Baixo, Nivel::Alto),
+            Prioridade::Media
+        );
+        assert_eq!(
+            Prioridade::da_matriz(Nivel::Alto, Nivel::Medio),
+            Prioridade::Alta
+        );
+        assert_eq!(
+            Prioridade::da_matriz(Nivel::Alto, Nivel::Alto),
+            Prioridade::Critica
+        );
+    }
+
+    #[test]
+    fn test_badge() {
+        assert_eq!(
+            prioridade_badge_filter(3).to_string(),
+            r#"<span class="badge badge-error">Crítica</span>"#
+        );
+        // valor fora da escala cai em Média, como o padrão da coluna
+        assert_eq!(
+            prioridade_badge_filter(9).to_string(),
+            r#"<span class="badge badge-info">Média</span>"#
+        );
+    }
+}
@@ -3,21 +3,35 @@ use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
 use serde_json::json;
-use shared::Repository;
-use sqlx::PgPool;
+use shared::{Db, Repository};
+use sqlx::PgConnection;
 
 use crate::chamado::model::GerenciamentoChamado;
+use crate::chamado::model::{AnexoComentario, ComentarioChamado, ComentarioThread};
+use crate::chamado::model::StatusHistorico;
+use crate::chamado::model::{BaseSla, ChamadoSla, SlaPolitica, ViolacaoSla};
+use crate::chamado::model::{
+    AtendenteHabilidade, Atribuicao, AtribuicaoHistorico, ChamadoFila, Fila, FilaMembro, MembroCarga,
+};
 use crate::chamado::model::{CategoriaChamado, Chamado, ServicoChamado, TipoChamado};
+use crate::chamado::prioridade::{Nivel, Prioridade};
 use crate::chamado::schema::CreateCategoriaChamadoSchema;
 use crate::chamado::schema::CreateChamado;
+use crate::chamado::schema::CreateAnexoComentario;
+use crate::chamado::schema::CreateAtribuicao;
+use crate::chamado::schema::CreateComentario;
+use crate::chamado::schema::{CreateFilaMembroSchema, CreateFilaSchema, CreateHabilidadeSchema};
 use crate::chamado::schema::CreateGerenciamentoChamado;
 use crate::chamado::schema::CreateServicoChamadoSchema;
+use crate::chamado::schema::CreateSlaPoliticaSchema;
+use crate::chamado::schema::CreateStatusHistorico;
 use crate::chamado::schema::CreateTipoChamadoSchema;
 use crate::chamado::schema::UpdateCategoriaChamadoSchema;
 use crate::chamado::schema::UpdateChamado;
 use crate::chamado::schema::UpdateGerenciamentoChamado;
 use crate::chamado::schema::UpdateServicoChamadoSchema;
 use crate::chamado::schema::UpdateTipoChamadoSchema;
+use crate::chamado::schema::UpsertChamadoSla;
 
 pub struct TipoChamadoRepository;
 
@@ -35,44 +49,52 @@ impl Repository<TipoChamado, i64> for TipoChamadoRepository {
     }
 
     fn select_clause(&self) -> &str {
-        "m.id, m.nome"
+        "m.id, m.nome, m.deleted_at"
     }
 
     fn from_clause(&self) -> &str {
         "chamado_tipos_chamado m"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<TipoChamado> {
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[("id", "m.id"), ("nome", "m.nome"), ("deleted_at", "m.deleted_at")]
+    }
+
+    fn soft_delete_column(&self) -> Option<&str> {
+        Some("m.deleted_at")
+    }
+
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<TipoChamado> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             TipoChamado,
             "INSERT INTO chamado_tipos_chamado (nome) VALUES ($1) RETURNING *",
             input.nome.to_string()
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(
+    async fn update<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         id: i64,
         input: Self::UpdateInput,
     ) -> Result<TipoChamado> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             TipoChamado,
-            r#"UPDATE chamado_tipos_chamado SET nome = $1 WHERE id = $2 RETURNING *"#,
+            r#"UPDATE chamado_tipos_chamado SET nome = $1 WHERE id = $2 AND deleted_at IS NULL RETURNING *"#,
             input.nome,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
-        sqlx::query!("DELETE FROM chamado_tipos_chamado WHERE id = $1", id)
-            .execute(pool)
-            .await?;
-        Ok(())
+    /// vai para a lixeira; `purge` remove de vez
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        self.soft_delete(db, id).await
     }
 }
 
@@ -99,7 +121,16 @@ impl Repository<CategoriaChamado, i64> for CategoriaChamadoRepository {
         "chamado_categoria_chamado m"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<CategoriaChamado> {
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[("id", "m.id"), ("nome", "m.nome")]
+    }
+
+    async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: Self::CreateInput,
+    ) -> Result<CategoriaChamado> {
+        let mut conn = db.acquire().await?;
         let query = format!(
             "INSERT INTO {} (nome) VALUES ($1) RETURNING *",
             self.table_name()
@@ -107,29 +138,31 @@ impl Repository<CategoriaChamado, i64> for CategoriaChamadoRepository {
 
         Ok(sqlx::query_as(&query)
             .bind(input.nome.to_string())
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?)
     }
 
-    async fn update(
+    async fn update<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         id: i64,
         input: Self::UpdateInput,
     ) -> Result<CategoriaChamado> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             CategoriaChamado,
             r#"UPDATE chamado_categoria_chamado SET nome = $1 WHERE id = $2 RETURNING *"#,
             input.nome,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM chamado_categoria_chamado WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -158,7 +191,12 @@ impl Repository<ServicoChamado, i64> for ServicoChamadoRepository {
         "chamado_servico_chamado m"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<ServicoChamado> {
+    async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: Self::CreateInput,
+    ) -> Result<ServicoChamado> {
+        let mut conn = db.acquire().await?;
         let query = format!(
             "INSERT INTO {} (nome, tipo_id) VALUES ($1, $2) RETURNING *",
             self.table_name()
@@ -167,16 +205,17 @@ impl Repository<ServicoChamado, i64> for ServicoChamadoRepository {
         Ok(sqlx::query_as(&query)
             .bind(input.nome.to_string())
             .bind(input.tipo_id)
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?)
     }
 
-    async fn update(
+    async fn update<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         id: i64,
         input: Self::UpdateInput,
     ) -> Result<ServicoChamado> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             ServicoChamado,
             r#"UPDATE chamado_servico_chamado SET nome = $1, tipo_id = $2  WHERE id = $3 RETURNING *"#,
@@ -184,13 +223,14 @@ impl Repository<ServicoChamado, i64> for ServicoChamadoRepository {
             input.tipo_id,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM chamado_servico_chamado WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -208,18 +248,40 @@ impl Repository<Chamado, i64> for ChamadoRepository {
     }
 
     fn searchable_fields(&self) -> &[&str] {
-        &["m.titulo", "m.descricao"]
+        // descricao é JSONB (EditorJS)
+        &["m.titulo", "m.descricao::text"]
+    }
+
+    // mesma expressão do índice idx_chamado_chamados_busca; editorjs_texto extrai o texto do JSONB
+    fn search_vector(&self) -> Option<&str> {
+        Some(
+            "setweight(to_tsvector('portuguese_unaccent', m.titulo), 'A') \
+            || setweight(to_tsvector('portuguese_unaccent', editorjs_texto(m.descricao)), 'B')",
+        )
     }
 
     fn select_clause(&self) -> &str {
-        "m.id, m.titulo, m.descricao, m.status, m.created_at, m.updated_at, m.user_solic_id, m.servico_id, m.tipo_id"
+        "m.id, m.titulo, m.descricao, m.status, m.created_at, m.updated_at, m.user_solic_id, m.servico_id, m.tipo_id, m.impacto, m.urgencia, m.prioridade"
     }
 
     fn from_clause(&self) -> &str {
         "chamado_chamados m"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<Chamado> {
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[
+            ("id", "m.id"),
+            ("titulo", "m.titulo"),
+            ("status", "m.status"),
+            ("prioridade", "m.prioridade"),
+            ("created_at", "m.created_at"),
+        ]
+    }
+
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<Chamado> {
+        let mut conn = db.acquire().await?;
+        let impacto = Nivel::from_i32(input.impacto.unwrap_or(Nivel::Medio.to_i32()));
+        let urgencia = Nivel::from_i32(input.urgencia.unwrap_or(Nivel::Medio.to_i32()));
         // Conteúdo vazio inicial
         let editor_data: Value = json!({
             "time": 0,
@@ -235,10 +297,13 @@ impl Repository<Chamado, i64> for ChamadoRepository {
             user_solic_id,
             servico_id,
             tipo_id,
+            impacto,
+            urgencia,
+            prioridade,
             created_at,
             updated_at
             ) VALUES (
-            $1, $2, $3, $4, $5, $6, NOW(), NOW()) RETURNING *",
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, NOW(), NOW()) RETURNING *",
             self.table_name()
         );
 
@@ -249,11 +314,20 @@ impl Repository<Chamado, i64> for ChamadoRepository {
             .bind(input.user_solic_id)
             .bind(input.servico_id)
             .bind(input.tipo_id)
-            .fetch_one(pool)
+            .bind(impacto.to_i32())
+            .bind(urgencia.to_i32())
+            .bind(Prioridade::da_matriz(impacto, urgencia).to_i32())
+            .fetch_one(&mut *conn)
             .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i64, input: Self::UpdateInput) -> Result<Chamado> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: Self::UpdateInput,
+    ) -> Result<Chamado> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Chamado,
             r#"UPDATE chamado_chamados SET titulo = $1, descricao = $2, servico_id = $3, tipo_id = $4, updated_at = NOW() WHERE id = $5 RETURNING *"#,
@@ -263,18 +337,88 @@ impl Repository<Chamado, i64> for ChamadoRepository {
             input.tipo_id,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM chamado_chamados WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
 }
 
+impl ChamadoRepository {
+    /// Status atual com a linha travada até o fim da transação (ver `ChamadoService::transicionar`)
+    pub async fn get_status_for_update(&self, conn: &mut PgConnection, id: i64) -> Result<i32> {
+        let row = sqlx::query!(
+            "SELECT status FROM chamado_chamados WHERE id = $1 FOR UPDATE",
+            id
+        )
+        .fetch_one(conn)
+        .await?;
+
+        Ok(row.status)
+    }
+
+    /// Altera somente o status do chamado (dentro de uma UnitOfWork, depois de validar a transição)
+    pub async fn update_status(
+        &self,
+        conn: &mut PgConnection,
+        id: i64,
+        status: i32,
+    ) -> Result<Chamado> {
+        Ok(sqlx::query_as!(
+            Chamado,
+            r#"
+            UPDATE chamado_chamados
+            SET
+                status = $1,
+                updated_at = NOW()
+            WHERE id = $2
+            RETURNING id, titulo, descricao, status, created_at, updated_at, user_solic_id, servico_id, tipo_id,
+                impacto, urgencia, prioridade
+            "#,
+            status,
+            id
+        )
+        .fetch_one(conn)
+        .await?)
+    }
+
+    /// Reavaliação de impacto e urgência por quem atende; a prioridade sai da matriz
+    pub async fn update_prioridade(
+        &self,
+        conn: &mut PgConnection,
+        id: i64,
+        impacto: Nivel,
+        urgencia: Nivel,
+    ) -> Result<Chamado> {
+        Ok(sqlx::query_as!(
+            Chamado,
+            r#"
+            UPDATE chamado_chamados
+            SET
+                impacto = $1,
+                urgencia = $2,
+                prioridade = $3,
+                updated_at = NOW()
+            WHERE id = $4
+            RETURNING id, titulo, descricao, status, created_at, updated_at, user_solic_id, servico_id, tipo_id,
+                impacto, urgencia, prioridade
+            "#,
+            impacto.to_i32(),
+            urgencia.to_i32(),
+            Prioridade::da_matriz(impacto, urgencia).to_i32(),
+            id
+        )
+        .fetch_one(conn)
+        .await?)
+    }
+}
+
 pub struct GerenciamentoChamadoRepository;
 
 #[async_trait]
@@ -291,18 +435,19 @@ impl Repository<GerenciamentoChamado, i64> for GerenciamentoChamadoRepository {
     }
 
     fn select_clause(&self) -> &str {
-        "m.id, m.descricao, m.categoria_id, m.chamado_id, m.created_at, m.updated_at, m.user_atend_id, m.observacao_chamado"
+        "m.id, m.descricao, m.categoria_id, m.chamado_id, m.created_at, m.updated_at, m.user_atend_id, m.observacao_chamado, m.updated_by"
     }
 
     fn from_clause(&self) -> &str {
         "chamado_gerenciamento_chamado m"
     }
 
-    async fn create(
+    async fn create<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         input: Self::CreateInput,
     ) -> Result<GerenciamentoChamado> {
+        let mut conn = db.acquire().await?;
         let query = format!(
             "INSERT INTO {} 
             (
@@ -324,27 +469,946 @@ impl Repository<GerenciamentoChamado, i64> for GerenciamentoChamadoRepository {
             .bind(input.chamado_id)
             .bind(input.user_atend_id)
             .bind(input.observacao_chamado)
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?)
     }
 
-    async fn update(
+    async fn update<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         id: i64,
         input: Self::UpdateInput,
     ) -> Result<GerenciamentoChamado> {
-        //nao implementado somente no service
-        todo!()
+        // somente se ainda estiver na versão lida pelo formulário (input.updated_at)
+        let mut conn = db.acquire().await?;
+        let gerenciamento = sqlx::query_as!(
+            GerenciamentoChamado,
+            r#"
+            UPDATE chamado_gerenciamento_chamado
+            SET
+                descricao = COALESCE($1, descricao),
+                categoria_id = $2,
+                observacao_chamado = COALESCE($3, observacao_chamado),
+                updated_by = $4,
+                updated_at = NOW()
+            WHERE id = $5 AND updated_at = $6
+            RETURNING *"#,
+            input.descricao,
+            input.categoria_id,
+            input.observacao_chamado,
+            input.updated_by,
+            id,
+            input.updated_at
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match gerenciamento {
+            Some(gerenciamento) => Ok(gerenciamento),
+            None => Err(self.stale_update(&mut *conn, id).await),
+        }
     }
 
-    async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!(
             "DELETE FROM chamado_gerenciamento_chamado WHERE id = $1",
             id
         )
-        .execute(pool)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+}
+
+impl GerenciamentoChamadoRepository {
+    /// Passa o atendimento já iniciado para o novo responsável (transferência)
+    pub async fn transferir(
+        &self,
+        conn: &mut PgConnection,
+        chamado_id: i64,
+        user_atend_id: i64,
+        updated_by: i64,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE chamado_gerenciamento_chamado
+            SET user_atend_id = $1, updated_by = $2
+            WHERE chamado_id = $3"#,
+            user_atend_id,
+            updated_by,
+            chamado_id
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+}
+
+pub struct ComentarioChamadoRepository;
+
+#[async_trait]
+impl Repository<ComentarioChamado, i64> for ComentarioChamadoRepository {
+    type CreateInput = CreateComentario;
+    type UpdateInput = CreateComentario;
+
+    fn table_name(&self) -> &str {
+        "chamado_comentario"
+    }
+
+    fn searchable_fields(&self) -> &[&str] {
+        &["m.corpo::text"]
+    }
+
+    fn select_clause(&self) -> &str {
+        "m.id, m.chamado_id, m.autor_id, m.corpo, m.interno, m.created_at, m.updated_at"
+    }
+
+    fn from_clause(&self) -> &str {
+        "chamado_comentario m"
+    }
+
+    async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: Self::CreateInput,
+    ) -> Result<ComentarioChamado> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            ComentarioChamado,
+            r#"
+            INSERT INTO chamado_comentario (chamado_id, autor_id, corpo, interno)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *"#,
+            input.chamado_id,
+            input.autor_id,
+            input.corpo,
+            input.interno
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: Self::UpdateInput,
+    ) -> Result<ComentarioChamado> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            ComentarioChamado,
+            r#"UPDATE chamado_comentario SET corpo = $1, interno = $2 WHERE id = $3 RETURNING *"#,
+            input.corpo,
+            input.interno,
+            id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
+        sqlx::query!("DELETE FROM chamado_comentario WHERE id = $1", id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
+
+impl ComentarioChamadoRepository {
+    /// Conversa do chamado em ordem cronológica, com o nome do autor (sem os anexos)
+    pub async fn list_by_chamado(
+        &self,
+        conn: &mut PgConnection,
+        chamado_id: i64,
+        incluir_internos: bool,
+    ) -> Result<Vec<ComentarioThread>> {
+        let query = format!(
+            "SELECT {}, u.full_name AS autor_nome
+            FROM {} JOIN users u ON u.id = m.autor_id
+            WHERE m.chamado_id = $1 AND ($2 OR NOT m.interno)
+            ORDER BY m.created_at, m.id",
+            self.select_clause(),
+            self.from_clause(),
+        );
+
+        Ok(sqlx::query_as(&query)
+            .bind(chamado_id)
+            .bind(incluir_internos)
+            .fetch_all(conn)
+            .await?)
+    }
+
+    pub async fn list_anexos(
+        &self,
+        conn: &mut PgConnection,
+        comentario_ids: &[i64],
+    ) -> Result<Vec<AnexoComentario>> {
+        Ok(sqlx::query_as!(
+            AnexoComentario,
+            r#"
+            SELECT id, comentario_id, nome, url, tamanho, content_type, created_at
+            FROM chamado_comentario_anexo
+            WHERE comentario_id = ANY($1)
+            ORDER BY id"#,
+            comentario_ids
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+
+    pub async fn create_anexo(
+        &self,
+        conn: &mut PgConnection,
+        comentario_id: i64,
+        anexo: &CreateAnexoComentario,
+    ) -> Result<AnexoComentario> {
+        Ok(sqlx::query_as!(
+            AnexoComentario,
+            r#"
+            INSERT INTO chamado_comentario_anexo (comentario_id, nome, url, tamanho, content_type)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *"#,
+            comentario_id,
+            anexo.nome,
+            anexo.url,
+            anexo.tamanho,
+            anexo.content_type
+        )
+        .fetch_one(conn)
+        .await?)
+    }
+}
+
+/// Histórico de status: somente inclusão, gravado por `ChamadoService::transicionar`
+pub struct StatusHistoricoRepository;
+
+impl StatusHistoricoRepository {
+    pub async fn create(&self, conn: &mut PgConnection, input: CreateStatusHistorico) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO chamado_status_historico (chamado_id, status_de, status_para, user_id, motivo)
+            VALUES ($1, $2, $3, $4, $5)"#,
+            input.chamado_id,
+            input.status_de,
+            input.status_para,
+            input.user_id,
+            input.motivo
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Linha do tempo do chamado, da abertura até o status atual
+    pub async fn list_by_chamado(
+        &self,
+        conn: &mut PgConnection,
+        chamado_id: i64,
+    ) -> Result<Vec<StatusHistorico>> {
+        Ok(sqlx::query_as!(
+            StatusHistorico,
+            r#"
+            SELECT
+                h.id, h.chamado_id, h.status_de, h.status_para, h.user_id,
+                u.full_name AS "user_nome?", h.motivo, h.created_at, t.segundos,
+                NULL::BIGINT AS "segundos_uteis?"
+            FROM chamado_status_historico h
+            JOIN chamado_tempo_status t ON t.historico_id = h.id
+            LEFT JOIN users u ON u.id = h.user_id
+            WHERE h.chamado_id = $1
+            ORDER BY h.created_at, h.id"#,
+            chamado_id
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+}
+
+pub struct SlaPoliticaRepository;
+
+#[async_trait]
+impl Repository<SlaPolitica, i64> for SlaPoliticaRepository {
+    type CreateInput = CreateSlaPoliticaSchema;
+    type UpdateInput = CreateSlaPoliticaSchema;
+
+    fn table_name(&self) -> &str {
+        "chamado_sla_politica"
+    }
+
+    fn searchable_fields(&self) -> &[&str] {
+        &["s.nome", "t.nome"]
+    }
+
+    fn select_clause(&self) -> &str {
+        "p.id, p.servico_id, s.nome AS servico_nome, p.tipo_id, t.nome AS tipo_nome,
+        p.prazo_resposta_min, p.prazo_resolucao_min, p.alerta_pct"
+    }
+
+    fn from_clause(&self) -> &str {
+        "chamado_sla_politica p
+        LEFT JOIN chamado_servico_chamado s ON s.id = p.servico_id
+        LEFT JOIN chamado_tipos_chamado t ON t.id = p.tipo_id
+        "
+    }
+
+    fn id_column(&self) -> &str {
+        "p.id"
+    }
+
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[
+            ("id", "p.id"),
+            ("servico_nome", "s.nome"),
+            ("tipo_nome", "t.nome"),
+            ("prazo_resposta_min", "p.prazo_resposta_min"),
+            ("prazo_resolucao_min", "p.prazo_resolucao_min"),
+        ]
+    }
+
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<SlaPolitica> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            SlaPolitica,
+            r#"
+            INSERT INTO chamado_sla_politica
+                (servico_id, tipo_id, prazo_resposta_min, prazo_resolucao_min, alerta_pct)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, servico_id, NULL as "servico_nome?", tipo_id, NULL as "tipo_nome?",
+                prazo_resposta_min, prazo_resolucao_min, alerta_pct
+            "#,
+            input.servico_id,
+            input.tipo_id,
+            input.prazo_resposta_min,
+            input.prazo_resolucao_min,
+            input.alerta_pct
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: Self::UpdateInput,
+    ) -> Result<SlaPolitica> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            SlaPolitica,
+            r#"
+            UPDATE chamado_sla_politica
+            SET
+                servico_id = $1,
+                tipo_id = $2,
+                prazo_resposta_min = $3,
+                prazo_resolucao_min = $4,
+                alerta_pct = $5
+            WHERE id = $6
+            RETURNING id, servico_id, NULL as "servico_nome?", tipo_id, NULL as "tipo_nome?",
+                prazo_resposta_min, prazo_resolucao_min, alerta_pct
+            "#,
+            input.servico_id,
+            input.tipo_id,
+            input.prazo_resposta_min,
+            input.prazo_resolucao_min,
+            input.alerta_pct,
+            id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
+        sqlx::query!("DELETE FROM chamado_sla_politica WHERE id = $1", id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Prazos calculados de cada chamado; gravado por `SlaService`
+pub struct ChamadoSlaRepository;
+
+impl ChamadoSlaRepository {
+    /*
+    chamados a (re)calcular com a política aplicável: a do serviço ou, na falta, a do tipo
+    sem `chamado_id`, todos os que ainda correm (nem Resolvido nem Fechado)
+    e os que nunca foram calculados
+    */
+    pub async fn pendentes(
+        &self,
+        conn: &mut PgConnection,
+        chamado_id: Option<i64>,
+    ) -> Result<Vec<BaseSla>> {
+        Ok(sqlx::query_as!(
+            BaseSla,
+            r#"
+            SELECT
+                c.id AS chamado_id,
+                p.id AS "politica_id!",
+                p.prazo_resposta_min AS "prazo_resposta_min!",
+                p.prazo_resolucao_min AS "prazo_resolucao_min!",
+                p.alerta_pct AS "alerta_pct!",
+                c.prioridade,
+                c.created_at AS aberto_em,
+                (SELECT MIN(h.created_at) FROM chamado_status_historico h
+                    WHERE h.chamado_id = c.id AND h.status_para = 1) AS respondido_em,
+                CASE WHEN c.status IN (3, 4) THEN COALESCE(
+                    (SELECT MAX(h.created_at) FROM chamado_status_historico h
+                        WHERE h.chamado_id = c.id AND h.status_para = 3),
+                    (SELECT MAX(h.created_at) FROM chamado_status_historico h
+                        WHERE h.chamado_id = c.id AND h.status_para = 4))
+                END AS resolvido_em,
+                ARRAY(SELECT t.inicio FROM chamado_tempo_status t
+                    WHERE t.chamado_id = c.id AND t.status = 2 ORDER BY t.inicio) AS "pausas_inicio!",
+                ARRAY(SELECT COALESCE(t.fim, NOW()) FROM chamado_tempo_status t
+                    WHERE t.chamado_id = c.id AND t.status = 2 ORDER BY t.inicio) AS "pausas_fim!"
+            FROM chamado_chamados c
+            JOIN LATERAL (
+                SELECT * FROM chamado_sla_politica
+                WHERE servico_id = c.servico_id OR tipo_id = c.tipo_id
+                ORDER BY servico_id IS NULL
+                LIMIT 1
+            ) p ON TRUE
+            LEFT JOIN chamado_sla s ON s.chamado_id = c.id
+            WHERE ($1::BIGINT IS NULL AND (COALESCE(c.status, 0) NOT IN (3, 4) OR s.chamado_id IS NULL))
+                OR c.id = $1
+            "#,
+            chamado_id
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+
+    /// Grava o cálculo; devolve true quando a violação foi vista agora pela primeira vez
+    pub async fn upsert(&self, conn: &mut PgConnection, input: UpsertChamadoSla) -> Result<bool> {
+        Ok(sqlx::query_scalar!(
+            r#"
+            INSERT INTO chamado_sla (chamado_id, politica_id, prazo_resposta, prazo_resolucao,
+                respondido_em, resolvido_em, situacao, violado_em, calculado_em)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, CASE WHEN $7 = 2 THEN NOW() END, NOW())
+            ON CONFLICT (chamado_id) DO UPDATE SET
+                politica_id = EXCLUDED.politica_id,
+                prazo_resposta = EXCLUDED.prazo_resposta,
+                prazo_resolucao = EXCLUDED.prazo_resolucao,
+                respondido_em = EXCLUDED.respondido_em,
+                resolvido_em = EXCLUDED.resolvido_em,
+                situacao = EXCLUDED.situacao,
+                violado_em = CASE WHEN EXCLUDED.situacao = 2
+                    THEN COALESCE(chamado_sla.violado_em, NOW()) END,
+                calculado_em = NOW()
+            RETURNING violado_em IS NOT NULL AND violado_em = calculado_em AS "nova_violacao!"
+            "#,
+            input.chamado_id,
+            input.politica_id,
+            input.prazo_resposta,
+            input.prazo_resolucao,
+            input.respondido_em,
+            input.resolvido_em,
+            input.situacao
+        )
+        .fetch_one(conn)
+        .await?)
+    }
+
+    pub async fn get_by_chamado(
+        &self,
+        conn: &mut PgConnection,
+        chamado_id: i64,
+    ) -> Result<Option<ChamadoSla>> {
+        Ok(sqlx::query_as!(
+            ChamadoSla,
+            r#"
+            SELECT chamado_id, prazo_resposta, prazo_resolucao, respondido_em, resolvido_em,
+                situacao, violado_em
+            FROM chamado_sla
+            WHERE chamado_id = $1"#,
+            chamado_id
+        )
+        .fetch_optional(conn)
+        .await?)
+    }
+
+    /// Situação dos chamados de uma página da listagem
+    pub async fn list_by_chamados(
+        &self,
+        conn: &mut PgConnection,
+        ids: &[i64],
+    ) -> Result<Vec<ChamadoSla>> {
+        Ok(sqlx::query_as!(
+            ChamadoSla,
+            r#"
+            SELECT chamado_id, prazo_resposta, prazo_resolucao, respondido_em, resolvido_em,
+                situacao, violado_em
+            FROM chamado_sla
+            WHERE chamado_id = ANY($1)"#,
+            ids
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+
+    /// Relatório de violações, da mais recente para a mais antiga
+    pub async fn violacoes(
+        &self,
+        conn: &mut PgConnection,
+        page: i32,
+        page_size: i32,
+    ) -> Result<(Vec<ViolacaoSla>, i64)> {
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "total!" FROM chamado_sla WHERE situacao = 2"#
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let linhas = sqlx::query_as!(
+            ViolacaoSla,
+            r#"
+            SELECT
+                s.chamado_id, c.titulo, c.status, sv.nome AS servico_nome, t.nome AS tipo_nome,
+                s.prazo_resposta, s.prazo_resolucao, s.respondido_em, s.resolvido_em, s.violado_em
+            FROM chamado_sla s
+            JOIN chamado_chamados c ON c.id = s.chamado_id
+            JOIN chamado_servico_chamado sv ON sv.id = c.servico_id
+            JOIN chamado_tipos_chamado t ON t.id = c.tipo_id
+            WHERE s.situacao = 2
+            ORDER BY s.violado_em DESC NULLS LAST, s.chamado_id DESC
+            LIMIT $1 OFFSET $2"#,
+            page_size as i64,
+            ((page - 1) * page_size) as i64
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok((linhas, total))
+    }
+}
+
+pub struct FilaRepository;
+
+#[async_trait]
+impl Repository<Fila, i64> for FilaRepository {
+    type CreateInput = CreateFilaSchema;
+    type UpdateInput = CreateFilaSchema;
+
+    fn table_name(&self) -> &str {
+        "chamado_fila"
+    }
+
+    fn searchable_fields(&self) -> &[&str] {
+        &["f.nome", "s.nome"]
+    }
+
+    fn select_clause(&self) -> &str {
+        "f.id, f.nome, f.servico_id, s.nome AS servico_nome, f.estrategia, f.ativo, f.ultimo_user_id"
+    }
+
+    fn from_clause(&self) -> &str {
+        "chamado_fila f
+        LEFT JOIN chamado_servico_chamado s ON s.id = f.servico_id
+        "
+    }
+
+    fn id_column(&self) -> &str {
+        "f.id"
+    }
+
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[("id", "f.id"), ("nome", "f.nome"), ("servico_nome", "s.nome")]
+    }
+
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<Fila> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            Fila,
+            r#"
+            INSERT INTO chamado_fila (nome, servico_id, estrategia, ativo)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, nome, servico_id, NULL as "servico_nome?", estrategia, ativo, ultimo_user_id
+            "#,
+            input.nome,
+            input.servico_id,
+            input.estrategia,
+            input.ativo
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn update<'c, A: Db<'c>>(&self, db: A, id: i64, input: Self::UpdateInput) -> Result<Fila> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            Fila,
+            r#"
+            UPDATE chamado_fila
+            SET nome = $1, servico_id = $2, estrategia = $3, ativo = $4
+            WHERE id = $5
+            RETURNING id, nome, servico_id, NULL as "servico_nome?", estrategia, ativo, ultimo_user_id
+            "#,
+            input.nome,
+            input.servico_id,
+            input.estrategia,
+            input.ativo,
+            id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
+        sqlx::query!("DELETE FROM chamado_fila WHERE id = $1", id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
+
+pub struct FilaMembroRepository;
+
+#[async_trait]
+impl Repository<FilaMembro, i64> for FilaMembroRepository {
+    type CreateInput = CreateFilaMembroSchema;
+    type UpdateInput = CreateFilaMembroSchema;
+
+    fn table_name(&self) -> &str {
+        "chamado_fila_membro"
+    }
+
+    fn searchable_fields(&self) -> &[&str] {
+        &["f.nome", "u.full_name"]
+    }
+
+    fn select_clause(&self) -> &str {
+        "m.id, m.fila_id, f.nome AS fila_nome, m.user_id, u.full_name AS user_nome, m.ativo"
+    }
+
+    fn from_clause(&self) -> &str {
+        "chamado_fila_membro m
+        LEFT JOIN chamado_fila f ON f.id = m.fila_id
+        LEFT JOIN users u ON u.id = m.user_id
+        "
+    }
+
+    fn id_column(&self) -> &str {
+        "m.id"
+    }
+
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[("id", "m.id"), ("fila_nome", "f.nome"), ("user_nome", "u.full_name")]
+    }
+
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<FilaMembro> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            FilaMembro,
+            r#"
+            INSERT INTO chamado_fila_membro (fila_id, user_id, ativo)
+            VALUES ($1, $2, $3)
+            RETURNING id, fila_id, NULL as "fila_nome?", user_id, NULL as "user_nome?", ativo
+            "#,
+            input.fila_id,
+            input.user_id,
+            input.ativo
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: Self::UpdateInput,
+    ) -> Result<FilaMembro> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            FilaMembro,
+            r#"
+            UPDATE chamado_fila_membro
+            SET fila_id = $1, user_id = $2, ativo = $3
+            WHERE id = $4
+            RETURNING id, fila_id, NULL as "fila_nome?", user_id, NULL as "user_nome?", ativo
+            "#,
+            input.fila_id,
+            input.user_id,
+            input.ativo,
+            id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
+        sqlx::query!("DELETE FROM chamado_fila_membro WHERE id = $1", id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
+
+pub struct HabilidadeRepository;
+
+#[async_trait]
+impl Repository<AtendenteHabilidade, i64> for HabilidadeRepository {
+    type CreateInput = CreateHabilidadeSchema;
+    type UpdateInput = CreateHabilidadeSchema;
+
+    fn table_name(&self) -> &str {
+        "chamado_atendente_habilidade"
+    }
+
+    fn searchable_fields(&self) -> &[&str] {
+        &["u.full_name", "t.nome"]
+    }
+
+    fn select_clause(&self) -> &str {
+        "h.id, h.user_id, u.full_name AS user_nome, h.tipo_id, t.nome AS tipo_nome"
+    }
+
+    fn from_clause(&self) -> &str {
+        "chamado_atendente_habilidade h
+        LEFT JOIN users u ON u.id = h.user_id
+        LEFT JOIN chamado_tipos_chamado t ON t.id = h.tipo_id
+        "
+    }
+
+    fn id_column(&self) -> &str {
+        "h.id"
+    }
+
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[("id", "h.id"), ("user_nome", "u.full_name"), ("tipo_nome", "t.nome")]
+    }
+
+    async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: Self::CreateInput,
+    ) -> Result<AtendenteHabilidade> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            AtendenteHabilidade,
+            r#"
+            INSERT INTO chamado_atendente_habilidade (user_id, tipo_id)
+            VALUES ($1, $2)
+            RETURNING id, user_id, NULL as "user_nome?", tipo_id, NULL as "tipo_nome?"
+            "#,
+            input.user_id,
+            input.tipo_id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: Self::UpdateInput,
+    ) -> Result<AtendenteHabilidade> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            AtendenteHabilidade,
+            r#"
+            UPDATE chamado_atendente_habilidade
+            SET user_id = $1, tipo_id = $2
+            WHERE id = $3
+            RETURNING id, user_id, NULL as "user_nome?", tipo_id, NULL as "tipo_nome?"
+            "#,
+            input.user_id,
+            input.tipo_id,
+            id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
+        sqlx::query!("DELETE FROM chamado_atendente_habilidade WHERE id = $1", id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Responsável pelo chamado e histórico de atribuições; gravado por `FilaService`
+pub struct AtribuicaoRepository;
+
+impl AtribuicaoRepository {
+    /// Fila ativa do serviço, travada para que o rodízio não escolha duas vezes o mesmo
+    pub async fn fila_do_servico_for_update(
+        &self,
+        conn: &mut PgConnection,
+        servico_id: i64,
+    ) -> Result<Option<Fila>> {
+        Ok(sqlx::query_as!(
+            Fila,
+            r#"
+            SELECT id, nome, servico_id, NULL as "servico_nome?", estrategia, ativo, ultimo_user_id
+            FROM chamado_fila
+            WHERE servico_id = $1 AND ativo
+            FOR UPDATE"#,
+            servico_id
+        )
+        .fetch_optional(conn)
+        .await?)
+    }
+
+    pub async fn fila_id_do_servico(&self, conn: &mut PgConnection, servico_id: i64) -> Result<Option<i64>> {
+        Ok(sqlx::query_scalar!(
+            "SELECT id FROM chamado_fila WHERE servico_id = $1 AND ativo",
+            servico_id
+        )
+        .fetch_optional(conn)
+        .await?)
+    }
+
+    /// Membros ativos com os chamados em aberto de cada um e se dominam `tipo_id`
+    pub async fn membros(
+        &self,
+        conn: &mut PgConnection,
+        fila_id: i64,
+        tipo_id: i64,
+    ) -> Result<Vec<MembroCarga>> {
+        Ok(sqlx::query_as!(
+            MembroCarga,
+            r#"
+            SELECT
+                m.user_id, u.full_name AS user_nome,
+                (SELECT COUNT(*) FROM chamado_atribuicao a
+                    JOIN chamado_chamados c ON c.id = a.chamado_id
+                    WHERE a.user_id = m.user_id AND COALESCE(c.status, 0) NOT IN (3, 4)) AS "carga!",
+                EXISTS(SELECT 1 FROM chamado_atendente_habilidade h
+                    WHERE h.user_id = m.user_id AND h.tipo_id = $2) AS "habilitado!"
+            FROM chamado_fila_membro m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.fila_id = $1 AND m.ativo AND u.is_active
+            ORDER BY u.full_name"#,
+            fila_id,
+            tipo_id
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+
+    pub async fn set_ultimo(&self, conn: &mut PgConnection, fila_id: i64, user_id: i64) -> Result<()> {
+        sqlx::query!(
+            "UPDATE chamado_fila SET ultimo_user_id = $1 WHERE id = $2",
+            user_id,
+            fila_id
+        )
+        .execute(conn)
         .await?;
         Ok(())
     }
+
+    pub async fn get_by_chamado(
+        &self,
+        conn: &mut PgConnection,
+        chamado_id: i64,
+    ) -> Result<Option<Atribuicao>> {
+        Ok(sqlx::query_as!(
+            Atribuicao,
+            r#"
+            SELECT a.chamado_id, a.fila_id, a.user_id, u.full_name AS user_nome, a.atribuido_em
+            FROM chamado_atribuicao a
+            JOIN users u ON u.id = a.user_id
+            WHERE a.chamado_id = $1"#,
+            chamado_id
+        )
+        .fetch_optional(conn)
+        .await?)
+    }
+
+    /// Define o responsável e registra a mudança no histórico
+    pub async fn atribuir(&self, conn: &mut PgConnection, input: CreateAtribuicao) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO chamado_atribuicao (chamado_id, fila_id, user_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (chamado_id) DO UPDATE SET
+                fila_id = COALESCE(EXCLUDED.fila_id, chamado_atribuicao.fila_id),
+                user_id = EXCLUDED.user_id,
+                atribuido_em = NOW()"#,
+            input.chamado_id,
+            input.fila_id,
+            input.user_para
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO chamado_atribuicao_historico (chamado_id, user_de, user_para, por_user_id, motivo)
+            VALUES ($1, $2, $3, $4, $5)"#,
+            input.chamado_id,
+            input.user_de,
+            input.user_para,
+            input.por_user_id,
+            input.motivo
+        )
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn historico(
+        &self,
+        conn: &mut PgConnection,
+        chamado_id: i64,
+    ) -> Result<Vec<AtribuicaoHistorico>> {
+        Ok(sqlx::query_as!(
+            AtribuicaoHistorico,
+            r#"
+            SELECT
+                h.id, h.chamado_id, h.user_de, de.full_name AS "de_nome?",
+                h.user_para, para.full_name AS para_nome,
+                h.por_user_id, por.full_name AS "por_nome?", h.motivo, h.created_at
+            FROM chamado_atribuicao_historico h
+            JOIN users para ON para.id = h.user_para
+            LEFT JOIN users de ON de.id = h.user_de
+            LEFT JOIN users por ON por.id = h.por_user_id
+            WHERE h.chamado_id = $1
+            ORDER BY h.created_at, h.id"#,
+            chamado_id
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+
+    /*
+    chamados em aberto atribuídos ao atendente
+    e os que aguardam, sem responsável, nas filas de que ele é membro ativo
+    */
+    pub async fn minha_fila(&self, conn: &mut PgConnection, user_id: i64) -> Result<Vec<ChamadoFila>> {
+        Ok(sqlx::query_as!(
+            ChamadoFila,
+            r#"
+            SELECT
+                c.id, c.titulo, c.status, c.prioridade, c.created_at,
+                s.nome AS servico_nome, t.nome AS tipo_nome, f.nome AS "fila_nome?",
+                a.user_id AS "atendente_id?", u.full_name AS "atendente_nome?"
+            FROM chamado_chamados c
+            JOIN chamado_servico_chamado s ON s.id = c.servico_id
+            JOIN chamado_tipos_chamado t ON t.id = c.tipo_id
+            LEFT JOIN chamado_fila f ON f.servico_id = c.servico_id
+            LEFT JOIN chamado_atribuicao a ON a.chamado_id = c.id
+            LEFT JOIN users u ON u.id = a.user_id
+            WHERE COALESCE(c.status, 0) NOT IN (3, 4)
+                AND (a.user_id = $1
+                    OR (a.chamado_id IS NULL AND EXISTS(
+                        SELECT 1 FROM chamado_fila_membro m
+                        WHERE m.fila_id = f.id AND m.user_id = $1 AND m.ativo)))
+            ORDER BY a.user_id IS NULL, c.prioridade DESC, c.created_at"#,
+            user_id
+        )
+        .fetch_all(conn)
+        .await?)
+    }
 }
@@ -1,11 +1,51 @@
 use axum::{
-    Router, middleware,
+    Router,
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{delete, get, post},
 };
 
 use shared::SharedState;
+use shared::admin::AdminResource;
+use shared::generic_list::{FormField, ListConfig};
+use utoipa::OpenApi;
 
-use crate::{chamado::view, middlewares};
+use crate::chamado::repository::{
+    CategoriaChamadoRepository, FilaMembroRepository, FilaRepository, HabilidadeRepository,
+    SlaPoliticaRepository, TipoChamadoRepository,
+};
+use crate::{
+    chamado::{anexo, api, fila::Estrategia, view},
+    middlewares,
+};
+
+/// Rotas JSON do módulo, relativas a `/chamado` (ver `crate::openapi`)
+#[derive(OpenApi)]
+#[openapi(paths(
+    view::tipo_list_api,
+    view::categoria_list_api,
+    view::servico_list_api,
+))]
+pub struct ApiDoc;
+
+/// Rotas da API v1, relativas a `/api/v1` (ver `crate::api`)
+#[derive(OpenApi)]
+#[openapi(paths(
+    api::listar_chamados,
+    api::obter_chamado,
+    api::criar_chamado,
+    api::atualizar_chamado,
+    api::excluir_chamado,
+    api::alterar_status,
+    api::listar_historico,
+    api::obter_atendimento,
+    api::iniciar_atendimento,
+    api::atualizar_atendimento,
+    api::transferir_chamado,
+    api::listar_comentarios,
+    api::criar_comentario,
+))]
+pub struct ApiV1Doc;
 
 pub fn router() -> Router<SharedState> {
     Router::new()
@@ -18,21 +58,29 @@ pub fn router() -> Router<SharedState> {
             ]))),
         )
         .merge(router_chamado_atendimento())
+        .merge(
+            router_sla().layer(middleware::from_fn(middlewares::require_roles(vec![
+                "chamado_admin",
+            ]))),
+        )
+        .merge(
+            router_fila().layer(middleware::from_fn(middlewares::require_roles(vec![
+                "chamado_admin",
+            ]))),
+        )
 }
 
 fn router_tipo() -> Router<SharedState> {
-    Router::new()
-        .route("/tipo", get(view::list_tipo_chamado))
-        .route(
-            "/tipo-form",
-            get(view::show_tipo_form).post(view::create_tipo),
-        )
-        .route(
-            "/tipo-form/{id}",
-            get(view::get_tipo).post(view::update_tipo),
-        )
-        .route("/tipo/{id}", delete(view::delete_tipo))
-        .merge(api_tipo_router())
+    AdminResource::new(
+        TipoChamadoRepository,
+        ListConfig::new("tipo", "Tipo", "Tipos", "/chamado/tipo")
+            .field("id", "ID")
+            .field("nome", "Nome")
+            .searchable(&["nome"]),
+        vec![FormField::text("nome", "Nome").required().placeholder("Digite o nome")],
+    )
+    .router()
+    .merge(api_tipo_router())
 }
 
 fn api_tipo_router() -> Router<SharedState> {
@@ -40,18 +88,16 @@ fn api_tipo_router() -> Router<SharedState> {
 }
 
 fn router_categoria() -> Router<SharedState> {
-    Router::new()
-        .route("/categoria", get(view::list_categoria))
-        .route(
-            "/categoria-form",
-            get(view::show_categoria_form).post(view::create_categoria),
-        )
-        .route(
-            "/categoria-form/{id}",
-            get(view::get_categoria).post(view::update_categoria),
-        )
-        .route("/categoria/{id}", delete(view::delete_categoria))
-        .merge(api_categoria_router())
+    AdminResource::new(
+        CategoriaChamadoRepository,
+        ListConfig::new("categoria", "Categoria", "Categorias", "/chamado/categoria")
+            .field("id", "ID")
+            .field("nome", "Nome")
+            .searchable(&["nome"]),
+        vec![FormField::text("nome", "Nome").required().placeholder("Digite o nome")],
+    )
+    .router()
+    .merge(api_categoria_router())
 }
 
 fn api_categoria_router() -> Router<SharedState> {
@@ -77,9 +123,100 @@ fn api_servico_router() -> Router<SharedState> {
     Router::new().route("/servico-api", get(view::servico_list_api))
 }
 
+/// Opções dos selects dos formulários do admin (ver `FormField::select_query`)
+const SERVICOS: &str = "SELECT id, nome::text FROM chamado_servico_chamado ORDER BY nome";
+const TIPOS: &str = "SELECT id, nome::text FROM chamado_tipos_chamado WHERE deleted_at IS NULL ORDER BY nome";
+const FILAS: &str = "SELECT id, nome::text FROM chamado_fila ORDER BY nome";
+const USUARIOS: &str = "SELECT id, full_name::text FROM users WHERE is_active ORDER BY full_name";
+
+/// Políticas de SLA (serviço ou, como padrão, tipo) e o relatório de violações
+fn router_sla() -> Router<SharedState> {
+    AdminResource::new(
+        SlaPoliticaRepository,
+        ListConfig::new("sla", "Política de SLA", "Políticas de SLA", "/chamado/sla")
+            .field("id", "ID")
+            .field("servico_nome", "Serviço")
+            .field("tipo_nome", "Tipo")
+            .field("prazo_resposta_min", "Resposta (min)")
+            .field("prazo_resolucao_min", "Resolução (min)")
+            .field("alerta_pct", "Alerta (%)")
+            .searchable(&["servico_nome", "tipo_nome"]),
+        vec![
+            FormField::select_query("servico_id", "Serviço", SERVICOS).placeholder("Nenhum (usar o tipo)"),
+            FormField::select_query("tipo_id", "Tipo", TIPOS).placeholder("Nenhum (padrão para os serviços do tipo)"),
+            FormField::number("prazo_resposta_min", "Prazo de resposta (minutos)").required(),
+            FormField::number("prazo_resolucao_min", "Prazo de resolução (minutos)").required(),
+            FormField::number("alerta_pct", "Em risco a partir de (% do prazo)")
+                .required()
+                .placeholder("80"),
+        ],
+    )
+    .router()
+    .route("/sla-violacoes", get(view::list_violacoes_sla))
+}
+
+/// Filas por serviço, membros, habilidades dos atendentes, transferência e "minha fila"
+fn router_fila() -> Router<SharedState> {
+    let filas = AdminResource::new(
+        FilaRepository,
+        ListConfig::new("fila", "Fila", "Filas", "/chamado/fila")
+            .field("id", "ID")
+            .field("nome", "Nome")
+            .field("servico_nome", "Serviço")
+            .field("estrategia", "Estratégia")
+            .field("ativo", "Ativa")
+            .searchable(&["nome", "servico_nome"]),
+        vec![
+            FormField::text("nome", "Nome").required(),
+            FormField::select_query("servico_id", "Serviço", SERVICOS).required(),
+            FormField::select("estrategia", "Estratégia", Estrategia::opcoes()).required(),
+            FormField::checkbox("ativo", "Ativa"),
+        ],
+    )
+    .router();
+
+    let membros = AdminResource::new(
+        FilaMembroRepository,
+        ListConfig::new("fila-membro", "Membro da fila", "Membros das filas", "/chamado/fila-membro")
+            .field("id", "ID")
+            .field("fila_nome", "Fila")
+            .field("user_nome", "Atendente")
+            .field("ativo", "Ativo")
+            .searchable(&["fila_nome", "user_nome"]),
+        vec![
+            FormField::select_query("fila_id", "Fila", FILAS).required(),
+            FormField::select_query("user_id", "Atendente", USUARIOS).required(),
+            FormField::checkbox("ativo", "Ativo"),
+        ],
+    )
+    .router();
+
+    let habilidades = AdminResource::new(
+        HabilidadeRepository,
+        ListConfig::new("habilidade", "Habilidade", "Habilidades", "/chamado/habilidade")
+            .field("id", "ID")
+            .field("user_nome", "Atendente")
+            .field("tipo_nome", "Tipo")
+            .searchable(&["user_nome", "tipo_nome"]),
+        vec![
+            FormField::select_query("user_id", "Atendente", USUARIOS).required(),
+            FormField::select_query("tipo_id", "Tipo", TIPOS).required(),
+        ],
+    )
+    .router();
+
+    filas
+        .merge(membros)
+        .merge(habilidades)
+        .route("/minha-fila", get(view::minha_fila))
+        .route("/chamado-transferir/{chamado_id}", post(view::transferir_chamado))
+}
+
 fn router_chamado() -> Router<SharedState> {
     Router::new()
         .route("/chamado", get(view::list_chamado))
+        .route("/chamado/exportar", get(view::export_chamado))
+        .route("/chamado/eventos", get(view::eventos_chamado))
         .route(
             "/chamado-form",
             get(view::show_chamado_form).post(view::create_chamado),
@@ -92,6 +229,11 @@ fn router_chamado() -> Router<SharedState> {
             "/chamado-upload-imagem/{id_chamado}",
             post(view::upload_imagem),
         )
+        .route("/chamado-status/{id}", post(view::alterar_status_chamado))
+        .route(
+            "/chamado-comentario/{chamado_id}",
+            post(view::create_comentario).layer(DefaultBodyLimit::max(anexo::LIMITE_REQUISICAO)),
+        )
         .route("/chamado/{id}", delete(view::delete_chamado))
 }
 
@@ -112,3 +254,41 @@ fn router_chamado_atendimento() -> Router<SharedState> {
                 ]))),
         )
 }
+
+/// API v1: chamados com a permissão chamado_user; atendimento com chamado_admin
+pub fn router_v1() -> Router<SharedState> {
+    let chamados = Router::new()
+        .route(
+            "/chamados",
+            get(api::listar_chamados).post(api::criar_chamado),
+        )
+        .route(
+            "/chamados/{id}",
+            get(api::obter_chamado)
+                .put(api::atualizar_chamado)
+                .delete(api::excluir_chamado),
+        )
+        .route("/chamados/{id}/status", post(api::alterar_status))
+        .route("/chamados/{id}/historico", get(api::listar_historico))
+        .route(
+            "/chamados/{id}/comentarios",
+            get(api::listar_comentarios).post(api::criar_comentario),
+        )
+        .layer(middleware::from_fn(middlewares::require_roles(vec![
+            "chamado_user",
+        ])));
+
+    let atendimento = Router::new()
+        .route(
+            "/chamados/{id}/atendimento",
+            get(api::obter_atendimento)
+                .post(api::iniciar_atendimento)
+                .put(api::atualizar_atendimento),
+        )
+        .route("/chamados/{id}/transferir", post(api::transferir_chamado))
+        .layer(middleware::from_fn(middlewares::require_roles(vec![
+            "chamado_admin",
+        ])));
+
+    chamados.merge(atendimento)
+}
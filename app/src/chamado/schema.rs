@@ -1,13 +1,18 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError, ValidationErrors};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateTipoChamadoSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub nome: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateTipoChamadoSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub nome: String,
 }
 
@@ -31,28 +36,43 @@ pub struct ServicoTipoViewSchema {
     pub nome_tipo: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateCategoriaChamadoSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub nome: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateCategoriaChamadoSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub nome: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateChamado {
+    #[validate(length(min = 1, message = "Informe o título"))]
     pub titulo: String,
+    /// sempre "Aberto" ao criar
+    #[schema(ignore)]
     pub status: Option<i32>,
+    /// sempre o usuário logado
+    #[schema(ignore)]
     pub user_solic_id: Option<i64>,
     pub servico_id: i64,
     pub tipo_id: i64,
+    /// 0 baixo, 1 médio (padrão), 2 alto
+    #[validate(range(min = 0, max = 2, message = "Impacto inválido (0 a 2)"))]
+    pub impacto: Option<i32>,
+    /// 0 baixa, 1 média (padrão), 2 alta
+    #[validate(range(min = 0, max = 2, message = "Urgência inválida (0 a 2)"))]
+    pub urgencia: Option<i32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateChamado {
+    #[validate(length(min = 1, message = "Informe o título"))]
     pub titulo: Option<String>,
+    /// documento EditorJS
     pub descricao: Value,
     pub servico_id: Option<i64>,
     pub tipo_id: Option<i64>,
@@ -67,10 +87,159 @@ pub struct CreateGerenciamentoChamado {
     pub observacao_chamado: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateGerenciamentoChamado {
-    pub descricao: Option<String>, //descriçao interna
+    pub descricao: Option<String>, //descriçao interna (legado: usar comentário interno)
     pub status: i32,               //status para alterar no chamado
     pub categoria_id: i64,
-    pub observacao_chamado: Option<String>, //observaçao para o usuario do chamado
+    pub observacao_chamado: Option<String>, //observaçao para o usuario (legado: usar comentário)
+    pub motivo: Option<String>, //motivo da mudança de status (histórico)
+    #[validate(range(min = 0, max = 2, message = "Impacto inválido (0 a 2)"))]
+    pub impacto: Option<i32>, //reavaliação de quem atende; vazio mantém o atual
+    #[validate(range(min = 0, max = 2, message = "Urgência inválida (0 a 2)"))]
+    pub urgencia: Option<i32>,
+    pub updated_at: DateTime<Utc>, //versão lida pelo formulário (concorrência otimista)
+    #[serde(skip_deserializing)]
+    #[schema(ignore)]
+    pub updated_by: Option<i64>, //preenchido pela view com o usuário logado
+}
+
+/// Novo status pedido pelo usuário; a transição é validada em chamado::transicao
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AlterarStatusChamado {
+    /// 0 Aberto, 1 Em Atendimento, 2 Pausado, 3 Resolvido, 4 Fechado
+    pub status: i32,
+    /// registrado no histórico de status
+    pub motivo: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CreateStatusHistorico {
+    pub chamado_id: i64,
+    pub status_de: Option<i32>,
+    pub status_para: i32,
+    pub user_id: Option<i64>,
+    pub motivo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateComentario {
+    /// preenchido pela view com o chamado da rota
+    #[serde(skip_deserializing)]
+    #[schema(ignore)]
+    pub chamado_id: i64,
+    /// preenchido pela view com o usuário logado
+    #[serde(skip_deserializing)]
+    #[schema(ignore)]
+    pub autor_id: i64,
+    /// documento EditorJS
+    pub corpo: Value,
+    /// somente quem atende (chamado_admin) publica comentário interno
+    #[serde(default)]
+    pub interno: bool,
+}
+
+/// Arquivo já gravado em uploads, a ser ligado ao comentário
+#[derive(Debug)]
+pub struct CreateAnexoComentario {
+    pub nome: String,
+    pub url: String,
+    pub tamanho: i64,
+    pub content_type: Option<String>,
+}
+
+/// Formulário da política de SLA (criação e edição): serviço ou tipo, nunca os dois
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSlaPoliticaSchema {
+    #[serde(default, deserialize_with = "crate::utils::serde_utils::option_i64_from_str")]
+    pub servico_id: Option<i64>,
+    #[serde(default, deserialize_with = "crate::utils::serde_utils::option_i64_from_str")]
+    pub tipo_id: Option<i64>,
+    pub prazo_resposta_min: i32,
+    pub prazo_resolucao_min: i32,
+    pub alerta_pct: i32,
+}
+
+// manual para apontar no campo o erro de alvo, que envolve os dois ids
+impl Validate for CreateSlaPoliticaSchema {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut erros = ValidationErrors::new();
+        let erro = |mensagem: &'static str| ValidationError::new("sla").with_message(mensagem.into());
+
+        if self.servico_id.is_some() == self.tipo_id.is_some() {
+            erros.add("servico_id", erro("Informe o serviço ou o tipo, não os dois"));
+        }
+        if self.prazo_resposta_min <= 0 {
+            erros.add("prazo_resposta_min", erro("O prazo deve ser maior que zero"));
+        }
+        if self.prazo_resolucao_min < self.prazo_resposta_min {
+            erros.add(
+                "prazo_resolucao_min",
+                erro("A resolução não pode vencer antes da resposta"),
+            );
+        }
+        if !(1..=100).contains(&self.alerta_pct) {
+            erros.add("alerta_pct", erro("Informe um percentual entre 1 e 100"));
+        }
+
+        if erros.is_empty() { Ok(()) } else { Err(erros) }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpsertChamadoSla {
+    pub chamado_id: i64,
+    pub politica_id: i64,
+    pub prazo_resposta: DateTime<Utc>,
+    pub prazo_resolucao: DateTime<Utc>,
+    pub respondido_em: Option<DateTime<Utc>>,
+    pub resolvido_em: Option<DateTime<Utc>>,
+    pub situacao: i32,
+}
+
+/// Formulário da fila do serviço (criação e edição)
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateFilaSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
+    pub nome: String,
+    pub servico_id: i64,
+    /// 0 manual, 1 rodízio, 2 menor carga, 3 habilidade
+    #[validate(range(min = 0, max = 3, message = "Estratégia inválida (0 a 3)"))]
+    pub estrategia: i32,
+    #[serde(default, deserialize_with = "crate::utils::serde_utils::bool_from_str")]
+    pub ativo: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateFilaMembroSchema {
+    pub fila_id: i64,
+    pub user_id: i64,
+    #[serde(default, deserialize_with = "crate::utils::serde_utils::bool_from_str")]
+    pub ativo: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateHabilidadeSchema {
+    pub user_id: i64,
+    pub tipo_id: i64,
+}
+
+#[derive(Debug)]
+pub struct CreateAtribuicao {
+    pub chamado_id: i64,
+    /// mantém a fila gravada quando vazio (transferência ou quem assumiu)
+    pub fila_id: Option<i64>,
+    pub user_de: Option<i64>,
+    pub user_para: i64,
+    /// vazio na distribuição automática
+    pub por_user_id: Option<i64>,
+    pub motivo: Option<String>,
+}
+
+/// Transferência manual do chamado para outro atendente da fila
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct TransferirChamado {
+    pub user_id: i64,
+    /// registrado no histórico de atribuições
+    pub motivo: Option<String>,
 }
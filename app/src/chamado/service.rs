@@ -1,18 +1,38 @@
-use std::{collections::HashSet, fs};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
 
 use anyhow::Result;
 use anyhow::anyhow;
-use serde_json::{Value, from_str};
-use shared::{PaginatedResponse, Repository};
-use sqlx::PgPool;
-use sqlx::Postgres;
-use sqlx::Transaction;
+use serde_json::{Value, json};
+use shared::{
+    AppError, Db, FilterField, FilterKind, FilterOp, FilterValue, ListQuery, PaginatedResponse, Repository,
+    UnitOfWork, limites_pagina,
+};
+use sqlx::{PgConnection, PgPool};
 
 use crate::chamado::StatusChamado;
+use crate::chamado::transicao::Papel;
 use crate::chamado::model::GerenciamentoChamado;
 use crate::chamado::model::ImagemChamado;
+use crate::chamado::model::{AnexoComentario, ComentarioChamado, ComentarioThread};
+use crate::chamado::model::{StatusHistorico, TempoStatus};
+use crate::chamado::model::{ChamadoSla, ViolacaoSla};
+use crate::chamado::prioridade::{Nivel, Prioridade};
+use crate::chamado::sla::{self, MarcosSla, PoliticaSla};
+use crate::chamado::fila::{self, Candidato, Estrategia};
+use crate::chamado::model::{Atribuicao, AtribuicaoHistorico, ChamadoFila, MembroCarga};
+use crate::chamado::repository::AtribuicaoRepository;
+use crate::chamado::repository::ComentarioChamadoRepository;
+use crate::chamado::repository::StatusHistoricoRepository;
+use crate::chamado::repository::ChamadoSlaRepository;
 use crate::chamado::repository::GerenciamentoChamadoRepository;
+use crate::chamado::schema::CreateAtribuicao;
 use crate::chamado::schema::CreateGerenciamentoChamado;
+use crate::chamado::schema::CreateStatusHistorico;
+use crate::chamado::schema::UpsertChamadoSla;
+use crate::chamado::schema::{CreateAnexoComentario, CreateComentario};
 use crate::chamado::schema::UpdateGerenciamentoChamado;
 use crate::{
     chamado::{
@@ -27,6 +47,8 @@ use crate::{
             UpdateChamado, UpdateServicoChamadoSchema, UpdateTipoChamadoSchema,
         },
     },
+    core::CalendarioService,
+    notificacao::{Evento, NotificacaoService, NovaNotificacao},
     permissao::{User, UserRolesService},
 };
 
@@ -66,14 +88,20 @@ impl TipoChamadoService {
         self.repo.delete(pool, id).await
     }
 
-    pub async fn get_paginated(
+    pub async fn restore(&self, pool: &PgPool, id: i64) -> Result<()> {
+        Repository::<TipoChamado, i64>::restore(&self.repo, pool, id).await
+    }
+
+    pub async fn purge(&self, pool: &PgPool, id: i64) -> Result<()> {
+        Repository::<TipoChamado, i64>::purge(&self.repo, pool, id).await
+    }
+
+    pub async fn list(
         &self,
         pool: &PgPool,
-        find: Option<&str>,
-        page: i32,
-        page_size: i32,
+        query: &ListQuery,
     ) -> Result<PaginatedResponse<TipoChamado>> {
-        Repository::<TipoChamado, i64>::get_paginated(&self.repo, pool, find, page, page_size).await
+        Repository::<TipoChamado, i64>::list(&self.repo, pool, query).await
     }
 
     pub async fn get_by_name(&self, pool: &PgPool, nome: String) -> Result<TipoChamado> {
@@ -123,15 +151,12 @@ impl CategoriaService {
         self.repo.delete(pool, id).await
     }
 
-    pub async fn get_paginated(
+    pub async fn list(
         &self,
         pool: &PgPool,
-        find: Option<&str>,
-        page: i32,
-        page_size: i32,
+        query: &ListQuery,
     ) -> Result<PaginatedResponse<CategoriaChamado>> {
-        Repository::<CategoriaChamado, i64>::get_paginated(&self.repo, pool, find, page, page_size)
-            .await
+        Repository::<CategoriaChamado, i64>::list(&self.repo, pool, query).await
     }
 
     pub async fn get_by_name(&self, pool: &PgPool, nome: String) -> Result<CategoriaChamado> {
@@ -265,6 +290,7 @@ impl ServicoService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
 }
@@ -284,128 +310,207 @@ impl ChamadoService {
         Repository::<Chamado, i64>::get_by_id(&self.repo, pool, id).await
     }
 
-    pub async fn create(&self, pool: &PgPool, input: CreateChamado) -> Result<Chamado> {
-        self.repo.create(pool, input).await
+    /*
+       abre o chamado, registra a abertura no histórico de status
+       e entrega à fila do serviço (ver FilaService::distribuir)
+    */
+    pub async fn create<'c, A: Db<'c>>(&self, db: A, input: CreateChamado) -> Result<Chamado> {
+        let mut uow = UnitOfWork::begin(db).await?;
+
+        let chamado = self.repo.create(uow.conn(), input).await?;
+        StatusHistoricoRepository
+            .create(
+                uow.conn(),
+                CreateStatusHistorico {
+                    chamado_id: chamado.id,
+                    status_de: None,
+                    status_para: chamado.status.unwrap_or_default(),
+                    user_id: Some(chamado.user_solic_id),
+                    motivo: None,
+                },
+            )
+            .await?;
+        SlaService::recalcular(uow.conn(), Some(chamado.id)).await?;
+        FilaService::distribuir(uow.conn(), &chamado).await?;
+
+        uow.commit().await?;
+
+        Ok(chamado)
     }
 
-    pub async fn update(&self, pool: &PgPool, id: i64, input: UpdateChamado) -> Result<Chamado> {
-        match self.get_by_id(pool, id).await {
-            Ok(old_chamado) => {
-                Self::cleanup_images(old_chamado, input.descricao.clone());
-            }
-            Err(_) => {}
+    pub async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: UpdateChamado,
+    ) -> Result<Chamado> {
+        let mut conn = db.acquire().await?;
+        if let Ok(old_chamado) = Repository::<Chamado, i64>::get_by_id(&self.repo, &mut *conn, id).await {
+            Self::cleanup_images(old_chamado, input.descricao.clone());
         }
 
-        self.repo.update(pool, id, input).await
+        self.repo.update(&mut *conn, id, input).await
     }
 
-    pub async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
-        self.repo.delete(pool, id).await
+    pub async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        self.repo.delete(db, id).await
     }
 
-    pub async fn get_paginated(
-        &self,
-        pool: &PgPool,
-        find: Option<&str>,
-        page: i32,
-        page_size: i32,
-    ) -> Result<PaginatedResponse<Chamado>> {
-        Repository::<Chamado, i64>::get_paginated(&self.repo, pool, find, page, page_size).await
+    pub async fn list(&self, pool: &PgPool, query: &ListQuery) -> Result<PaginatedResponse<Chamado>> {
+        Repository::<Chamado, i64>::list(&self.repo, pool, query).await
     }
 
     /*
        retorna lista de chamados do usuario
     */
-    pub async fn get_paginated_ownership(
+    pub async fn list_ownership(
         &self,
         pool: &PgPool,
-        find: Option<&str>,
-        page: i32,
-        page_size: i32,
+        query: ListQuery,
         user_id: i64,
     ) -> Result<PaginatedResponse<Chamado>> {
-        let page = page.max(1) as i32;
-        let page_size = page_size.min(100) as i32;
-        let offset = (page - 1) * page_size;
-
-        let like_term = match find {
-            Some(search_term) => format!("%{search_term}%"),
-            None => "%".to_string(),
-        };
+        let filters = query.filters.clone().push(
+            FilterField::new("user_solic_id", "m.user_solic_id", FilterKind::Int),
+            FilterOp::Eq(FilterValue::Int(user_id)),
+        );
+        self.list(pool, &query.with_filters(filters)).await
+    }
 
-        // Busca total de registros para paginação
-        let total: (i64,) = sqlx::query_as::<_, (i64,)>(
-            r#"
-            SELECT COUNT(p) FROM chamado_chamados p
-            WHERE p.titulo ILIKE $1 and p.user_solic_id = $2
-            "#,
-        )
-        .bind(&like_term)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await?;
+    /// Listagem com a visibilidade do papel (ver `Papel::somente_do_usuario`); tela, exportação e API
+    pub async fn list_visiveis(
+        &self,
+        pool: &PgPool,
+        query: ListQuery,
+        papel: Papel,
+        user_id: i64,
+    ) -> Result<PaginatedResponse<Chamado>> {
+        match papel.somente_do_usuario(user_id) {
+            Some(user_id) => self.list_ownership(pool, query, user_id).await,
+            None => self.list(pool, &query).await,
+        }
+    }
 
-        let total_records = total.0;
-        let total_pages = if total_records == 0 {
-            1
-        } else {
-            ((total_records as f64) / (page_size as f64)).ceil() as i32
-        };
+    /*
+       muda o status pedido pelo usuário (solicitante ou atendente),
+       validando a transição em chamado::transicao
+    */
+    pub async fn alterar_status<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        para: StatusChamado,
+        papel: Papel,
+        user_id: i64,
+        motivo: Option<String>,
+    ) -> Result<Chamado> {
+        let mut uow = UnitOfWork::begin(db).await?;
+        let chamado =
+            Self::transicionar(uow.conn(), id, para, papel, Some(user_id), motivo).await?;
+        uow.commit().await?;
+
+        Ok(chamado)
+    }
 
-        // Busca os registros paginados
-        let items: Vec<Chamado> = sqlx::query_as!(
-            Chamado,
-            r#"
-            SELECT 
-                id,
-                titulo,
-                descricao,
-                status,
-                created_at,
-                updated_at,
-                user_solic_id,
-                servico_id,
-                tipo_id
-            FROM chamado_chamados p
-            WHERE p.titulo ILIKE $1 and p.user_solic_id = $2
-            ORDER BY p.id DESC
-            LIMIT $3 OFFSET $4
-            "#,
-            like_term,
-            user_id,
-            page_size as i64,
-            offset as i64
-        )
-        .fetch_all(pool)
-        .await?;
+    /*
+       único caminho para gravar o status: trava o chamado, confere a tabela de transições,
+       falha com AppError::Conflict quando o papel não pode fazer a mudança
+       e registra a transição em chamado_status_historico, recalculando o SLA
+       e notificando o solicitante
+    */
+    pub async fn transicionar(
+        conn: &mut PgConnection,
+        id: i64,
+        para: StatusChamado,
+        papel: Papel,
+        user_id: Option<i64>,
+        motivo: Option<String>,
+    ) -> Result<Chamado> {
+        let repo = ChamadoRepository;
+        let atual = StatusChamado::from_i32(repo.get_status_for_update(conn, id).await?);
+
+        atual.validar_transicao(para, papel)?;
+
+        let chamado = repo.update_status(conn, id, para.to_i32()).await?;
+        // manter o status não entra no histórico
+        if atual != para {
+            let motivo_historico = motivo.filter(|m| !m.trim().is_empty());
+            StatusHistoricoRepository
+                .create(
+                    conn,
+                    CreateStatusHistorico {
+                        chamado_id: id,
+                        status_de: Some(atual.to_i32()),
+                        status_para: para.to_i32(),
+                        user_id,
+                        motivo: motivo_historico.clone(),
+                    },
+                )
+                .await?;
+            SlaService::recalcular(conn, Some(id)).await?;
+
+            // o solicitante fica sabendo do que ele mesmo não fez
+            if user_id != Some(chamado.user_solic_id) {
+                NotificacaoService::notificar(
+                    conn,
+                    NovaNotificacao {
+                        user_id: chamado.user_solic_id,
+                        evento: Evento::Status,
+                        titulo: format!("Chamado #{}: {}", id, para),
+                        mensagem: Some(format!("{} → {}", atual, para)),
+                        url: Some(format!("/chamado/chamado-form/{}", id)),
+                        contexto: json!({
+                            "chamado_id": id,
+                            "chamado_titulo": chamado.titulo,
+                            "motivo": motivo_historico,
+                        }),
+                    },
+                )
+                .await?;
+            }
+        }
 
-        Ok(PaginatedResponse {
-            data: items,
-            total_records,
-            page,
-            page_size,
-            total_pages,
-        })
+        Ok(chamado)
     }
 
-    pub async fn update_status(pool: &PgPool, id: i64, status: i32) -> Result<Chamado> {
-        // valida status
-        let _ = StatusChamado::try_from(status);
+    /*
+       linha do tempo de status e o tempo total em cada um (base das métricas de resolução),
+       corrido e dentro do expediente
+    */
+    pub async fn historico(
+        &self,
+        pool: &PgPool,
+        id: i64,
+    ) -> Result<(Vec<StatusHistorico>, Vec<TempoStatus>)> {
+        let mut conn = pool.acquire().await?;
+
+        let mut linhas = StatusHistoricoRepository.list_by_chamado(&mut conn, id).await?;
+        let calendario = CalendarioService::do_expediente(&mut conn).await?;
+
+        let mut tempos: Vec<TempoStatus> = Vec::new();
+        for linha in linhas.iter_mut() {
+            // Fechado encerra a contagem (segundos vazio)
+            let Some(segundos) = linha.segundos else {
+                continue;
+            };
+            let fim = linha.created_at + chrono::Duration::seconds(segundos);
+            let uteis = calendario.tempo_util(linha.created_at, fim).num_seconds();
+            linha.segundos_uteis = Some(uteis);
+
+            match tempos.iter_mut().find(|t| t.status == linha.status_para) {
+                Some(t) => {
+                    t.segundos += segundos;
+                    t.segundos_uteis += uteis;
+                }
+                None => tempos.push(TempoStatus {
+                    status: linha.status_para,
+                    segundos,
+                    segundos_uteis: uteis,
+                }),
+            }
+        }
+        tempos.sort_by_key(|t| t.status);
 
-        Ok(sqlx::query_as!(
-            Chamado,
-            r#"
-            UPDATE chamado_chamados
-            SET 
-                status = $1,
-                updated_at = NOW()
-            WHERE id = $2
-            RETURNING *"#,
-            status,
-            id
-        )
-        .fetch_one(pool)
-        .await?)
+        Ok((linhas, tempos))
     }
 
     // Função auxiliar para verificar se o usuário é dono do chamado
@@ -608,127 +713,508 @@ impl GerenciamentoChamadoService {
     /*
        sempre que iniciar um atendimento
        mudar o estatus do chamado para em atendimento
+       quem inicia passa a ser o responsável pelo chamado
 
     */
-    pub async fn create(
+    pub async fn create<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         input: CreateGerenciamentoChamado,
     ) -> Result<GerenciamentoChamado> {
-        let status = StatusChamado::EmAtendimento.to_i32();
-        // abre a transação
-        let mut tx: Transaction<'_, Postgres> = pool.begin().await?;
-
-        // atualiza o status do chamado
-        let _chamado: Chamado = sqlx::query_as!(
-            Chamado,
-            r#"
-            UPDATE chamado_chamados
-            SET 
-                status = $1,
-                updated_at = NOW()
-            WHERE id = $2
-            RETURNING id, titulo, descricao, status, created_at, updated_at, user_solic_id, servico_id, tipo_id
-            "#,
-            status,
-            input.chamado_id
+        let mut uow = UnitOfWork::begin(db).await?;
+
+        ChamadoService::transicionar(
+            uow.conn(),
+            input.chamado_id,
+            StatusChamado::EmAtendimento,
+            Papel::Atendente,
+            Some(input.user_atend_id),
+            None,
         )
-        .fetch_one(&mut *tx)
         .await?;
+        FilaService::assumir(uow.conn(), input.chamado_id, input.user_atend_id).await?;
+        let gerenciamento = self.repo.create(uow.conn(), input).await?;
 
-        // insere na tabela gerenciamento
-        let query = format!(
-            "INSERT INTO {} 
-            (
-                descricao,
-                categoria_id,
-                chamado_id,
-                user_atend_id,
-                observacao_chamado,
-                created_at,
-                updated_at
-            ) VALUES (
-                $1, $2, $3, $4, $5, NOW(), NOW()
-            ) RETURNING id, descricao, categoria_id, chamado_id, user_atend_id, observacao_chamado, created_at, updated_at",
-            self.repo.table_name()
-        );
-
-        let gerenciamento: GerenciamentoChamado = sqlx::query_as(&query)
-            .bind(input.descricao)
-            .bind(input.categoria_id)
-            .bind(input.chamado_id)
-            .bind(input.user_atend_id)
-            .bind(input.observacao_chamado)
-            .fetch_one(&mut *tx)
-            .await?;
-
-        tx.commit().await?;
+        uow.commit().await?;
 
         Ok(gerenciamento)
     }
 
-    pub async fn update(
+    /*
+       falha com ConflictError se outro atendente alterou o atendimento
+       depois que o formulário foi aberto (input.updated_at)
+    */
+    pub async fn update<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         id: i64,
         chamado_id: i64,
         input: UpdateGerenciamentoChamado,
     ) -> Result<GerenciamentoChamado> {
-        let _status = StatusChamado::try_from(input.status);
-        // abre a transação
-        let mut tx: Transaction<'_, Postgres> = pool.begin().await?;
+        let status = StatusChamado::try_from(input.status)
+            .map_err(|e| AppError::Unprocessable(e.to_string()))?;
+        let mut uow = UnitOfWork::begin(db).await?;
 
-        // atualiza o status do chamado
-        let _chamado: Chamado = sqlx::query_as!(
-            Chamado,
-            r#"
-            UPDATE chamado_chamados
-            SET 
-                status = $1,
-                updated_at = NOW()
-            WHERE id = $2
-            RETURNING id, titulo, descricao, status, created_at, updated_at, user_solic_id, servico_id, tipo_id
-            "#,
-            input.status,
-            chamado_id
+        // impacto/urgência reavaliados mudam a prioridade e, com ela, os prazos de SLA
+        if input.impacto.is_some() || input.urgencia.is_some() {
+            Self::reavaliar_prioridade(uow.conn(), chamado_id, input.impacto, input.urgencia).await?;
+        }
+
+        // atendimento é exclusivo de chamado_admin (validado no router)
+        ChamadoService::transicionar(
+            uow.conn(),
+            chamado_id,
+            status,
+            Papel::Atendente,
+            input.updated_by,
+            input.motivo.clone(),
         )
-        .fetch_one(&mut *tx)
         .await?;
+        let gerenciamento = self.repo.update(uow.conn(), id, input).await?;
 
-        // insere na tabela gerenciamento
-        let query = format!(
-            "UPDATE {} SET descricao = $1, categoria_id = $2 , observacao_chamado = $3, updated_at = NOW()
-            WHERE id = $4 RETURNING * ",
-            self.repo.table_name()
-        );
+        uow.commit().await?;
 
-        let gerenciamento: GerenciamentoChamado = sqlx::query_as(&query)
-            .bind(input.descricao)
-            .bind(input.categoria_id)
-            .bind(input.observacao_chamado)
-            .bind(id)
-            .fetch_one(&mut *tx)
+        Ok(gerenciamento)
+    }
+
+    /// Grava impacto e urgência (o que não veio fica como está) e recalcula o SLA se a prioridade mudou
+    async fn reavaliar_prioridade(
+        conn: &mut PgConnection,
+        chamado_id: i64,
+        impacto: Option<i32>,
+        urgencia: Option<i32>,
+    ) -> Result<()> {
+        let repo = ChamadoRepository;
+        let atual = Repository::<Chamado, i64>::get_by_id(&repo, &mut *conn, chamado_id).await?;
+        let impacto = Nivel::from_i32(impacto.unwrap_or(atual.impacto));
+        let urgencia = Nivel::from_i32(urgencia.unwrap_or(atual.urgencia));
+        if impacto.to_i32() == atual.impacto && urgencia.to_i32() == atual.urgencia {
+            return Ok(());
+        }
+
+        let chamado = repo.update_prioridade(conn, chamado_id, impacto, urgencia).await?;
+        if chamado.prioridade != atual.prioridade {
+            SlaService::recalcular(conn, Some(chamado_id)).await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ComentarioChamadoService {
+    repo: ComentarioChamadoRepository,
+}
+
+impl ComentarioChamadoService {
+    pub fn new() -> Self {
+        Self {
+            repo: ComentarioChamadoRepository,
+        }
+    }
+
+    /*
+       conversa do chamado em ordem cronológica;
+       comentários internos só para quem atende (incluir_internos)
+    */
+    pub async fn list_thread(
+        &self,
+        pool: &PgPool,
+        chamado_id: i64,
+        incluir_internos: bool,
+    ) -> Result<Vec<ComentarioThread>> {
+        let mut conn = pool.acquire().await?;
+
+        let mut thread = self
+            .repo
+            .list_by_chamado(&mut conn, chamado_id, incluir_internos)
             .await?;
 
-        tx.commit().await?;
+        let ids: Vec<i64> = thread.iter().map(|c| c.comentario.id).collect();
+        let mut anexos: HashMap<i64, Vec<AnexoComentario>> = HashMap::new();
+        for anexo in self.repo.list_anexos(&mut conn, &ids).await? {
+            anexos.entry(anexo.comentario_id).or_default().push(anexo);
+        }
+
+        for comentario in thread.iter_mut() {
+            comentario.anexos = anexos.remove(&comentario.comentario.id).unwrap_or_default();
+        }
 
-        Ok(gerenciamento)
+        Ok(thread)
     }
 
-    pub async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
-        self.repo.delete(pool, id).await
+    /*
+       grava o comentário e os anexos (já salvos em uploads) numa única transação
+    */
+    pub async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: CreateComentario,
+        anexos: Vec<CreateAnexoComentario>,
+    ) -> Result<ComentarioChamado> {
+        let mut uow = UnitOfWork::begin(db).await?;
+
+        let comentario = self.repo.create(uow.conn(), input).await?;
+        for anexo in &anexos {
+            self.repo
+                .create_anexo(uow.conn(), comentario.id, anexo)
+                .await?;
+        }
+        Self::notificar(uow.conn(), &comentario).await?;
+
+        uow.commit().await?;
+
+        Ok(comentario)
     }
+}
 
-    pub async fn get_paginated(
+impl ComentarioChamadoService {
+    /*
+       avisa o solicitante (se o comentário não for interno) e o responsável,
+       menos o próprio autor
+    */
+    async fn notificar(conn: &mut PgConnection, comentario: &ComentarioChamado) -> Result<()> {
+        let chamado =
+            Repository::<Chamado, i64>::get_by_id(&ChamadoRepository, &mut *conn, comentario.chamado_id)
+                .await?;
+        let responsavel = AtribuicaoRepository
+            .get_by_chamado(conn, comentario.chamado_id)
+            .await?
+            .map(|a| a.user_id);
+
+        let mut destinatarios = Vec::new();
+        if !comentario.interno {
+            destinatarios.push((chamado.user_solic_id, format!("/chamado/chamado-form/{}", chamado.id)));
+        }
+        if let Some(responsavel) = responsavel.filter(|r| *r != chamado.user_solic_id) {
+            destinatarios.push((responsavel, format!("/chamado/chamado-atendimento/{}", chamado.id)));
+        }
+
+        for (user_id, url) in destinatarios {
+            if user_id == comentario.autor_id {
+                continue;
+            }
+            NotificacaoService::notificar(
+                conn,
+                NovaNotificacao {
+                    user_id,
+                    evento: Evento::Comentario,
+                    titulo: format!("Novo comentário no chamado #{}", chamado.id),
+                    mensagem: Some(chamado.titulo.clone()),
+                    url: Some(url),
+                    contexto: json!({
+                        "chamado_id": chamado.id,
+                        "chamado_titulo": chamado.titulo,
+                    }),
+                },
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Prazos de SLA dos chamados; as regras ficam em chamado::sla
+pub struct SlaService {
+    repo: ChamadoSlaRepository,
+}
+
+impl SlaService {
+    pub fn new() -> Self {
+        Self {
+            repo: ChamadoSlaRepository,
+        }
+    }
+
+    /*
+       recalcula prazos e situação: com `chamado_id`, só aquele chamado (após uma transição);
+       sem, os que ainda correm (verificador em segundo plano)
+       chamados sem política aplicável ficam sem SLA
+       devolve os chamados cuja violação foi vista agora
+    */
+    pub async fn recalcular(conn: &mut PgConnection, chamado_id: Option<i64>) -> Result<Vec<i64>> {
+        let repo = ChamadoSlaRepository;
+        let agora = chrono::Utc::now();
+        let mut violados = Vec::new();
+
+        let pendentes = repo.pendentes(conn, chamado_id).await?;
+        if pendentes.is_empty() {
+            return Ok(violados);
+        }
+        let calendario = CalendarioService::do_expediente(conn).await?;
+
+        for base in pendentes {
+            let pausado = base
+                .pausas_inicio
+                .iter()
+                .zip(&base.pausas_fim)
+                .map(|(inicio, fim)| calendario.tempo_util(*inicio, *fim))
+                .sum();
+            let calculo = sla::calcular(
+                &PoliticaSla {
+                    prazo_resposta_min: base.prazo_resposta_min,
+                    prazo_resolucao_min: base.prazo_resolucao_min,
+                    alerta_pct: base.alerta_pct,
+                }
+                .para_prioridade(Prioridade::from_i32(base.prioridade)),
+                &MarcosSla {
+                    aberto_em: base.aberto_em,
+                    respondido_em: base.respondido_em,
+                    resolvido_em: base.resolvido_em,
+                    pausado,
+                },
+                &calendario,
+                agora,
+            );
+
+            let nova_violacao = repo
+                .upsert(
+                    conn,
+                    UpsertChamadoSla {
+                        chamado_id: base.chamado_id,
+                        politica_id: base.politica_id,
+                        prazo_resposta: calculo.prazo_resposta,
+                        prazo_resolucao: calculo.prazo_resolucao,
+                        respondido_em: base.respondido_em,
+                        resolvido_em: base.resolvido_em,
+                        situacao: calculo.situacao.to_i32(),
+                    },
+                )
+                .await?;
+            if nova_violacao {
+                violados.push(base.chamado_id);
+            }
+        }
+
+        Ok(violados)
+    }
+
+    /// Uma rodada do verificador: todos os chamados em aberto numa transação
+    pub async fn verificar(&self, pool: &PgPool) -> Result<Vec<i64>> {
+        let mut uow = UnitOfWork::begin(pool).await?;
+        let violados = Self::recalcular(uow.conn(), None).await?;
+        uow.commit().await?;
+
+        Ok(violados)
+    }
+
+    pub async fn get_by_chamado(&self, pool: &PgPool, chamado_id: i64) -> Result<Option<ChamadoSla>> {
+        let mut conn = pool.acquire().await?;
+        self.repo.get_by_chamado(&mut conn, chamado_id).await
+    }
+
+    /// SLA dos chamados de uma página, por id (badge da listagem)
+    pub async fn situacoes(&self, pool: &PgPool, ids: &[i64]) -> Result<HashMap<i64, ChamadoSla>> {
+        let mut conn = pool.acquire().await?;
+        Ok(self
+            .repo
+            .list_by_chamados(&mut conn, ids)
+            .await?
+            .into_iter()
+            .map(|s| (s.chamado_id, s))
+            .collect())
+    }
+
+    pub async fn violacoes(
         &self,
         pool: &PgPool,
-        find: Option<&str>,
         page: i32,
         page_size: i32,
-    ) -> Result<PaginatedResponse<GerenciamentoChamado>> {
-        Repository::<GerenciamentoChamado, i64>::get_paginated(
-            &self.repo, pool, find, page, page_size,
+    ) -> Result<PaginatedResponse<ViolacaoSla>> {
+        let (page, page_size) = limites_pagina(page, page_size);
+        let mut conn = pool.acquire().await?;
+        let (data, total_records) = self.repo.violacoes(&mut conn, page, page_size).await?;
+
+        Ok(PaginatedResponse::offset(data, total_records, page, page_size))
+    }
+}
+
+/// Filas por serviço e responsável pelo chamado; as estratégias ficam em chamado::fila
+pub struct FilaService {
+    repo: AtribuicaoRepository,
+}
+
+impl FilaService {
+    pub fn new() -> Self {
+        Self {
+            repo: AtribuicaoRepository,
+        }
+    }
+
+    /*
+       escolhe o responsável do chamado recém-aberto pela estratégia da fila do serviço
+       sem fila ativa, estratégia manual ou nenhum membro ativo, o chamado aguarda alguém assumir
+    */
+    pub async fn distribuir(conn: &mut PgConnection, chamado: &Chamado) -> Result<Option<i64>> {
+        let repo = AtribuicaoRepository;
+        let Some(fila) = repo.fila_do_servico_for_update(conn, chamado.servico_id).await? else {
+            return Ok(None);
+        };
+        let estrategia = Estrategia::from_i32(fila.estrategia);
+        if estrategia == Estrategia::Manual {
+            return Ok(None);
+        }
+
+        let candidatos: Vec<Candidato> = repo
+            .membros(conn, fila.id, chamado.tipo_id)
+            .await?
+            .iter()
+            .map(|m| Candidato {
+                user_id: m.user_id,
+                carga: m.carga,
+                habilitado: m.habilitado,
+            })
+            .collect();
+        let Some(user_id) = fila::escolher(estrategia, &candidatos, fila.ultimo_user_id) else {
+            return Ok(None);
+        };
+
+        repo.set_ultimo(conn, fila.id, user_id).await?;
+        repo.atribuir(
+            conn,
+            CreateAtribuicao {
+                chamado_id: chamado.id,
+                fila_id: Some(fila.id),
+                user_de: None,
+                user_para: user_id,
+                por_user_id: None,
+                motivo: Some(format!("Distribuição automática ({})", estrategia)),
+            },
+        )
+        .await?;
+        Self::notificar_responsavel(conn, chamado, user_id).await?;
+
+        Ok(Some(user_id))
+    }
+
+    /// Quem inicia o atendimento vira o responsável, se ainda não era
+    pub async fn assumir(conn: &mut PgConnection, chamado_id: i64, user_id: i64) -> Result<()> {
+        let repo = AtribuicaoRepository;
+        let atual = repo.get_by_chamado(conn, chamado_id).await?;
+        if atual.as_ref().is_some_and(|a| a.user_id == user_id) {
+            return Ok(());
+        }
+
+        repo.atribuir(
+            conn,
+            CreateAtribuicao {
+                chamado_id,
+                fila_id: None,
+                user_de: atual.map(|a| a.user_id),
+                user_para: user_id,
+                por_user_id: Some(user_id),
+                motivo: Some("Assumiu o atendimento".to_string()),
+            },
         )
         .await
     }
+
+    /*
+       transferência manual para outro membro ativo da fila do serviço
+       o atendimento já iniciado acompanha o novo responsável
+       falha com AppError::Conflict no chamado fechado
+    */
+    pub async fn transferir<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        chamado_id: i64,
+        para: i64,
+        por: i64,
+        motivo: Option<String>,
+    ) -> Result<Atribuicao> {
+        let mut uow = UnitOfWork::begin(db).await?;
+
+        let status = ChamadoRepository.get_status_for_update(uow.conn(), chamado_id).await?;
+        if StatusChamado::from_i32(status) == StatusChamado::Fechado {
+            return Err(AppError::Conflict("Chamado fechado não pode ser transferido".into()).into());
+        }
+        let chamado = Repository::<Chamado, i64>::get_by_id(&ChamadoRepository, uow.conn(), chamado_id).await?;
+
+        let membros = self.membros_conn(uow.conn(), &chamado).await?;
+        if !membros.iter().any(|m| m.user_id == para) {
+            return Err(AppError::Unprocessable(
+                "O atendente não é membro ativo da fila do serviço".into(),
+            )
+            .into());
+        }
+
+        let atual = self.repo.get_by_chamado(uow.conn(), chamado_id).await?;
+        if atual.as_ref().is_some_and(|a| a.user_id == para) {
+            return Err(AppError::Unprocessable("O atendente já é o responsável".into()).into());
+        }
+
+        self.repo
+            .atribuir(
+                uow.conn(),
+                CreateAtribuicao {
+                    chamado_id,
+                    fila_id: None,
+                    user_de: atual.map(|a| a.user_id),
+                    user_para: para,
+                    por_user_id: Some(por),
+                    motivo: motivo.filter(|m| !m.trim().is_empty()),
+                },
+            )
+            .await?;
+        GerenciamentoChamadoRepository
+            .transferir(uow.conn(), chamado_id, para, por)
+            .await?;
+        Self::notificar_responsavel(uow.conn(), &chamado, para).await?;
+        let atribuicao = self
+            .repo
+            .get_by_chamado(uow.conn(), chamado_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        uow.commit().await?;
+
+        Ok(atribuicao)
+    }
+
+    /// Avisa o novo responsável (distribuição ou transferência)
+    async fn notificar_responsavel(conn: &mut PgConnection, chamado: &Chamado, user_id: i64) -> Result<()> {
+        NotificacaoService::notificar(
+            conn,
+            NovaNotificacao {
+                user_id,
+                evento: Evento::Atribuido,
+                titulo: format!("Chamado #{} atribuído a você", chamado.id),
+                mensagem: Some(chamado.titulo.clone()),
+                url: Some(format!("/chamado/chamado-atendimento/{}", chamado.id)),
+                contexto: json!({
+                    "chamado_id": chamado.id,
+                    "chamado_titulo": chamado.titulo,
+                }),
+            },
+        )
+        .await
+    }
+
+    pub async fn get_by_chamado(&self, pool: &PgPool, chamado_id: i64) -> Result<Option<Atribuicao>> {
+        let mut conn = pool.acquire().await?;
+        self.repo.get_by_chamado(&mut conn, chamado_id).await
+    }
+
+    pub async fn historico(&self, pool: &PgPool, chamado_id: i64) -> Result<Vec<AtribuicaoHistorico>> {
+        let mut conn = pool.acquire().await?;
+        self.repo.historico(&mut conn, chamado_id).await
+    }
+
+    /// Membros ativos da fila do serviço do chamado (destinos de transferência)
+    pub async fn membros(&self, pool: &PgPool, chamado: &Chamado) -> Result<Vec<MembroCarga>> {
+        let mut conn = pool.acquire().await?;
+        self.membros_conn(&mut conn, chamado).await
+    }
+
+    async fn membros_conn(&self, conn: &mut PgConnection, chamado: &Chamado) -> Result<Vec<MembroCarga>> {
+        match self.repo.fila_id_do_servico(conn, chamado.servico_id).await? {
+            Some(fila_id) => self.repo.membros(conn, fila_id, chamado.tipo_id).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn minha_fila(&self, pool: &PgPool, user_id: i64) -> Result<Vec<ChamadoFila>> {
+        let mut conn = pool.acquire().await?;
+        self.repo.minha_fila(&mut conn, user_id).await
+    }
 }
@@ -0,0 +1,280 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, warn};
+
+use crate::chamado::prioridade::Prioridade;
+use crate::chamado::service::SlaService;
+use crate::core::calendario::Calendario;
+
+/// Situação do chamado em relação aos prazos; a pior entre resposta e resolução
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(i32)]
+pub enum SituacaoSla {
+    NoPrazo = 0,
+    EmRisco = 1,
+    Violado = 2,
+}
+
+impl SituacaoSla {
+    pub fn to_i32(self) -> i32 {
+        self as i32
+    }
+}
+
+impl std::fmt::Display for SituacaoSla {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            SituacaoSla::NoPrazo => "No prazo",
+            SituacaoSla::EmRisco => "Em risco",
+            SituacaoSla::Violado => "Violado",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// Prazos em minutos da política aplicada (serviço ou, na falta, tipo)
+#[derive(Debug, Clone, Copy)]
+pub struct PoliticaSla {
+    pub prazo_resposta_min: i32,
+    pub prazo_resolucao_min: i32,
+    pub alerta_pct: i32,
+}
+
+impl PoliticaSla {
+    /// Prazos escalados pela prioridade do chamado (Crítica encurta, Baixa alonga); nunca abaixo de 1 minuto
+    pub fn para_prioridade(self, prioridade: Prioridade) -> Self {
+        let escala = |minutos: i32| (minutos * prioridade.fator_prazo_pct() / 100).max(1);
+        Self {
+            prazo_resposta_min: escala(self.prazo_resposta_min),
+            prazo_resolucao_min: escala(self.prazo_resolucao_min),
+            alerta_pct: self.alerta_pct,
+        }
+    }
+}
+
+/// Marcos do chamado tirados do histórico de status
+#[derive(Debug, Clone, Copy)]
+pub struct MarcosSla {
+    pub aberto_em: DateTime<Utc>,
+    /// primeira entrada em Em Atendimento
+    pub respondido_em: Option<DateTime<Utc>>,
+    /// entrada em Resolvido (ou Fechado direto, no cancelamento)
+    pub resolvido_em: Option<DateTime<Utc>>,
+    /// tempo útil dos trechos em Pausado, inclusive o atual
+    pub pausado: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalculoSla {
+    pub prazo_resposta: DateTime<Utc>,
+    pub prazo_resolucao: DateTime<Utc>,
+    pub situacao: SituacaoSla,
+}
+
+/*
+prazo de resposta conta da abertura até o início do atendimento;
+o de resolução, da abertura até Resolvido, e o tempo Pausado empurra o prazo
+prazos e tempo consumido contam só o expediente do calendário
+*/
+pub fn calcular(
+    politica: &PoliticaSla,
+    marcos: &MarcosSla,
+    calendario: &Calendario,
+    agora: DateTime<Utc>,
+) -> CalculoSla {
+    let alvo_resposta = Duration::minutes(politica.prazo_resposta_min.into());
+    let alvo_resolucao = Duration::minutes(politica.prazo_resolucao_min.into());
+
+    // cancelado antes do atendimento também encerra o prazo de resposta
+    let fim_resposta = marcos.respondido_em.or(marcos.resolvido_em);
+    let resposta = situacao(
+        calendario.tempo_util(marcos.aberto_em, fim_resposta.unwrap_or(agora)),
+        alvo_resposta,
+        fim_resposta.is_some(),
+        politica.alerta_pct,
+    );
+    let resolucao = situacao(
+        calendario.tempo_util(marcos.aberto_em, marcos.resolvido_em.unwrap_or(agora))
+            - marcos.pausado,
+        alvo_resolucao,
+        marcos.resolvido_em.is_some(),
+        politica.alerta_pct,
+    );
+
+    CalculoSla {
+        prazo_resposta: calendario.somar(marcos.aberto_em, alvo_resposta),
+        prazo_resolucao: calendario.somar(marcos.aberto_em, alvo_resolucao + marcos.pausado),
+        situacao: resposta.max(resolucao),
+    }
+}
+
+/// Depois de concluído só importa se estourou; "em risco" vale enquanto corre
+fn situacao(consumido: Duration, alvo: Duration, concluido: bool, alerta_pct: i32) -> SituacaoSla {
+    if consumido > alvo {
+        SituacaoSla::Violado
+    } else if !concluido && consumido * 100 >= alvo * alerta_pct {
+        SituacaoSla::EmRisco
+    } else {
+        SituacaoSla::NoPrazo
+    }
+}
+
+/// Lê `SLA_INTERVALO_SECS` (padrão 60s)
+fn intervalo_verificacao() -> std::time::Duration {
+    std::env::var("SLA_INTERVALO_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(std::time::Duration::from_secs(60))
+}
+
+/*
+verificador em segundo plano (ver utils::shutdown::Workers):
+recalcula os chamados em aberto para que "em risco" e "violado" avancem
+mesmo sem nenhuma transição de status
+*/
+pub async fn verificador(pool: PgPool, token: CancellationToken) {
+    let mut intervalo = tokio::time::interval(intervalo_verificacao());
+    let service = SlaService::new();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = intervalo.tick() => {}
+        }
+
+        match service.verificar(&pool).await {
+            Ok(violados) if !violados.is_empty() => {
+                warn!("SLA violado nos chamados {:?}", violados)
+            }
+            Ok(_) => {}
+            Err(e) => error!("falha ao verificar SLA: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLITICA: PoliticaSla = PoliticaSla {
+        prazo_resposta_min: 60,
+        prazo_resolucao_min: 480,
+        alerta_pct: 80,
+    };
+
+    /// sem expediente cadastrado: tempo corrido
+    fn corrido() -> Calendario {
+        Calendario::new()
+    }
+
+    fn marcos(aberto_em: DateTime<Utc>) -> MarcosSla {
+        MarcosSla {
+            aberto_em,
+            respondido_em: None,
+            resolvido_em: None,
+            pausado: Duration::zero(),
+        }
+    }
+
+    #[test]
+    fn test_resposta_em_risco_e_violada() {
+        let agora = Utc::now();
+
+        let calculo = calcular(
+            &POLITICA,
+            &marcos(agora - Duration::minutes(50)),
+            &corrido(),
+            agora,
+        );
+        assert_eq!(calculo.situacao, SituacaoSla::EmRisco);
+
+        let calculo = calcular(
+            &POLITICA,
+            &marcos(agora - Duration::minutes(61)),
+            &corrido(),
+            agora,
+        );
+        assert_eq!(calculo.situacao, SituacaoSla::Violado);
+    }
+
+    #[test]
+    fn test_prazos_pela_prioridade() {
+        let critica = POLITICA.para_prioridade(Prioridade::Critica);
+        assert_eq!(
+            (critica.prazo_resposta_min, critica.prazo_resolucao_min),
+            (15, 120)
+        );
+
+        let baixa = POLITICA.para_prioridade(Prioridade::Baixa);
+        assert_eq!(
+            (baixa.prazo_resposta_min, baixa.prazo_resolucao_min),
+            (90, 720)
+        );
+
+        let curta = PoliticaSla {
+            prazo_resposta_min: 1,
+            ..POLITICA
+        };
+        assert_eq!(
+            curta
+                .para_prioridade(Prioridade::Critica)
+                .prazo_resposta_min,
+            1
+        );
+    }
+
+    #[test]
+    fn test_pausa_empurra_prazo_de_resolucao() {
+        let agora = Utc::now();
+        let aberto_em = agora - Duration::minutes(500);
+        let m = MarcosSla {
+            respondido_em: Some(aberto_em + Duration::minutes(10)),
+            pausado: Duration::minutes(120),
+            ..marcos(aberto_em)
+        };
+
+        let calculo = calcular(&POLITICA, &m, &corrido(), agora);
+        assert_eq!(calculo.prazo_resolucao, aberto_em + Duration::minutes(600));
+        // 380 de 480 minutos úteis: abaixo de 80%
+        assert_eq!(calculo.situacao, SituacaoSla::NoPrazo);
+    }
+
+    #[test]
+    fn test_concluido_no_prazo_nao_fica_em_risco() {
+        let aberto_em = Utc::now() - Duration::days(2);
+        let m = MarcosSla {
+            respondido_em: Some(aberto_em + Duration::minutes(55)),
+            resolvido_em: Some(aberto_em + Duration::minutes(470)),
+            ..marcos(aberto_em)
+        };
+
+        assert_eq!(
+            calcular(&POLITICA, &m, &corrido(), Utc::now()).situacao,
+            SituacaoSla::NoPrazo
+        );
+    }
+
+    #[test]
+    fn test_prazo_conta_so_o_expediente() {
+        use chrono::{NaiveTime, TimeZone, Weekday};
+
+        let cal = Calendario::new().faixa(
+            Weekday::Fri,
+            NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+        );
+        // sexta 17/10/2025 17:00 (UTC-3); resposta em 1h, resolução em 8h
+        let aberto_em = Utc.with_ymd_and_hms(2025, 10, 17, 20, 0, 0).unwrap();
+        let calculo = calcular(&POLITICA, &marcos(aberto_em), &cal, aberto_em);
+
+        assert_eq!(calculo.prazo_resposta, aberto_em + Duration::hours(1));
+        // só há expediente às sextas: 1h nesta e 7h na próxima
+        assert_eq!(
+            calculo.prazo_resolucao,
+            Utc.with_ymd_and_hms(2025, 10, 24, 18, 0, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::{Stream, stream};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, postgres::PgListener};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::chamado::transicao::Papel;
+
+/// Canal do Postgres preenchido pelo gatilho de chamado_chamados (migração chamado_eventos)
+const CANAL_PG: &str = "chamado_eventos";
+
+/// Eventos guardados para quem está lendo devagar; acima disso o leitor recarrega a lista
+const CAPACIDADE: usize = 256;
+
+/// Espera antes de tentar reconectar o LISTEN
+const ESPERA_RECONEXAO: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoEvento {
+    Criado,
+    Atualizado,
+    Excluido,
+}
+
+/// Mudança em um chamado, como enviada pelo pg_notify
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct EventoChamado {
+    pub tipo: TipoEvento,
+    pub id: i64,
+    pub user_solic_id: i64,
+    pub status: i32,
+}
+
+impl EventoChamado {
+    /*
+    quem recebe o evento na listagem
+      atendente: chamados novos de qualquer solicitante
+      todos: mudanças nos próprios chamados
+    */
+    pub fn visivel(&self, papel: Papel, user_id: i64) -> bool {
+        (papel == Papel::Atendente && self.tipo == TipoEvento::Criado)
+            || self.user_solic_id == user_id
+    }
+}
+
+/*
+repassa os eventos do LISTEN para as conexões SSE desta instância
+o token encerra os streams abertos junto com o servidor
+*/
+#[derive(Clone)]
+pub struct CanalChamados {
+    tx: broadcast::Sender<EventoChamado>,
+    token: CancellationToken,
+}
+
+impl CanalChamados {
+    pub fn new(token: CancellationToken) -> Self {
+        let (tx, _) = broadcast::channel(CAPACIDADE);
+        Self { tx, token }
+    }
+
+    /// Stream SSE com os eventos visíveis para o usuário
+    pub fn assinar(
+        &self,
+        papel: Papel,
+        user_id: i64,
+    ) -> Sse<impl Stream<Item = Result<Event, Infallible>> + use<>> {
+        let estado = (self.tx.subscribe(), self.token.clone());
+
+        let eventos = stream::unfold(estado, move |(mut rx, token)| async move {
+            loop {
+                let recebido = tokio::select! {
+                    _ = token.cancelled() => return None,
+                    recebido = rx.recv() => recebido,
+                };
+
+                let evento = match recebido {
+                    Ok(evento) if evento.visivel(papel, user_id) => Event::default()
+                        .event("chamado")
+                        .json_data(&evento)
+                        .unwrap_or_default(),
+                    Ok(_) => continue,
+                    // perdeu eventos: a página busca a lista de novo
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        Event::default().event("recarregar").data("")
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                };
+
+                return Some((Ok(evento), (rx, token)));
+            }
+        });
+
+        Sse::new(eventos).keep_alive(KeepAlive::default())
+    }
+}
+
+/// Tarefa em segundo plano: LISTEN chamado_eventos, reconectando quando a conexão cai
+pub async fn ouvinte(pool: PgPool, canal: CanalChamados, token: CancellationToken) {
+    loop {
+        let mut listener = match conectar(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("falha ao escutar {}: {}", CANAL_PG, e);
+                tokio::select! {
+                    _ = token.cancelled() => return,
+                    _ = tokio::time::sleep(ESPERA_RECONEXAO) => continue,
+                }
+            }
+        };
+        info!("escutando {}", CANAL_PG);
+
+        loop {
+            let notificacao = tokio::select! {
+                _ = token.cancelled() => return,
+                notificacao = listener.recv() => notificacao,
+            };
+
+            match notificacao {
+                Ok(notificacao) => {
+                    match serde_json::from_str::<EventoChamado>(notificacao.payload()) {
+                        // sem conexões SSE abertas o envio falha, e tudo bem
+                        Ok(evento) => {
+                            let _ = canal.tx.send(evento);
+                        }
+                        Err(e) => warn!(
+                            "evento de chamado inválido '{}': {}",
+                            notificacao.payload(),
+                            e
+                        ),
+                    }
+                }
+                Err(e) => {
+                    warn!("conexão do {} perdida: {}", CANAL_PG, e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn conectar(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(CANAL_PG).await?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evento(tipo: TipoEvento, user_solic_id: i64) -> EventoChamado {
+        EventoChamado {
+            tipo,
+            id: 1,
+            user_solic_id,
+            status: 0,
+        }
+    }
+
+    #[test]
+    fn test_payload_do_gatilho() {
+        let payload = r#"{"tipo" : "criado", "id" : 7, "user_solic_id" : 3, "status" : 0}"#;
+        let evento: EventoChamado = serde_json::from_str(payload).unwrap();
+
+        assert_eq!(evento.tipo, TipoEvento::Criado);
+        assert_eq!(evento.id, 7);
+        assert_eq!(evento.user_solic_id, 3);
+    }
+
+    #[test]
+    fn test_visibilidade() {
+        // atendente vê os novos de todos, e as mudanças só dos próprios
+        assert!(evento(TipoEvento::Criado, 3).visivel(Papel::Atendente, 9));
+        assert!(!evento(TipoEvento::Atualizado, 3).visivel(Papel::Atendente, 9));
+        assert!(evento(TipoEvento::Atualizado, 9).visivel(Papel::Atendente, 9));
+
+        // solicitante vê só os próprios
+        assert!(!evento(TipoEvento::Criado, 3).visivel(Papel::Solicitante, 9));
+        assert!(evento(TipoEvento::Excluido, 9).visivel(Papel::Solicitante, 9));
+    }
+
+    #[test]
+    fn test_lista_do_atendente_inclui_chamado_novo_de_outro() {
+        // o evento que chega ao atendente precisa estar na lista que a página busca de novo
+        let novo = evento(TipoEvento::Criado, 3);
+        let dono = Papel::Atendente.somente_do_usuario(9);
+
+        assert!(novo.visivel(Papel::Atendente, 9));
+        assert!(dono.is_none_or(|dono| dono == novo.user_solic_id));
+
+        // o solicitante continua vendo só os próprios
+        assert_eq!(Papel::Solicitante.somente_do_usuario(9), Some(9));
+    }
+}
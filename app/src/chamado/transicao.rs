@@ -0,0 +1,168 @@
+use shared::AppError;
+
+use crate::chamado::StatusChamado;
+use crate::core::StatusOpt;
+use crate::middlewares::CurrentUser;
+
+/// Quem pede a mudança de status: o dono do chamado ou quem atende (chamado_admin)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Papel {
+    Solicitante,
+    Atendente,
+}
+
+impl Papel {
+    pub fn do_usuario(current_user: &CurrentUser) -> Self {
+        if current_user.current_user.is_superuser
+            || current_user
+                .permissions
+                .iter()
+                .any(|p| p == "chamado_admin")
+        {
+            Papel::Atendente
+        } else {
+            Papel::Solicitante
+        }
+    }
+
+    /// Dono a filtrar nas listagens: quem atende vê todos os chamados, o solicitante só os próprios
+    pub fn somente_do_usuario(self, user_id: i64) -> Option<i64> {
+        match self {
+            Papel::Atendente => None,
+            Papel::Solicitante => Some(user_id),
+        }
+    }
+}
+
+const SOLICITANTE: &[Papel] = &[Papel::Solicitante, Papel::Atendente];
+const ATENDENTE: &[Papel] = &[Papel::Atendente];
+
+/*
+tabela de transições (de, para, quem pode)
+o solicitante só cancela enquanto Aberto e confirma ou reabre depois de Resolvido;
+o restante do fluxo é de quem atende
+*/
+const TRANSICOES: &[(StatusChamado, StatusChamado, &[Papel])] = &[
+    (
+        StatusChamado::Aberto,
+        StatusChamado::EmAtendimento,
+        ATENDENTE,
+    ),
+    (StatusChamado::Aberto, StatusChamado::Fechado, SOLICITANTE),
+    (
+        StatusChamado::EmAtendimento,
+        StatusChamado::Pausado,
+        ATENDENTE,
+    ),
+    (
+        StatusChamado::EmAtendimento,
+        StatusChamado::Resolvido,
+        ATENDENTE,
+    ),
+    (
+        StatusChamado::Pausado,
+        StatusChamado::EmAtendimento,
+        ATENDENTE,
+    ),
+    (StatusChamado::Pausado, StatusChamado::Resolvido, ATENDENTE),
+    (
+        StatusChamado::Resolvido,
+        StatusChamado::Fechado,
+        SOLICITANTE,
+    ),
+    (
+        StatusChamado::Resolvido,
+        StatusChamado::EmAtendimento,
+        SOLICITANTE,
+    ),
+];
+
+impl StatusChamado {
+    /// Manter o mesmo status não é transição e sempre é aceito
+    pub fn pode_ir_para(self, para: StatusChamado, papel: Papel) -> bool {
+        self == para
+            || TRANSICOES
+                .iter()
+                .any(|(de, ate, papeis)| *de == self && *ate == para && papeis.contains(&papel))
+    }
+
+    /// Status para onde o papel pode levar o chamado a partir deste
+    pub fn proximos(self, papel: Papel) -> Vec<StatusChamado> {
+        TRANSICOES
+            .iter()
+            .filter(|(de, _, papeis)| *de == self && papeis.contains(&papel))
+            .map(|(_, ate, _)| *ate)
+            .collect()
+    }
+
+    pub fn validar_transicao(self, para: StatusChamado, papel: Papel) -> Result<(), AppError> {
+        if self.pode_ir_para(para, papel) {
+            Ok(())
+        } else {
+            Err(AppError::Conflict(format!(
+                "Chamado não pode passar de {} para {}",
+                self, para
+            )))
+        }
+    }
+
+    /// Somente chamados ainda em Aberto podem ser excluídos
+    pub fn pode_excluir(self) -> bool {
+        self == StatusChamado::Aberto
+    }
+
+    /*
+    para o select do atendimento: o status atual seguido dos permitidos
+    */
+    pub fn opcoes_transicao(self, papel: Papel) -> Vec<StatusOpt> {
+        std::iter::once(self)
+            .chain(self.proximos(papel))
+            .map(|s| StatusOpt {
+                value: s as i32,
+                label: s.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fluxo_atendente() {
+        use StatusChamado::*;
+
+        assert!(Aberto.pode_ir_para(EmAtendimento, Papel::Atendente));
+        assert!(EmAtendimento.pode_ir_para(Pausado, Papel::Atendente));
+        assert!(Pausado.pode_ir_para(Resolvido, Papel::Atendente));
+        assert!(Resolvido.pode_ir_para(Fechado, Papel::Atendente));
+        assert!(!Aberto.pode_ir_para(Resolvido, Papel::Atendente));
+        assert!(!Fechado.pode_ir_para(Aberto, Papel::Atendente));
+    }
+
+    #[test]
+    fn test_fluxo_solicitante() {
+        use StatusChamado::*;
+
+        assert!(Aberto.pode_ir_para(Fechado, Papel::Solicitante));
+        assert!(Resolvido.pode_ir_para(EmAtendimento, Papel::Solicitante));
+        assert!(!Aberto.pode_ir_para(EmAtendimento, Papel::Solicitante));
+        assert!(!EmAtendimento.pode_ir_para(Resolvido, Papel::Solicitante));
+        assert_eq!(Fechado.proximos(Papel::Solicitante), vec![]);
+    }
+
+    #[test]
+    fn test_mesmo_status_sempre_aceito() {
+        assert!(
+            StatusChamado::Fechado
+                .validar_transicao(StatusChamado::Fechado, Papel::Solicitante)
+                .is_ok()
+        );
+        assert!(
+            StatusChamado::Aberto
+                .validar_transicao(StatusChamado::Pausado, Papel::Atendente)
+                .is_err()
+        );
+    }
+}
@@ -1,314 +1,61 @@
 use anyhow::Error;
-use std::collections::HashMap;
 use tokio::fs;
 
 use axum::{
     Extension, Form, Json,
     extract::{Multipart, Path, Query, State},
-    http::StatusCode,
-    response::{Html, IntoResponse, Redirect},
+    response::{Html, IntoResponse, Response},
 };
 
 use chrono::{Datelike, Local};
 use minijinja::context;
-use shared::{FlashStatus, ListParams, PaginatedResponse, PaginationQuery, SharedState, helpers};
+use shared::export::{self, ColumnKind, ExportConfig, ExportParams};
+use shared::{
+    AppError, ConflictError, ErrorResponse, FlashStatus, ListParams, PaginatedResponse,
+    PaginationQuery, SharedState, flash_redirect, helpers, mensagem_erro,
+};
 use tower_sessions::service;
-use tracing::debug;
 use uuid::Uuid;
 
 use crate::{
     chamado::{
-        StatusChamado,
+        StatusChamado, anexo, status_filter,
+        prioridade::{Nivel, Prioridade},
+        tempo_real::CanalChamados,
+        transicao::Papel,
         model::{CategoriaChamado, ServicoChamado, TipoChamado},
         schema::{
-            CreateCategoriaChamadoSchema, CreateChamado, CreateGerenciamentoChamado,
-            CreateServicoChamadoSchema, CreateTipoChamadoSchema, UpdateCategoriaChamadoSchema,
-            UpdateChamado, UpdateGerenciamentoChamado, UpdateServicoChamadoSchema,
-            UpdateTipoChamadoSchema,
+            AlterarStatusChamado, CreateAnexoComentario, CreateChamado, CreateComentario, CreateGerenciamentoChamado,
+            CreateServicoChamadoSchema, TransferirChamado, UpdateChamado, UpdateGerenciamentoChamado,
+            UpdateServicoChamadoSchema,
         },
         service::{
-            CategoriaService, ChamadoService, GerenciamentoChamadoService, ServicoService,
-            TipoChamadoService,
+            CategoriaService, ChamadoService, ComentarioChamadoService, FilaService,
+            GerenciamentoChamadoService, ServicoService, SlaService, TipoChamadoService,
         },
     },
     middlewares::CurrentUser,
     permissao::UserService,
+    utils::conflito::conflito_response,
 };
 
-pub async fn list_tipo_chamado(
-    State(state): State<SharedState>,
-    Query(params): Query<ListParams>,
-) -> impl IntoResponse {
-    let service = TipoChamadoService::new();
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-    let mut context = minijinja::context! {};
-
-    context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    // Usar o PermissionService para buscar dados paginados
-    let permissions_result = service
-        .get_paginated(
-            &state.db,
-            params.find.as_deref(),
-            params.page.unwrap_or(1),
-            params.page_size.unwrap_or(10),
-        )
-        .await;
-
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-
-            match state.templates.get_template("chamado/tipo_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar permissions: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/",
-                &format!("Erro ao carregar permissions: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
-}
-
-pub async fn show_tipo_form(
-    State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    match state.templates.get_template("chamado/tipo_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
-}
-
-pub async fn get_tipo(
-    State(state): State<SharedState>,
-    Path(id): Path<i64>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    let service = TipoChamadoService::new();
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    // Carregar o template
-    let template = match state.templates.get_template("chamado/tipo_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
-
-    let perfil = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar tipo: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/chamado/tipo-form",
-                &format!("tipo não encontrado: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
-
-    // Preparar o contexto
-    let ctx = context! {
-        row => perfil,
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
-}
-
-pub async fn create_tipo(
-    State(state): State<SharedState>,
-    Form(body): Form<CreateTipoChamadoSchema>,
-) -> impl IntoResponse {
-    let service = TipoChamadoService::new();
-
-    match service.get_by_name(&*state.db, body.nome.clone()).await {
-        Ok(tipo_existente) if tipo_existente.id > 0 => {
-            let flash_url =
-                helpers::create_flash_url("/chamado/tipo", "Tipo existe!", FlashStatus::Error);
-            return Redirect::to(&flash_url).into_response();
-        }
-        _ => {
-            // Não existe, pode criar
-            match service.create(&*state.db, body).await {
-                Ok(_) => {
-                    let flash_url = helpers::create_flash_url(
-                        "/chamado/tipo",
-                        "Tipo criado com sucesso!",
-                        FlashStatus::Success,
-                    );
-                    Redirect::to(&flash_url).into_response()
-                }
-                Err(err) => {
-                    let flash_url = helpers::create_flash_url(
-                        "/chamado/tipo-form",
-                        &format!("Erro ao criar Tipo: {}", err),
-                        FlashStatus::Error,
-                    );
-                    Redirect::to(&flash_url).into_response()
-                }
-            }
-        }
-    }
-}
-
-pub async fn update_tipo(
-    State(state): State<SharedState>,
-    Path(id): Path<i64>,
-    Form(input): Form<UpdateTipoChamadoSchema>,
-) -> impl IntoResponse {
-    let service = TipoChamadoService::new();
-
-    match service.update(&*state.db, id, input).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/tipo"),
-                &format!("Tipo atualizado com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/tipo-form/{}", id),
-                &format!("Erro ao atualizar tipo: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-    }
-}
-
-pub async fn delete_tipo(
-    State(state): State<SharedState>,
-    Path(id): Path<i64>,
-) -> impl IntoResponse {
-    let service = TipoChamadoService::new();
-    match service.delete(&*state.db, id).await {
-        Ok(()) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/tipo",
-                "Tipo excluído com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/tipo",
-                &format!("Erro ao excluir tipo: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-    }
-}
-
+#[utoipa::path(
+    get,
+    path = "/tipo-api",
+    tag = "chamado",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<TipoChamado>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn tipo_list_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<TipoChamado>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<TipoChamado>>, AppError> {
     let service = TipoChamadoService::new();
-    let res = service
-        .get_paginated(
-            &state.db,
-            q.find.as_deref(),
-            q.page.unwrap_or(1) as i32,
-            q.page_size.unwrap_or(10) as i32,
-        )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let res = service.list(&state.db, &q.list_query()).await?;
 
     Ok(Json(res))
 }
@@ -321,284 +68,23 @@ pub async fn tipo_list_api(
 
 */
 
-pub async fn list_categoria(
-    State(state): State<SharedState>,
-    Query(params): Query<ListParams>,
-) -> impl IntoResponse {
-    let service = CategoriaService::new();
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let mut context = minijinja::context! {};
-
-    context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    // Usar o PermissionService para buscar dados paginados
-    let permissions_result = service
-        .get_paginated(
-            &state.db,
-            params.find.as_deref(),
-            params.page.unwrap_or(1),
-            params.page_size.unwrap_or(10),
-        )
-        .await;
-
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-
-            match state.templates.get_template("chamado/categoria_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar permissions: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/",
-                &format!("Erro ao carregar permissions: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
-}
-
-pub async fn show_categoria_form(
-    State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    match state.templates.get_template("chamado/categoria_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
-}
-
-pub async fn get_categoria(
-    State(state): State<SharedState>,
-    Path(id): Path<i64>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    let service = CategoriaService::new();
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    // Carregar o template
-    let template = match state.templates.get_template("chamado/categoria_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
-
-    let perfil = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar categoria: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/chamado/categoria-form",
-                &format!("categoria não encontrado: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
-
-    // Preparar o contexto
-    let ctx = context! {
-        row => perfil,
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
-}
-
-pub async fn create_categoria(
-    State(state): State<SharedState>,
-    Form(body): Form<CreateCategoriaChamadoSchema>,
-) -> impl IntoResponse {
-    let service = CategoriaService::new();
-
-    match service.get_by_name(&*state.db, body.nome.clone()).await {
-        Ok(categoria) if categoria.id > 0 => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/categoria",
-                "Categoria já existe!",
-                FlashStatus::Error,
-            );
-            return Redirect::to(&flash_url).into_response();
-        }
-        _ => {
-            // Não existe, pode criar
-            match service.create(&*state.db, body).await {
-                Ok(_) => {
-                    let flash_url = helpers::create_flash_url(
-                        "/chamado/categoria",
-                        "Categoria criado com sucesso!",
-                        FlashStatus::Success,
-                    );
-                    Redirect::to(&flash_url).into_response()
-                }
-                Err(err) => {
-                    let flash_url = helpers::create_flash_url(
-                        "/chamado/categoria-form",
-                        &format!("Erro ao criar Categoria: {}", err),
-                        FlashStatus::Error,
-                    );
-                    Redirect::to(&flash_url).into_response()
-                }
-            }
-        }
-    }
-}
-
-pub async fn update_categoria(
-    State(state): State<SharedState>,
-    Path(id): Path<i64>,
-    Form(input): Form<UpdateCategoriaChamadoSchema>,
-) -> impl IntoResponse {
-    let service = CategoriaService::new();
-
-    match service.update(&*state.db, id, input).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/categoria"),
-                &format!("Categoria atualizado com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/categoria-form/{}", id),
-                &format!("Erro ao atualizar categoria: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-    }
-}
-
-pub async fn delete_categoria(
-    State(state): State<SharedState>,
-    Path(id): Path<i64>,
-) -> impl IntoResponse {
-    let service = CategoriaService::new();
-    match service.delete(&*state.db, id).await {
-        Ok(()) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/categoria",
-                "Categoria excluído com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/categoria",
-                &format!("Erro ao excluir categoria: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-    }
-}
-
+#[utoipa::path(
+    get,
+    path = "/categoria-api",
+    tag = "chamado",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<CategoriaChamado>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn categoria_list_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<CategoriaChamado>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<CategoriaChamado>>, AppError> {
     let service = CategoriaService::new();
-    let res = service
-        .get_paginated(
-            &state.db,
-            q.find.as_deref(),
-            q.page.unwrap_or(1) as i32,
-            q.page_size.unwrap_or(10) as i32,
-        )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let res = service.list(&state.db, &q.list_query()).await?;
 
     Ok(Json(res))
 }
@@ -614,176 +100,61 @@ pub async fn categoria_list_api(
 pub async fn list_servico(
     State(state): State<SharedState>,
     Query(params): Query<ListParams>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service = ServicoService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let mut context = minijinja::context! {};
-
-    context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    let permissions_result = service
+    let paginated_response = service
         .get_paginated_with_tipo(
             &state.db,
             params.find.as_deref(),
             params.page.unwrap_or(1),
             params.page_size.unwrap_or(10),
         )
-        .await;
+        .await?;
 
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.unwrap_or_default(),
+    };
 
-            match state.templates.get_template("chamado/servico_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar serviços: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/",
-                &format!("Erro ao carregar permissions: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
+    helpers::render(&state.templates, "chamado/servico_list.html", context)
 }
 
 pub async fn show_servico_form(
     State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    match state.templates.get_template("chamado/servico_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+) -> Result<Html<String>, AppError> {
+    let context = minijinja::context! {};
+
+    helpers::render(&state.templates, "chamado/servico_form.html", context)
 }
 
 pub async fn get_servico(
     State(state): State<SharedState>,
     Path(id): Path<i64>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
+) -> Result<Html<String>, AppError> {
     let service = ServicoService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Carregar o template
-    let template = match state.templates.get_template("chamado/servico_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
+    let template = state.templates.get_template("chamado/servico_form.html")?;
 
-    let perfil = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar servico: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/chamado/servico-form",
-                &format!("servico não encontrado: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
+    let perfil = service.get_by_id(&state.db, id).await?;
 
     let tipo = TipoChamadoService::new()
         .get_by_id(&*state.db, perfil.tipo_id)
-        .await
-        .unwrap();
+        .await?;
 
     // Preparar o contexto
     let ctx = context! {
         row => perfil,
         tipo => tipo,
-        flash_message => flash_message,
-        flash_status => flash_status,
     };
 
     // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(Html(template.render(&ctx)?))
 }
 
 pub async fn create_servico(
@@ -794,32 +165,21 @@ pub async fn create_servico(
 
     match service.get_by_name(&*state.db, body.nome.clone()).await {
         Ok(servico) if servico.id > 0 => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/servico",
-                "Serviço já existe!",
-                FlashStatus::Error,
-            );
-            return Redirect::to(&flash_url).into_response();
+            flash_redirect("/chamado/servico", "Serviço já existe!", FlashStatus::Error)
         }
         _ => {
             // Não existe, pode criar
             match service.create(&*state.db, body).await {
-                Ok(_) => {
-                    let flash_url = helpers::create_flash_url(
-                        "/chamado/servico",
-                        "Serviço criado com sucesso!",
-                        FlashStatus::Success,
-                    );
-                    Redirect::to(&flash_url).into_response()
-                }
-                Err(err) => {
-                    let flash_url = helpers::create_flash_url(
-                        "/chamado/servico-form",
-                        &format!("Erro ao criar servico: {}", err),
-                        FlashStatus::Error,
-                    );
-                    Redirect::to(&flash_url).into_response()
-                }
+                Ok(_) => flash_redirect(
+                    "/chamado/servico",
+                    "Serviço criado com sucesso!",
+                    FlashStatus::Success,
+                ),
+                Err(err) => flash_redirect(
+                    "/chamado/servico-form",
+                    &format!("Erro ao criar servico: {}", mensagem_erro(err)),
+                    FlashStatus::Error,
+                ),
             }
         }
     }
@@ -833,22 +193,16 @@ pub async fn update_servico(
     let service = ServicoService::new();
 
     match service.update(&*state.db, id, input).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/servico"),
-                &format!("Serviço atualizado com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/servico-form/{}", id),
-                &format!("Erro ao atualizar serviço: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/chamado/servico",
+            "Serviço atualizado com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            &format!("/chamado/servico-form/{}", id),
+            &format!("Erro ao atualizar serviço: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -858,29 +212,34 @@ pub async fn delete_servico(
 ) -> impl IntoResponse {
     let service = ServicoService::new();
     match service.delete(&*state.db, id).await {
-        Ok(()) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/servico",
-                "Serviço excluído com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/servico",
-                &format!("Erro ao excluir serviço: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(()) => flash_redirect(
+            "/chamado/servico",
+            "Serviço excluído com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/chamado/servico",
+            &format!("Erro ao excluir serviço: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/servico-api",
+    tag = "chamado",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<ServicoChamado>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn servico_list_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<ServicoChamado>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<ServicoChamado>>, AppError> {
     let service = ServicoService::new();
     let res = service
         .get_paginated(
@@ -889,11 +248,7 @@ pub async fn servico_list_api(
             q.page.unwrap_or(1) as i32,
             q.page_size.unwrap_or(10) as i32,
         )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     Ok(Json(res))
 }
@@ -910,209 +265,157 @@ pub async fn list_chamado(
     Extension(current_user): Extension<CurrentUser>,
     State(state): State<SharedState>,
     Query(params): Query<ListParams>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service = ChamadoService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let mut context = minijinja::context! {};
-
-    context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
+    let list_query = params.list_query();
+
+    // mesma regra dos eventos ao vivo (tempo_real): quem atende vê todos, o solicitante os próprios
+    let paginated_response = service
+        .list_visiveis(
+            &state.db,
+            list_query.clone(),
+            Papel::do_usuario(&current_user),
+            current_user.current_user.id,
+        )
+        .await?;
+    let ids: Vec<i64> = paginated_response.data.iter().map(|c| c.id).collect();
+    let sla = SlaService::new().situacoes(&state.db, &ids).await?;
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        sla => sla,
+        is_atender => pode_atender(&current_user),
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        cursor => list_query.cursor,
+        next_cursor => paginated_response.next_cursor,
+        find => params.find.unwrap_or_default(),
+        ordem => minijinja::context! {
+            sort => list_query.sort,
+            dir => list_query.dir,
+            query => list_query.base_query_string(),
+        },
+        sort_query => list_query.sort_query_string(),
+        colunas_exportacao => chamado_export().options(),
     };
 
-    let permissions = current_user.permissions.clone();
+    helpers::render(&state.templates, "chamado/chamado_list.html", context)
+}
 
-    //verificar se permiter atender chamados
-    let can_attend = |perm| {
-        if permissions.contains(&perm) {
-            return true;
-        } else if current_user.current_user.is_superuser {
-            return true;
-        }
-        false
-    };
+/// verificar se permite atender chamados
+pub(super) fn pode_atender(current_user: &CurrentUser) -> bool {
+    Papel::do_usuario(current_user) == Papel::Atendente
+}
 
-    let result;
-    // verificar listagem somente chamados do usuario
-    if can_attend("chamado_admin".into()) {
-        result = service
-            .get_paginated_ownership(
-                &state.db,
-                params.find.as_deref(),
-                params.page.unwrap_or(1),
-                params.page_size.unwrap_or(10),
-                current_user.current_user.id,
-            )
-            .await;
-    } else {
-        result = service
-            .get_paginated(
-                &state.db,
-                params.find.as_deref(),
-                params.page.unwrap_or(1),
-                params.page_size.unwrap_or(10),
-            )
-            .await;
-    }
+/// Colunas da exportação de chamados
+fn chamado_export() -> ExportConfig {
+    ExportConfig::new("chamados")
+        .column("id", "ID", ColumnKind::Int)
+        .column("titulo", "Título", ColumnKind::Text)
+        .column_map("status", "Status", |v| {
+            v.as_i64()
+                .map(|status| status_filter(status as i32))
+                .unwrap_or_default()
+        })
+        .column_map("prioridade", "Prioridade", |v| {
+            v.as_i64()
+                .map(|prioridade| Prioridade::from_i32(prioridade as i32).to_string())
+                .unwrap_or_default()
+        })
+        .column("servico_id", "Serviço", ColumnKind::Int)
+        .column("tipo_id", "Tipo", ColumnKind::Int)
+        .column("user_solic_id", "Solicitante", ColumnKind::Int)
+        .column("created_at", "Aberto em", ColumnKind::DateTime)
+        .column("updated_at", "Atualizado em", ColumnKind::DateTime)
+}
 
-    match result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                is_atender => can_attend("chamado_admin".into()),
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
+/// Eventos da listagem em tempo real (SSE): a página recarrega a lista a cada evento
+pub async fn eventos_chamado(
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(canal): Extension<CanalChamados>,
+) -> impl IntoResponse {
+    canal.assinar(Papel::do_usuario(&current_user), current_user.current_user.id)
+}
 
-            match state.templates.get_template("chamado/chamado_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar serviços: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/",
-                &format!("Erro ao carregar chamados: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
+/// Exportação CSV/XLSX da listagem de chamados, com a mesma regra de visibilidade da tela
+pub async fn export_chamado(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Query(params): Query<ListParams>,
+    Query(pares): Query<Vec<(String, String)>>,
+) -> Result<Response, AppError> {
+    let export = ExportParams::parse(&pares)?;
+    let papel = Papel::do_usuario(&current_user);
+    let user_id = current_user.current_user.id;
+
+    let db = state.db.clone();
+    export::exportar(&chamado_export(), &export, params.list_query(), move |query| {
+        let db = db.clone();
+        async move {
+            ChamadoService::new()
+                .list_visiveis(&db, query, papel, user_id)
+                .await
         }
-    }
+    })
+    .await
 }
 
 pub async fn show_chamado_form(
     State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
+) -> Result<Html<String>, AppError> {
     let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
+        niveis => Nivel::opcoes(),
     };
 
-    match state.templates.get_template("chamado/chamado_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "chamado/chamado_form.html", context)
 }
 
 pub async fn get_chamado(
     State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
     Path(id): Path<i64>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
+) -> Result<Html<String>, AppError> {
     let service = ChamadoService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
+    if !ChamadoService::can_access(&current_user.current_user, id, &state.db).await {
+        return Err(AppError::PermissionDenied);
+    }
 
     // Carregar o template
-    let template = match state.templates.get_template("chamado/chamado_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
+    let template = state.templates.get_template("chamado/chamado_form.html")?;
 
-    let chamado = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar chamado: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/chamado/chamado-form",
-                &format!("chamado não encontrado: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
+    let chamado = service.get_by_id(&state.db, id).await?;
 
     let tipo = TipoChamadoService::new()
         .get_by_id(&*state.db, chamado.tipo_id)
-        .await
-        .unwrap();
+        .await?;
 
     let servico = ServicoService::new()
         .get_by_id(&*state.db, chamado.servico_id)
-        .await
-        .unwrap();
+        .await?;
+
+    let comentarios = ComentarioChamadoService::new()
+        .list_thread(&state.db, id, pode_atender(&current_user))
+        .await?;
+
+    let transicoes = StatusChamado::from_i32(chamado.status.unwrap_or_default())
+        .opcoes_transicao(Papel::do_usuario(&current_user));
 
     // Preparar o contexto
     let ctx = context! {
         row => chamado,
         tipo => tipo,
         servico => servico,
-        flash_message => flash_message,
-        flash_status => flash_status,
+        comentarios => comentarios,
+        pode_atender => pode_atender(&current_user),
+        extensoes_anexo => anexo::extensoes_aceitas(),
+        transicoes => transicoes,
     };
 
     // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(Html(template.render(&ctx)?))
 }
 
 pub async fn create_chamado(
@@ -1128,22 +431,16 @@ pub async fn create_chamado(
     body.status = Some(StatusChamado::Aberto as i32);
 
     match service.create(&*state.db, body).await {
-        Ok(chamado) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/chamado-form/{}", chamado.id),
-                "Chamado criado com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/chamado-form",
-                &format!("Erro ao criar chamado: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(chamado) => flash_redirect(
+            &format!("/chamado/chamado-form/{}", chamado.id),
+            "Chamado criado com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/chamado/chamado-form",
+            &format!("Erro ao criar chamado: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1158,31 +455,24 @@ pub async fn update_chamado(
     //verificar se o chamado é do usuario
     //ou administrador
     if !ChamadoService::can_access(&current_user.current_user, id, &*state.db).await {
-        let flash_url = helpers::create_flash_url(
-            &format!("/chamado/chamado"),
+        return flash_redirect(
+            "/chamado/chamado",
             &format!("Você não tem permissão para atualizar chamado: {}", id),
             FlashStatus::Error,
         );
-        return Redirect::to(&flash_url).into_response();
     }
 
     match service.update(&*state.db, id, input).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/chamado"),
-                &format!("Chamado atualizado com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/chamado-form/{}", id),
-                &format!("Erro ao atualizar chamado: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/chamado/chamado",
+            "Chamado atualizado com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            &format!("/chamado/chamado-form/{}", id),
+            &format!("Erro ao atualizar chamado: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1191,18 +481,22 @@ pub async fn upload_imagem(
     State(state): State<SharedState>,
     Path(id_chamado): Path<i64>,
     mut multipart: Multipart,
-) -> Json<serde_json::Value> {
+) -> Result<Json<serde_json::Value>, AppError> {
     //verificar se o chamado é do usuario
     if !ChamadoService::can_access(&current_user.current_user, id_chamado, &*state.db).await {
-        return Json(serde_json::json!({
+        return Ok(Json(serde_json::json!({
             "success": 0,
             "error": "Você não tem permissão para acessar este chamado."
-        }));
+        })));
     }
 
     let mut file_url = String::new();
 
-    while let Some(field) = multipart.next_field().await.unwrap() {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::InvalidInput(e.body_text()))?
+    {
         let name = field.name().unwrap_or_default();
 
         if name == "image" {
@@ -1249,12 +543,211 @@ pub async fn upload_imagem(
     }
 
     // Retorno esperado pelo Editor.js
-    Json(serde_json::json!({
+    Ok(Json(serde_json::json!({
         "success": if file_url.is_empty() { 0 } else { 1 },
         "file": {
             "url": file_url
         }
-    }))
+    })))
+}
+
+/*
+ muda o status pelo formulário do chamado (cancelar, confirmar ou reabrir)
+ as regras de quem pode o quê ficam em chamado::transicao
+*/
+pub async fn alterar_status_chamado(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Form(input): Form<AlterarStatusChamado>,
+) -> Result<Response, AppError> {
+    if !ChamadoService::can_access(&current_user.current_user, id, &state.db).await {
+        return Err(AppError::PermissionDenied);
+    }
+
+    let url_chamado = format!("/chamado/chamado-form/{}", id);
+    let status = StatusChamado::try_from(input.status)
+        .map_err(|e| AppError::Unprocessable(e.to_string()))?;
+
+    Ok(
+        match ChamadoService::new()
+            .alterar_status(
+                &*state.db,
+                id,
+                status,
+                Papel::do_usuario(&current_user),
+                current_user.current_user.id,
+                input.motivo,
+            )
+            .await
+        {
+            Ok(chamado) => flash_redirect(
+                &url_chamado,
+                &format!(
+                    "Chamado {}!",
+                    StatusChamado::from_i32(chamado.status.unwrap_or_default())
+                ),
+                FlashStatus::Success,
+            ),
+            Err(err) => flash_redirect(
+                &url_chamado,
+                &format!("Erro ao alterar status: {}", mensagem_erro(err)),
+                FlashStatus::Error,
+            ),
+        },
+    )
+}
+
+/*
+ novo comentário na conversa do chamado (multipart)
+ campos: corpo (documento EditorJS), interno, voltar e os arquivos em anexos
+ * solicitante ou quem atende; interno somente chamado_admin
+*/
+pub async fn create_comentario(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(chamado_id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<Response, AppError> {
+    if !ChamadoService::can_access(&current_user.current_user, chamado_id, &state.db).await {
+        return Err(AppError::PermissionDenied);
+    }
+
+    let mut voltar = format!("/chamado/chamado-form/{}", chamado_id);
+    let mut corpo = String::new();
+    let mut interno = false;
+    let mut anexos = Vec::new();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::InvalidInput(e.body_text()))?
+    {
+        match field.name().unwrap_or_default() {
+            // só volta para telas do próprio módulo
+            "voltar" => {
+                let valor = field.text().await.unwrap_or_default();
+                if valor.starts_with("/chamado/") {
+                    voltar = valor;
+                }
+            }
+            "corpo" => corpo = field.text().await.unwrap_or_default(),
+            "interno" => interno = field.text().await.unwrap_or_default() == "true",
+            "anexos" => {
+                let nome = field.file_name().unwrap_or_default().to_string();
+                if nome.is_empty() {
+                    continue; // input de arquivo enviado vazio
+                }
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::InvalidInput(e.body_text()))?;
+
+                // só guarda em memória; nada vai para o disco antes de tudo ser validado
+                let validacao = if anexos.len() == anexo::MAX_ANEXOS {
+                    Err(format!("Envie no máximo {} anexos por comentário.", anexo::MAX_ANEXOS))
+                } else {
+                    anexo::validar(&nome, &data)
+                };
+                match validacao {
+                    Ok(tipo) => anexos.push((nome, tipo, data)),
+                    Err(mensagem) => return Ok(flash_redirect(&voltar, &mensagem, FlashStatus::Error)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let corpo: serde_json::Value = match serde_json::from_str(&corpo) {
+        Ok(corpo) => corpo,
+        Err(_) => {
+            return Ok(flash_redirect(
+                &voltar,
+                "Escreva o comentário antes de enviar.",
+                FlashStatus::Error,
+            ));
+        }
+    };
+
+    let mut gravados = Vec::new();
+    let mut urls = Vec::new();
+    for (nome, tipo, data) in anexos {
+        match salvar_anexo(chamado_id, nome, tipo, &data).await {
+            Ok(gravado) => {
+                urls.push(gravado.url.clone());
+                gravados.push(gravado);
+            }
+            Err(err) => {
+                remover_anexos(&urls).await;
+                return Err(err);
+            }
+        }
+    }
+
+    let input = CreateComentario {
+        chamado_id,
+        autor_id: current_user.current_user.id,
+        corpo,
+        interno: interno && pode_atender(&current_user),
+    };
+
+    Ok(
+        match ComentarioChamadoService::new()
+            .create(&*state.db, input, gravados)
+            .await
+        {
+            Ok(_) => flash_redirect(&voltar, "Comentário enviado!", FlashStatus::Success),
+            Err(err) => {
+                remover_anexos(&urls).await;
+                flash_redirect(
+                    &voltar,
+                    &format!("Erro ao enviar comentário: {}", mensagem_erro(err)),
+                    FlashStatus::Error,
+                )
+            }
+        },
+    )
+}
+
+/// Grava o anexo já validado em uploads/chamado/{ano}/{mes}/{chamado}/anexos, como em upload_imagem
+async fn salvar_anexo(
+    chamado_id: i64,
+    nome: String,
+    tipo: &anexo::TipoAnexo,
+    data: &[u8],
+) -> Result<CreateAnexoComentario, AppError> {
+    let agora = Local::now();
+    let path_file = format!(
+        "uploads/chamado/{}/{}/{}/anexos",
+        agora.year(),
+        agora.month(),
+        chamado_id
+    );
+    fs::create_dir_all(&path_file)
+        .await
+        .map_err(|e| AppError::Internal(e.into()))?;
+
+    // nome em disco gerado, com a extensão validada; o original fica só no banco
+    let filename = format!("{}/{}.{}", path_file, Uuid::new_v4(), tipo.extensao);
+    fs::write(&filename, data)
+        .await
+        .map_err(|e| AppError::Internal(e.into()))?;
+
+    Ok(CreateAnexoComentario {
+        nome,
+        url: format!("/{}", filename),
+        tamanho: data.len() as i64,
+        content_type: Some(tipo.content_type.to_string()),
+    })
+}
+
+/// Apaga os arquivos gravados de um comentário que não foi salvo
+async fn remover_anexos(urls: &[String]) {
+    for url in urls {
+        if let Err(e) = fs::remove_file(url.trim_start_matches('/')).await {
+            tracing::warn!("anexo órfão {} não removido: {}", url, e);
+        }
+    }
 }
 
 pub async fn delete_chamado(
@@ -1266,12 +759,11 @@ pub async fn delete_chamado(
 
     //verificar se o chamado é do usuario
     if !ChamadoService::can_access(&current_user.current_user, id, &*state.db).await {
-        let flash_url = helpers::create_flash_url(
-            &format!("/chamado/chamado"),
+        return flash_redirect(
+            "/chamado/chamado",
             &format!("Você não tem permissão para deletar este chamado: {}", id),
             FlashStatus::Error,
         );
-        return Redirect::to(&flash_url).into_response();
     }
 
     match service.get_by_id(&state.db, id).await {
@@ -1280,51 +772,42 @@ pub async fn delete_chamado(
             let status = match chamado.status {
                 Some(status_code) => StatusChamado::from_i32(status_code),
                 None => {
-                    let flash_url = helpers::create_flash_url(
+                    return flash_redirect(
                         "/chamado/chamado",
                         "Status do chamado não encontrado.",
                         FlashStatus::Error,
                     );
-                    return Redirect::to(&flash_url).into_response();
                 }
             };
 
-            if status != StatusChamado::Aberto {
-                let flash_url = helpers::create_flash_url(
+            if !status.pode_excluir() {
+                return flash_redirect(
                     "/chamado/chamado",
                     "Chamado não pode ser excluído pois não está mais em Aberto.",
                     FlashStatus::Error,
                 );
-                return Redirect::to(&flash_url).into_response();
             }
         }
         Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/chamado"),
-                &format!("Erro ao obter chamado: {}", err),
+            return flash_redirect(
+                "/chamado/chamado",
+                &format!("Erro ao obter chamado: {}", mensagem_erro(err)),
                 FlashStatus::Error,
             );
-            return Redirect::to(&flash_url).into_response();
         }
     };
 
     match service.delete(&*state.db, id).await {
-        Ok(()) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/chamado",
-                "Chamado excluído com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/chamado",
-                &format!("Erro ao excluir chamado: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(()) => flash_redirect(
+            "/chamado/chamado",
+            "Chamado excluído com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/chamado/chamado",
+            &format!("Erro ao excluir chamado: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1344,7 +827,6 @@ se o chamado estiver em aberto inicia atendimento
 pub async fn inicia_atendimento_chamado(
     State(state): State<SharedState>,
     Extension(current_user): Extension<CurrentUser>,
-    Query(params): Query<HashMap<String, String>>,
     Path(chamado_id): Path<i64>,
 ) -> impl IntoResponse {
     let service_chamado = ChamadoService::new();
@@ -1353,12 +835,11 @@ pub async fn inicia_atendimento_chamado(
     let _chamado = if let Ok(chamado) = service_chamado.get_by_id(&state.db, chamado_id).await {
         chamado
     } else {
-        let flash_url = helpers::create_flash_url(
+        return flash_redirect(
             "/chamado/chamado",
             &format!("Chamado não encontrado: {}", chamado_id),
             FlashStatus::Error,
         );
-        return Redirect::to(&flash_url).into_response();
     };
 
     // busca se ja em atendimento
@@ -1375,32 +856,30 @@ pub async fn inicia_atendimento_chamado(
             user_atend_id: current_user.current_user.id,
             observacao_chamado: None,
         };
-        match service_atendimento.create(&state.db, gerenciamento).await {
+        match service_atendimento.create(&*state.db, gerenciamento).await {
             Ok(atendimento) => atendimento,
             Err(e) => {
-                let flash_url = helpers::create_flash_url(
+                return flash_redirect(
                     "/chamado/chamado",
-                    &format!("Erro ao iniciar atendimento: {}", e),
+                    &format!("Erro ao iniciar atendimento: {}", mensagem_erro(e)),
                     FlashStatus::Error,
                 );
-                return Redirect::to(&flash_url).into_response();
             }
         }
     };
 
-    let flash_url = helpers::create_flash_url(
+    flash_redirect(
         &format!("/chamado/chamado-atendimento/{}", atendimento.chamado_id),
         "Iniciado com sucesso",
         FlashStatus::Success,
-    );
-    Redirect::to(&flash_url).into_response()
+    )
 }
 
 pub async fn get_atendimento_chamado(
     State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
+    Extension(current_user): Extension<CurrentUser>,
     Path(chamado_id): Path<i64>,
-) -> Result<Html<String>, impl IntoResponse> {
+) -> Result<Html<String>, AppError> {
     let service_atendimento = GerenciamentoChamadoService::new();
     let service_chamado = ChamadoService::new();
     let service_user = UserService::new();
@@ -1410,98 +889,67 @@ pub async fn get_atendimento_chamado(
 
     let mut categoria: Option<CategoriaChamado> = None;
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Carregar o template
-    let template = match state
+    let template = state
         .templates
-        .get_template("chamado/atendimento_chamado.html")
-    {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
+        .get_template("chamado/atendimento_chamado.html")?;
 
-    let atendimento = match service_atendimento
+    let atendimento = service_atendimento
         .get_by_chamado_id(&state.db, chamado_id)
-        .await
-    {
-        Ok(atendimento) => atendimento,
-        Err(e) => {
-            let flash_url = helpers::create_flash_url(
-                "/chamado/chamado",
-                &format!("Chamado não encontrado: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
+        .await?;
 
-    let chamado = service_chamado
-        .get_by_id(&state.db, chamado_id)
-        .await
-        .unwrap();
+    let chamado = service_chamado.get_by_id(&state.db, chamado_id).await?;
 
     let user_atendimento = service_user
         .get_by_id(&state.db, atendimento.user_atend_id)
-        .await
-        .unwrap();
+        .await?;
 
-    if !atendimento.categoria_id.is_none() {
-        categoria = Some(
-            service_categoria
-                .get_by_id(&state.db, atendimento.categoria_id.unwrap())
-                .await
-                .unwrap(),
-        );
+    if let Some(categoria_id) = atendimento.categoria_id {
+        categoria = Some(service_categoria.get_by_id(&state.db, categoria_id).await?);
     }
 
-    let tipo = service_tipo
-        .get_by_id(&state.db, chamado.tipo_id)
-        .await
-        .unwrap();
+    let tipo = service_tipo.get_by_id(&state.db, chamado.tipo_id).await?;
 
     let servico = service_servico
         .get_by_id(&state.db, chamado.servico_id)
-        .await
-        .unwrap();
+        .await?;
+
+    let comentarios = ComentarioChamadoService::new()
+        .list_thread(&state.db, chamado_id, true)
+        .await?;
+
+    let (historico, tempos_status) = service_chamado.historico(&state.db, chamado_id).await?;
+    let sla = SlaService::new().get_by_chamado(&state.db, chamado_id).await?;
+
+    let service_fila = FilaService::new();
+    let atribuicao = service_fila.get_by_chamado(&state.db, chamado_id).await?;
+    let atribuicoes = service_fila.historico(&state.db, chamado_id).await?;
+    let membros_fila = service_fila.membros(&state.db, &chamado).await?;
 
     // Preparar o contexto
     let ctx = context! {
         row => atendimento,
+        atribuicao => atribuicao,
+        atribuicoes => atribuicoes,
+        membros_fila => membros_fila,
+        comentarios => comentarios,
+        historico => historico,
+        tempos_status => tempos_status,
+        sla => sla,
+        pode_atender => pode_atender(&current_user),
+        extensoes_anexo => anexo::extensoes_aceitas(),
         chamado => chamado,
         user_atendimento => user_atendimento,
         categoria => categoria,
         tipo => tipo,
         servico => servico,
-        status_options => StatusChamado::status_options(),
-        flash_message => flash_message,
-        flash_status => flash_status,
+        status_options => StatusChamado::from_i32(chamado.status.unwrap_or_default())
+            .opcoes_transicao(Papel::Atendente),
+        niveis => Nivel::opcoes(),
     };
 
     // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(Html(template.render(&ctx)?))
 }
 
 /*
@@ -1514,44 +962,123 @@ pub async fn update_atendimento_chamado(
     State(state): State<SharedState>,
     Extension(current_user): Extension<CurrentUser>,
     Path(atendimento_id): Path<i64>,
-    Form(input): Form<UpdateGerenciamentoChamado>,
-) -> impl IntoResponse {
+    Form(mut input): Form<UpdateGerenciamentoChamado>,
+) -> Result<Response, AppError> {
     if !current_user
         .permissions
         .contains(&"chamado_admin".to_string())
         && !current_user.current_user.is_superuser
     {
-        let flash_url = helpers::create_flash_url(
-            &format!("/chamado/chamado"),
-            &format!("Você não tem permissão para atualizar este atendimento!"),
+        return Ok(flash_redirect(
+            "/chamado/chamado",
+            "Você não tem permissão para atualizar este atendimento!",
             FlashStatus::Error,
-        );
-        return Redirect::to(&flash_url).into_response();
+        ));
     }
 
     let service = GerenciamentoChamadoService::new();
 
-    let atendimento = service.get_by_id(&*state.db, atendimento_id).await.unwrap();
+    let atendimento = service.get_by_id(&state.db, atendimento_id).await?;
+    let url_atendimento = format!("/chamado/chamado-atendimento/{}", atendimento.chamado_id);
+
+    input.updated_by = Some(current_user.current_user.id);
+    let enviado = serde_json::to_value(&input).unwrap_or_default();
 
-    match service
+    let resultado = service
         .update(&*state.db, atendimento_id, atendimento.chamado_id, input)
-        .await
-    {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/chamado-atendimento/{}", atendimento.chamado_id),
-                &format!("Atendimento atualizado com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        .await;
+
+    Ok(match resultado {
+        Ok(_) => flash_redirect(
+            &format!("/chamado/chamado-atendimento/{}", atendimento.chamado_id),
+            "Atendimento atualizado com sucesso!",
+            FlashStatus::Success,
+        ),
         Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/chamado/chamado-atendimento/{}", atendimento.chamado_id),
-                &format!("Erro ao atualizar atendimento: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
+            if let Some(conflito) = err.downcast_ref::<ConflictError>() {
+                conflito_response(&state, conflito, &enviado, &url_atendimento).await
+            } else {
+                flash_redirect(
+                    &url_atendimento,
+                    &format!("Erro ao atualizar atendimento: {}", mensagem_erro(err)),
+                    FlashStatus::Error,
+                )
+            }
         }
+    })
+}
+
+/*
+ transfere o chamado para outro membro da fila do serviço
+ * somente usuario chamado_admin (validado no router)
+*/
+pub async fn transferir_chamado(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(chamado_id): Path<i64>,
+    Form(input): Form<TransferirChamado>,
+) -> Response {
+    let url_atendimento = format!("/chamado/chamado-atendimento/{}", chamado_id);
+
+    match FilaService::new()
+        .transferir(
+            &*state.db,
+            chamado_id,
+            input.user_id,
+            current_user.current_user.id,
+            input.motivo,
+        )
+        .await
+    {
+        Ok(atribuicao) => flash_redirect(
+            &url_atendimento,
+            &format!("Chamado transferido para {}", atribuicao.user_nome),
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            &url_atendimento,
+            &format!("Erro ao transferir chamado: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
+
+/// Chamados atribuídos ao atendente e os que aguardam nas filas de que é membro
+pub async fn minha_fila(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Html<String>, AppError> {
+    let rows = FilaService::new()
+        .minha_fila(&state.db, current_user.current_user.id)
+        .await?;
+    let ids: Vec<i64> = rows.iter().map(|r| r.id).collect();
+    let sla = SlaService::new().situacoes(&state.db, &ids).await?;
+
+    let context = minijinja::context! {
+        rows => rows,
+        sla => sla,
+    };
+
+    helpers::render(&state.templates, "chamado/minha_fila.html", context)
+}
+
+/// Relatório de chamados com SLA violado
+pub async fn list_violacoes_sla(
+    State(state): State<SharedState>,
+    Query(params): Query<ListParams>,
+) -> Result<Html<String>, AppError> {
+    let list_query = params.list_query();
+    let paginated_response = SlaService::new()
+        .violacoes(&state.db, list_query.page, list_query.page_size)
+        .await?;
+
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+    };
+
+    helpers::render(&state.templates, "chamado/sla_violacoes.html", context)
+}
@@ -0,0 +1,573 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::{self, BufRead, Write},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use sqlx::{PgPool, migrate::Migrator};
+
+use crate::{
+    core::{MunicipioIbge, UfIbge, fetch_municipios, fetch_ufs, upsert_municipios, upsert_ufs},
+    permissao::UserService,
+};
+
+/// Migrações embutidas no binário (pasta `migrations` da raiz do workspace)
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+const USO: &str = "\
+Uso: app [COMANDO]
+
+Sem comando o servidor HTTP é iniciado.
+
+Comandos:
+  serve                             inicia o servidor HTTP
+  migrate                           aplica as migrações pendentes
+  create-superuser <usuario> <email> [nome completo]
+                                    cria um superusuário (senha lida de SUPERUSER_PASSWORD ou do terminal)
+  reset-otp <usuario>               gera um novo segredo OTP para o usuário
+  sync-ibge [--from-file <arquivo>] atualiza UFs e municípios pela API do IBGE ou por um JSON exportado dela
+  seed-dev                          popula o banco com dados de desenvolvimento
+  help                              mostra esta mensagem";
+
+/// Comandos aceitos pelo binário além do servidor HTTP
+#[derive(Debug, PartialEq, Eq)]
+pub enum Comando {
+    Serve,
+    Migrate,
+    CreateSuperuser {
+        username: String,
+        email: String,
+        full_name: Option<String>,
+    },
+    ResetOtp {
+        username: String,
+    },
+    SyncIbge {
+        from_file: Option<String>,
+    },
+    SeedDev,
+    Help,
+}
+
+impl Comando {
+    /// Interpreta os argumentos da linha de comando (sem o nome do binário)
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut args = args.iter().map(String::as_str);
+
+        let comando = match args.next() {
+            None | Some("serve") => Comando::Serve,
+            Some("migrate") => Comando::Migrate,
+            Some("create-superuser") => {
+                let username = args
+                    .next()
+                    .ok_or_else(|| anyhow!("informe o usuário"))?
+                    .to_string();
+                let email = args
+                    .next()
+                    .ok_or_else(|| anyhow!("informe o e-mail"))?
+                    .to_string();
+                let resto: Vec<&str> = args.by_ref().collect();
+                let full_name = if resto.is_empty() {
+                    None
+                } else {
+                    Some(resto.join(" "))
+                };
+                Comando::CreateSuperuser {
+                    username,
+                    email,
+                    full_name,
+                }
+            }
+            Some("reset-otp") => Comando::ResetOtp {
+                username: args
+                    .next()
+                    .ok_or_else(|| anyhow!("informe o usuário"))?
+                    .to_string(),
+            },
+            Some("sync-ibge") => match args.next() {
+                None => Comando::SyncIbge { from_file: None },
+                Some("--from-file") => Comando::SyncIbge {
+                    from_file: Some(
+                        args.next()
+                            .ok_or_else(|| anyhow!("informe o caminho do arquivo"))?
+                            .to_string(),
+                    ),
+                },
+                Some(outro) => bail!("opção desconhecida para sync-ibge: {}", outro),
+            },
+            Some("seed-dev") => Comando::SeedDev,
+            Some("help") | Some("--help") | Some("-h") => Comando::Help,
+            Some(outro) => bail!("comando desconhecido: {}", outro),
+        };
+
+        if let Some(sobra) = args.next() {
+            bail!("argumento inesperado: {}", sobra);
+        }
+
+        Ok(comando)
+    }
+}
+
+pub fn uso() -> &'static str {
+    USO
+}
+
+/// Executa um comando administrativo. `Serve` não é tratado aqui.
+pub async fn run(pool: &PgPool, comando: Comando) -> Result<()> {
+    match comando {
+        Comando::Serve => Ok(()),
+        Comando::Help => {
+            println!("{}", USO);
+            Ok(())
+        }
+        Comando::Migrate => migrate(pool).await,
+        Comando::CreateSuperuser {
+            username,
+            email,
+            full_name,
+        } => create_superuser(pool, &username, &email, full_name).await,
+        Comando::ResetOtp { username } => reset_otp(pool, &username).await,
+        Comando::SyncIbge { from_file } => sync_ibge(pool, from_file.as_deref()).await,
+        Comando::SeedDev => seed_dev(pool).await,
+    }
+}
+
+async fn migrate(pool: &PgPool) -> Result<()> {
+    MIGRATOR
+        .run(pool)
+        .await
+        .context("falha ao aplicar migrações")?;
+    println!(
+        "Migrações aplicadas ({} no total).",
+        MIGRATOR.iter().count()
+    );
+    Ok(())
+}
+
+/*
+cria o superusuario com senha informada
+a senha vem da variavel SUPERUSER_PASSWORD ou é lida do terminal
+*/
+async fn create_superuser(
+    pool: &PgPool,
+    username: &str,
+    email: &str,
+    full_name: Option<String>,
+) -> Result<()> {
+    let password = match env::var("SUPERUSER_PASSWORD") {
+        Ok(p) if !p.is_empty() => p,
+        _ => ler_linha("Senha: ")?,
+    };
+    if password.len() < 8 {
+        bail!("a senha deve ter no mínimo 8 caracteres");
+    }
+
+    let hash = UserService::get_password_hash(&password)
+        .map_err(|e| anyhow!("falha ao gerar hash da senha: {}", e))?;
+    let otp_base32 = UserService::random_base32();
+
+    let id = inserir_usuario(
+        pool,
+        username,
+        email,
+        full_name.as_deref().unwrap_or(username),
+        &hash,
+        &otp_base32,
+        true,
+    )
+    .await?;
+
+    println!("Superusuário '{}' criado (id {}).", username, id);
+    imprimir_otp(username, &otp_base32);
+    Ok(())
+}
+
+async fn reset_otp(pool: &PgPool, username: &str) -> Result<()> {
+    let user = UserService::get_by_username(pool, username)
+        .await
+        .with_context(|| format!("usuário '{}' não encontrado", username))?;
+
+    let user = UserService::update_otp(pool, user.id).await?;
+    let otp_base32 = user
+        .otp_base32
+        .ok_or_else(|| anyhow!("OTP não gravado para '{}'", username))?;
+
+    println!("OTP de '{}' redefinido.", username);
+    imprimir_otp(username, &otp_base32);
+    Ok(())
+}
+
+/*
+atualiza UFs e municipios
+sem arquivo consulta a API do IBGE, com arquivo lê o JSON de
+https://servicodados.ibge.gov.br/api/v1/localidades/municipios
+*/
+async fn sync_ibge(pool: &PgPool, from_file: Option<&str>) -> Result<()> {
+    let por_uf: Vec<(UfIbge, Vec<MunicipioIbge>)> = match from_file {
+        Some(caminho) => {
+            let conteudo = tokio::fs::read_to_string(caminho)
+                .await
+                .with_context(|| format!("falha ao ler {}", caminho))?;
+            let municipios: Vec<MunicipioIbge> = serde_json::from_str(&conteudo)
+                .with_context(|| format!("JSON do IBGE inválido em {}", caminho))?;
+            agrupar_por_uf(municipios)
+        }
+        None => {
+            let ufs = fetch_ufs().await.context("falha ao buscar UFs no IBGE")?;
+            let mut por_uf = Vec::with_capacity(ufs.len());
+            for uf in ufs {
+                let municipios = fetch_municipios(&uf.sigla)
+                    .await
+                    .with_context(|| format!("falha ao buscar municípios de {}", uf.sigla))?;
+                por_uf.push((uf, municipios));
+            }
+            por_uf
+        }
+    };
+
+    let ufs: Vec<UfIbge> = por_uf.iter().map(|(uf, _)| uf.clone()).collect();
+    upsert_ufs(pool, &ufs)
+        .await
+        .context("falha ao salvar UFs")?;
+
+    let mut total = 0;
+    for (uf, municipios) in por_uf {
+        total += municipios.len();
+        upsert_municipios(pool, municipios, &uf.id)
+            .await
+            .with_context(|| format!("falha ao salvar municípios de {}", uf.sigla))?;
+    }
+
+    println!("{} UFs e {} municípios atualizados.", ufs.len(), total);
+    Ok(())
+}
+
+/// Agrupa a lista nacional de municípios pela UF informada em cada registro
+fn agrupar_por_uf(municipios: Vec<MunicipioIbge>) -> Vec<(UfIbge, Vec<MunicipioIbge>)> {
+    let mut grupos: HashMap<i64, (UfIbge, Vec<MunicipioIbge>)> = HashMap::new();
+
+    for municipio in municipios {
+        let Some(uf) = municipio
+            .microrregiao
+            .as_ref()
+            .map(|micro| micro.mesorregiao.uf.clone())
+        else {
+            eprintln!(
+                "Município {} ({}) sem microrregião, ignorado.",
+                municipio.nome, municipio.id
+            );
+            continue;
+        };
+
+        grupos
+            .entry(uf.id)
+            .or_insert_with(|| (uf, Vec::new()))
+            .1
+            .push(municipio);
+    }
+
+    let mut grupos: Vec<_> = grupos.into_values().collect();
+    grupos.sort_by_key(|(uf, _)| uf.id);
+    grupos
+}
+
+/*
+dados minimos para desenvolvimento
+idempotente: pode ser executado varias vezes
+*/
+async fn seed_dev(pool: &PgPool) -> Result<()> {
+    let senha = env::var("DEV_PASSWORD").unwrap_or_else(|_| "Dev@12345".to_string());
+    let hash = UserService::get_password_hash(&senha)
+        .map_err(|e| anyhow!("falha ao gerar hash da senha: {}", e))?;
+
+    let mut tx = pool.begin().await?;
+
+    for modulo in ["permissao", "chamado", "externo", "core"] {
+        sqlx::query("INSERT INTO module (title) VALUES ($1) ON CONFLICT (title) DO NOTHING")
+            .bind(modulo)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    // (permissao, descricao, modulo)
+    let permissoes = [
+        ("admin", "Acesso administrativo", "permissao"),
+        ("chamado_user", "Abrir e acompanhar chamados", "chamado"),
+        ("chamado_admin", "Atender chamados", "chamado"),
+    ];
+    for (nome, descricao, modulo) in permissoes {
+        sqlx::query(
+            r#"
+            INSERT INTO permission (name, description, module_id)
+            SELECT $1, $2, m.id FROM module m WHERE m.title = $3
+            ON CONFLICT (name, module_id) DO NOTHING
+            "#,
+        )
+        .bind(nome)
+        .bind(descricao)
+        .bind(modulo)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // (perfil, permissoes)
+    let perfis: [(&str, &[&str]); 2] = [
+        ("Atendente", &["chamado_user", "chamado_admin"]),
+        ("Solicitante", &["chamado_user"]),
+    ];
+    for (perfil, permissoes) in perfis {
+        sqlx::query("INSERT INTO roles (name) VALUES ($1) ON CONFLICT (name) DO NOTHING")
+            .bind(perfil)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO role_permissions (role_id, permission_id)
+            SELECT r.id, p.id FROM roles r, permission p
+            WHERE r.name = $1 AND p.name = ANY($2)
+            ON CONFLICT (role_id, permission_id) DO NOTHING
+            "#,
+        )
+        .bind(perfil)
+        .bind(permissoes)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // (usuario, nome, superusuario, perfil)
+    let usuarios = [
+        ("admin", "Administrador", true, None),
+        (
+            "atendente",
+            "Atendente de Desenvolvimento",
+            false,
+            Some("Atendente"),
+        ),
+        (
+            "solicitante",
+            "Solicitante de Desenvolvimento",
+            false,
+            Some("Solicitante"),
+        ),
+    ];
+    let mut criados = Vec::new();
+    for (username, full_name, superuser, perfil) in usuarios {
+        let otp_base32 = UserService::random_base32();
+        let inserido: Option<(i64,)> = sqlx::query_as(
+            r#"
+            INSERT INTO users (username, password, email, full_name, otp_base32, is_active, is_staff, is_superuser)
+            VALUES ($1, $2, $3, $4, $5, true, $6, $6)
+            ON CONFLICT (username) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(username)
+        .bind(&hash)
+        .bind(format!("{}@dev.local", username))
+        .bind(full_name)
+        .bind(&otp_base32)
+        .bind(superuser)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(perfil) = perfil {
+            sqlx::query(
+                r#"
+                INSERT INTO user_roles (user_id, role_id)
+                SELECT u.id, r.id FROM users u, roles r
+                WHERE u.username = $1 AND r.name = $2
+                ON CONFLICT (user_id, role_id) DO NOTHING
+                "#,
+            )
+            .bind(username)
+            .bind(perfil)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if inserido.is_some() {
+            criados.push((username, otp_base32));
+        }
+    }
+
+    // (tipo, servicos)
+    let tipos: [(&str, &[&str]); 2] = [
+        ("Suporte", &["Instalação de software", "Acesso a sistemas"]),
+        ("Infraestrutura", &["Rede", "Impressoras"]),
+    ];
+    for (tipo, servicos) in tipos {
+        sqlx::query(
+            "INSERT INTO chamado_tipos_chamado (nome) VALUES ($1) ON CONFLICT (nome) DO NOTHING",
+        )
+        .bind(tipo)
+        .execute(&mut *tx)
+        .await?;
+
+        for servico in servicos {
+            sqlx::query(
+                r#"
+                INSERT INTO chamado_servico_chamado (nome, tipo_id)
+                SELECT $1, t.id FROM chamado_tipos_chamado t WHERE t.nome = $2
+                ON CONFLICT (tipo_id, nome) DO NOTHING
+                "#,
+            )
+            .bind(servico)
+            .bind(tipo)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    for categoria in ["Dúvida", "Incidente", "Requisição"] {
+        sqlx::query(
+            "INSERT INTO chamado_categoria_chamado (nome) VALUES ($1) ON CONFLICT (nome) DO NOTHING",
+        )
+        .bind(categoria)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // linha nao possui unique no nome
+    sqlx::query(
+        r#"
+        INSERT INTO linha (nome, permite_cnpj, permite_cpf, permite_avalista, valor_maximo)
+        SELECT 'Pronaf B', false, true, false, 50000.00
+        WHERE NOT EXISTS (SELECT 1 FROM linha WHERE nome = 'Pronaf B')
+        "#,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    println!("Dados de desenvolvimento aplicados.");
+    if criados.is_empty() {
+        println!("Nenhum usuário novo criado.");
+    }
+    for (username, otp_base32) in criados {
+        println!("Usuário '{}' criado com a senha de DEV_PASSWORD.", username);
+        imprimir_otp(username, &otp_base32);
+    }
+    Ok(())
+}
+
+async fn inserir_usuario(
+    pool: &PgPool,
+    username: &str,
+    email: &str,
+    full_name: &str,
+    password_hash: &str,
+    otp_base32: &str,
+    superuser: bool,
+) -> Result<i64> {
+    let existente: Option<(String,)> =
+        sqlx::query_as("SELECT username FROM users WHERE username = $1 OR email = $2 LIMIT 1")
+            .bind(username)
+            .bind(email)
+            .fetch_optional(pool)
+            .await?;
+    if let Some((nome,)) = existente {
+        if nome == username {
+            bail!("usuário '{}' já existe", username);
+        }
+        bail!("e-mail '{}' já cadastrado", email);
+    }
+
+    let (id,): (i64,) = sqlx::query_as(
+        r#"
+        INSERT INTO users (username, password, email, full_name, otp_base32, is_active, is_staff, is_superuser)
+        VALUES ($1, $2, $3, $4, $5, true, $6, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(username)
+    .bind(password_hash)
+    .bind(email)
+    .bind(full_name)
+    .bind(otp_base32)
+    .bind(superuser)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+fn imprimir_otp(username: &str, otp_base32: &str) {
+    // a URL carrega o segredo no formato esperado pelo autenticador
+    match UserService::get_otp_url(username, otp_base32) {
+        Some(url) => println!("  URL OTP: {}", url),
+        None => eprintln!("  Segredo OTP de '{}' não é base32 válido.", username),
+    }
+}
+
+fn ler_linha(prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut linha = String::new();
+    io::stdin().lock().read_line(&mut linha)?;
+    Ok(linha.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Comando, UserService};
+
+    fn args(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_comandos() {
+        assert_eq!(Comando::parse(&args(&[])).unwrap(), Comando::Serve);
+        assert_eq!(
+            Comando::parse(&args(&["migrate"])).unwrap(),
+            Comando::Migrate
+        );
+        assert_eq!(
+            Comando::parse(&args(&[
+                "create-superuser",
+                "admin",
+                "a@b.com",
+                "Ana",
+                "Souza"
+            ]))
+            .unwrap(),
+            Comando::CreateSuperuser {
+                username: "admin".into(),
+                email: "a@b.com".into(),
+                full_name: Some("Ana Souza".into()),
+            }
+        );
+        assert_eq!(
+            Comando::parse(&args(&["sync-ibge", "--from-file", "m.json"])).unwrap(),
+            Comando::SyncIbge {
+                from_file: Some("m.json".into())
+            }
+        );
+        assert!(Comando::parse(&args(&["reset-otp"])).is_err());
+        assert!(Comando::parse(&args(&["migrate", "extra"])).is_err());
+        assert!(Comando::parse(&args(&["desconhecido"])).is_err());
+    }
+
+    #[test]
+    fn test_url_otp_confere_com_login() {
+        let otp_base32 = UserService::random_base32();
+        let url = UserService::get_otp_url("admin", &otp_base32).unwrap();
+
+        // o autenticador lê o segredo da URL
+        let segredo = url
+            .split(['?', '&'])
+            .find_map(|p| p.strip_prefix("secret="))
+            .unwrap();
+        let codigo = otpauth::TOTP::from_base32(segredo)
+            .unwrap()
+            .generate(30, chrono::Utc::now().timestamp() as u64);
+
+        assert!(UserService::is_valid_otp(
+            &format!("{:06}", codigo),
+            &otp_base32
+        ));
+    }
+}
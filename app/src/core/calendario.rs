@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+/// Fuso do expediente; datas de feriado e faixas de horário são locais
+pub const FUSO: Tz = chrono_tz::America::Sao_Paulo;
+
+/// Limite de dias percorridos por `somar` (calendário sem nenhum dia útil)
+const LIMITE_DIAS: u32 = 3660;
+
+const MINUTOS_DIA: u32 = 24 * 60;
+
+/// Faixa de expediente em minutos desde a meia-noite; `fim` vai até 1440
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Faixa {
+    inicio: u32,
+    fim: u32,
+}
+
+/*
+calendário de dias e horas úteis: expediente por dia da semana e feriados
+  nacionais fixos e estaduais/municipais vêm de calendario_feriado (ver CalendarioService)
+  móveis (Carnaval, Sexta-feira Santa, Corpus Christi) são calculados a partir da Páscoa
+
+  let cal = Calendario::new()
+      .faixa(Weekday::Mon, hora(8, 0), hora(12, 0))
+      .feriado(data, true);
+  let prazo = cal.somar(agora, Duration::hours(4));
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Calendario {
+    /// faixas por dia da semana, índice 0 = segunda
+    expediente: [Vec<Faixa>; 7],
+    /// (mês, dia) dos feriados que se repetem todo ano
+    recorrentes: HashSet<(u32, u32)>,
+    datas: HashSet<NaiveDate>,
+    moveis: bool,
+}
+
+impl Calendario {
+    /// Vazio, com os feriados móveis; enquanto não receber faixas conta tempo corrido
+    pub fn new() -> Self {
+        Self {
+            moveis: true,
+            ..Default::default()
+        }
+    }
+
+    /// Acrescenta uma faixa de horário ao dia; faixas sobrepostas são unidas
+    pub fn faixa(mut self, dia: Weekday, inicio: NaiveTime, fim: NaiveTime) -> Self {
+        let inicio = minutos(inicio);
+        // 00:00 como fim quer dizer meia-noite do dia seguinte
+        let fim = match minutos(fim) {
+            0 => MINUTOS_DIA,
+            m => m,
+        };
+        if inicio >= fim {
+            return self;
+        }
+
+        let faixas = &mut self.expediente[dia.num_days_from_monday() as usize];
+        faixas.push(Faixa { inicio, fim });
+        faixas.sort();
+        let mut unidas: Vec<Faixa> = Vec::with_capacity(faixas.len());
+        for f in faixas.drain(..) {
+            match unidas.last_mut() {
+                Some(ultima) if f.inicio <= ultima.fim => ultima.fim = ultima.fim.max(f.fim),
+                _ => unidas.push(f),
+            }
+        }
+        *faixas = unidas;
+        self
+    }
+
+    /// `recorrente`: vale para o mesmo dia e mês de todos os anos
+    pub fn feriado(mut self, data: NaiveDate, recorrente: bool) -> Self {
+        if recorrente {
+            self.recorrentes.insert((data.month(), data.day()));
+        } else {
+            self.datas.insert(data);
+        }
+        self
+    }
+
+    pub fn eh_feriado(&self, data: NaiveDate) -> bool {
+        self.datas.contains(&data)
+            || self.recorrentes.contains(&(data.month(), data.day()))
+            || (self.moveis && feriados_moveis(data.year()).iter().any(|(d, _)| *d == data))
+    }
+
+    fn faixas_do_dia(&self, data: NaiveDate) -> &[Faixa] {
+        if self.eh_feriado(data) {
+            return &[];
+        }
+        &self.expediente[data.weekday().num_days_from_monday() as usize]
+    }
+
+    fn sem_expediente(&self) -> bool {
+        self.expediente.iter().all(Vec::is_empty)
+    }
+
+    /*
+    instante em que `duracao` de tempo útil terá passado desde `inicio`
+    fora do expediente a contagem começa na próxima faixa
+    calendário sem expediente cadastrado conta tempo corrido
+    */
+    pub fn somar(&self, inicio: DateTime<Utc>, duracao: Duration) -> DateTime<Utc> {
+        if duracao <= Duration::zero() || self.sem_expediente() {
+            return inicio + duracao;
+        }
+
+        let mut restante = duracao;
+        let mut data = inicio.with_timezone(&FUSO).date_naive();
+        for _ in 0..LIMITE_DIAS {
+            for faixa in self.faixas_do_dia(data) {
+                let de = instante(data, faixa.inicio).max(inicio);
+                let ate = instante(data, faixa.fim);
+                if ate <= de {
+                    continue;
+                }
+                if ate - de >= restante {
+                    return de + restante;
+                }
+                restante -= ate - de;
+            }
+            data = match data.succ_opt() {
+                Some(d) => d,
+                None => break,
+            };
+        }
+
+        // nenhum dia útil no horizonte (todo dia é feriado): tempo corrido
+        inicio + duracao
+    }
+
+    /// Tempo útil entre dois instantes
+    pub fn tempo_util(&self, inicio: DateTime<Utc>, fim: DateTime<Utc>) -> Duration {
+        if fim <= inicio {
+            return Duration::zero();
+        }
+        if self.sem_expediente() {
+            return fim - inicio;
+        }
+
+        let mut total = Duration::zero();
+        let mut data = inicio.with_timezone(&FUSO).date_naive();
+        let ultimo = fim.with_timezone(&FUSO).date_naive();
+        while data <= ultimo {
+            for faixa in self.faixas_do_dia(data) {
+                let de = instante(data, faixa.inicio).max(inicio);
+                let ate = instante(data, faixa.fim).min(fim);
+                if ate > de {
+                    total += ate - de;
+                }
+            }
+            data = match data.succ_opt() {
+                Some(d) => d,
+                None => break,
+            };
+        }
+        total
+    }
+}
+
+fn minutos(hora: NaiveTime) -> u32 {
+    hora.hour() * 60 + hora.minute()
+}
+
+/// Minuto local do dia convertido para UTC; 1440 é a meia-noite seguinte
+fn instante(data: NaiveDate, minuto: u32) -> DateTime<Utc> {
+    let local = data.and_time(NaiveTime::MIN) + Duration::minutes(minuto.into());
+    FUSO.from_local_datetime(&local)
+        .earliest()
+        // hora inexistente (início de horário de verão): avança a hora pulada
+        .or_else(|| {
+            FUSO.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+}
+
+/// Domingo de Páscoa (algoritmo de Meeus/Jones/Butcher, calendário gregoriano)
+pub fn pascoa(ano: i32) -> NaiveDate {
+    let a = ano % 19;
+    let b = ano / 100;
+    let c = ano % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let mes = (h + l - 7 * m + 114) / 31;
+    let dia = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(ano, mes as u32, dia as u32).expect("data da Páscoa válida")
+}
+
+/// Feriados que dependem da Páscoa
+pub fn feriados_moveis(ano: i32) -> [(NaiveDate, &'static str); 4] {
+    let p = pascoa(ano);
+    [
+        (p - Duration::days(48), "Carnaval (segunda-feira)"),
+        (p - Duration::days(47), "Carnaval (terça-feira)"),
+        (p - Duration::days(2), "Sexta-feira Santa"),
+        (p + Duration::days(60), "Corpus Christi"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hora(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn data(a: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(a, m, d).unwrap()
+    }
+
+    /// horário de Brasília (UTC-3) para UTC
+    fn brt(a: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        FUSO.from_local_datetime(&data(a, m, d).and_time(hora(h, min)))
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn comercial() -> Calendario {
+        [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ]
+        .into_iter()
+        .fold(Calendario::new(), |cal, dia| {
+            cal.faixa(dia, hora(8, 0), hora(12, 0))
+                .faixa(dia, hora(13, 0), hora(18, 0))
+        })
+        .feriado(data(2000, 12, 25), true)
+    }
+
+    #[test]
+    fn test_pascoa_e_moveis() {
+        assert_eq!(pascoa(2024), data(2024, 3, 31));
+        assert_eq!(pascoa(2025), data(2025, 4, 20));
+        assert_eq!(pascoa(2026), data(2026, 4, 5));
+
+        let moveis = feriados_moveis(2025);
+        assert_eq!(moveis[0].0, data(2025, 3, 3));
+        assert_eq!(moveis[1].0, data(2025, 3, 4));
+        assert_eq!(moveis[2].0, data(2025, 4, 18));
+        assert_eq!(moveis[3].0, data(2025, 6, 19));
+    }
+
+    #[test]
+    fn test_somar_atravessa_almoco_noite_e_fim_de_semana() {
+        let cal = comercial();
+
+        // sexta 11:00 + 2h úteis: 1h de manhã e 1h à tarde
+        let fim = cal.somar(brt(2025, 10, 17, 11, 0), Duration::hours(2));
+        assert_eq!(fim, brt(2025, 10, 17, 14, 0));
+
+        // sexta 17:00 + 3h úteis: segunda 10:00
+        let fim = cal.somar(brt(2025, 10, 17, 17, 0), Duration::hours(3));
+        assert_eq!(fim, brt(2025, 10, 20, 10, 0));
+
+        // sábado: começa a contar na segunda às 08:00
+        let fim = cal.somar(brt(2025, 10, 18, 9, 0), Duration::minutes(30));
+        assert_eq!(fim, brt(2025, 10, 20, 8, 30));
+    }
+
+    #[test]
+    fn test_feriados_nao_contam() {
+        let cal = comercial();
+
+        // quarta 24/12 17:00 + 2h: Natal (recorrente) pulado, sexta 26/12 09:00
+        let fim = cal.somar(brt(2025, 12, 24, 17, 0), Duration::hours(2));
+        assert_eq!(fim, brt(2025, 12, 26, 9, 0));
+
+        // Carnaval calculado: segunda e terça 03-04/03/2025
+        assert!(cal.eh_feriado(data(2025, 3, 3)));
+        assert!(cal.eh_feriado(data(2025, 3, 4)));
+        assert!(!cal.eh_feriado(data(2025, 3, 5)));
+    }
+
+    #[test]
+    fn test_tempo_util() {
+        let cal = comercial();
+
+        // sexta 17:00 até segunda 09:00: 1h + 1h
+        let util = cal.tempo_util(brt(2025, 10, 17, 17, 0), brt(2025, 10, 20, 9, 0));
+        assert_eq!(util, Duration::hours(2));
+
+        // sem expediente cadastrado: tempo corrido
+        assert_eq!(
+            Calendario::new().tempo_util(brt(2025, 10, 17, 17, 0), brt(2025, 10, 20, 9, 0)),
+            Duration::hours(64)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod calendario;
 mod handler;
 mod model;
 mod repository;
@@ -7,6 +8,7 @@ mod service;
 mod view;
 
 pub use handler::serve_upload;
-pub use router::{router, router_public};
-pub use schema::StatusOpt;
-pub use service::MunicipioService;
+pub use repository::{fetch_municipios, fetch_ufs, upsert_municipios, upsert_ufs};
+pub use router::{ApiDoc, router, router_public};
+pub use schema::{MunicipioIbge, StatusOpt, UfIbge};
+pub use service::CalendarioService;
@@ -1,3 +1,4 @@
+use chrono::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -16,3 +17,26 @@ pub struct Municipio {
     pub nome: String,
     pub uf_id: i64,
 }
+
+/// Faixa de horário do expediente (ver core::calendario)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Expediente {
+    pub id: i64,
+    /// ISO: 1 segunda ... 7 domingo
+    pub dia_semana: i32,
+    pub inicio: NaiveTime,
+    pub fim: NaiveTime,
+}
+
+/// Feriado nacional, estadual (`uf_id`) ou municipal (`municipio_id`)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Feriado {
+    pub id: i64,
+    pub nome: String,
+    pub data: NaiveDate,
+    pub recorrente: bool,
+    pub uf_id: Option<i64>,
+    pub uf_sigla: Option<String>,
+    pub municipio_id: Option<i64>,
+    pub municipio_nome: Option<String>,
+}
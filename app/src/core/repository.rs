@@ -1,13 +1,16 @@
+use async_trait::async_trait;
 use reqwest::Client;
-use sqlx::PgPool;
+use shared::{Db, Repository};
+use sqlx::{PgConnection, PgPool};
 use tracing::debug;
 
-use crate::core::schema::{MunicipioIbge, UfIbge};
+use crate::core::model::{Expediente, Feriado};
+use crate::core::schema::{CreateExpediente, CreateFeriado, MunicipioIbge, UfIbge};
 
 /// Busca todas as UFs na API do IBGE
 pub async fn fetch_ufs() -> Result<Vec<UfIbge>, reqwest::Error> {
     let url = "https://servicodados.ibge.gov.br/api/v1/localidades/estados";
-    let resp = Client::new().get(url).send().await?;
+    let resp = Client::new().get(url).send().await?.error_for_status()?;
     let ufs: Vec<UfIbge> = resp.json().await?;
     Ok(ufs)
 }
@@ -19,8 +22,8 @@ pub async fn fetch_municipios(sigla: &str) -> Result<Vec<MunicipioIbge>, reqwest
         sigla
     );
     debug!("Fetching municipios for UF {}: {}", sigla, url);
-    let resp = Client::new().get(&url).send().await.unwrap();
-    let municipios: Vec<MunicipioIbge> = resp.json().await.unwrap();
+    let resp = Client::new().get(&url).send().await?.error_for_status()?;
+    let municipios: Vec<MunicipioIbge> = resp.json().await?;
     debug!("Fetched {} municipios for UF {}", municipios.len(), sigla);
     Ok(municipios)
 }
@@ -68,3 +71,220 @@ pub async fn upsert_municipios(
     }
     Ok(())
 }
+
+pub struct ExpedienteRepository;
+
+#[async_trait]
+impl Repository<Expediente, i64> for ExpedienteRepository {
+    type CreateInput = CreateExpediente;
+    type UpdateInput = CreateExpediente;
+
+    fn table_name(&self) -> &str {
+        "calendario_expediente"
+    }
+
+    fn searchable_fields(&self) -> &[&str] {
+        &[]
+    }
+
+    fn select_clause(&self) -> &str {
+        "e.id, e.dia_semana, e.inicio, e.fim"
+    }
+
+    fn from_clause(&self) -> &str {
+        "calendario_expediente e"
+    }
+
+    fn id_column(&self) -> &str {
+        "e.id"
+    }
+
+    fn order_by_column(&self) -> &str {
+        "e.dia_semana, e.inicio"
+    }
+
+    async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: Self::CreateInput,
+    ) -> anyhow::Result<Expediente> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            Expediente,
+            r#"
+            INSERT INTO calendario_expediente (dia_semana, inicio, fim)
+            VALUES ($1, $2, $3)
+            RETURNING id, dia_semana, inicio, fim
+            "#,
+            input.dia_semana,
+            input.inicio,
+            input.fim
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: Self::UpdateInput,
+    ) -> anyhow::Result<Expediente> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            Expediente,
+            r#"
+            UPDATE calendario_expediente
+            SET dia_semana = $1, inicio = $2, fim = $3
+            WHERE id = $4
+            RETURNING id, dia_semana, inicio, fim
+            "#,
+            input.dia_semana,
+            input.inicio,
+            input.fim,
+            id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> anyhow::Result<()> {
+        let mut conn = db.acquire().await?;
+        sqlx::query!("DELETE FROM calendario_expediente WHERE id = $1", id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
+
+impl ExpedienteRepository {
+    pub async fn list_all(&self, conn: &mut PgConnection) -> anyhow::Result<Vec<Expediente>> {
+        Ok(sqlx::query_as!(
+            Expediente,
+            "SELECT id, dia_semana, inicio, fim FROM calendario_expediente ORDER BY dia_semana, inicio"
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+}
+
+pub struct FeriadoRepository;
+
+#[async_trait]
+impl Repository<Feriado, i64> for FeriadoRepository {
+    type CreateInput = CreateFeriado;
+    type UpdateInput = CreateFeriado;
+
+    fn table_name(&self) -> &str {
+        "calendario_feriado"
+    }
+
+    fn searchable_fields(&self) -> &[&str] {
+        &["f.nome", "m.nome"]
+    }
+
+    fn select_clause(&self) -> &str {
+        "f.id, f.nome, f.data, f.recorrente, f.uf_id, u.sigla AS uf_sigla,
+        f.municipio_id, m.nome AS municipio_nome"
+    }
+
+    fn from_clause(&self) -> &str {
+        "calendario_feriado f
+        LEFT JOIN uf u ON u.id = f.uf_id
+        LEFT JOIN municipio m ON m.id = f.municipio_id
+        "
+    }
+
+    fn id_column(&self) -> &str {
+        "f.id"
+    }
+
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[("id", "f.id"), ("nome", "f.nome"), ("data", "f.data")]
+    }
+
+    async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: Self::CreateInput,
+    ) -> anyhow::Result<Feriado> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            Feriado,
+            r#"
+            INSERT INTO calendario_feriado (nome, data, recorrente, uf_id, municipio_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, nome, data, recorrente, uf_id, NULL as "uf_sigla?",
+                municipio_id, NULL as "municipio_nome?"
+            "#,
+            input.nome,
+            input.data,
+            input.recorrente,
+            input.uf_id,
+            input.municipio_id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: Self::UpdateInput,
+    ) -> anyhow::Result<Feriado> {
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as!(
+            Feriado,
+            r#"
+            UPDATE calendario_feriado
+            SET nome = $1, data = $2, recorrente = $3, uf_id = $4, municipio_id = $5
+            WHERE id = $6
+            RETURNING id, nome, data, recorrente, uf_id, NULL as "uf_sigla?",
+                municipio_id, NULL as "municipio_nome?"
+            "#,
+            input.nome,
+            input.data,
+            input.recorrente,
+            input.uf_id,
+            input.municipio_id,
+            id
+        )
+        .fetch_one(&mut *conn)
+        .await?)
+    }
+
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> anyhow::Result<()> {
+        let mut conn = db.acquire().await?;
+        sqlx::query!("DELETE FROM calendario_feriado WHERE id = $1", id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+}
+
+impl FeriadoRepository {
+    /// Nacionais e, quando informados, os da UF e os do município (a UF do município também vale)
+    pub async fn list_aplicaveis(
+        &self,
+        conn: &mut PgConnection,
+        uf_id: Option<i64>,
+        municipio_id: Option<i64>,
+    ) -> anyhow::Result<Vec<Feriado>> {
+        Ok(sqlx::query_as!(
+            Feriado,
+            r#"
+            SELECT f.id, f.nome, f.data, f.recorrente, f.uf_id, NULL as "uf_sigla?",
+                f.municipio_id, NULL as "municipio_nome?"
+            FROM calendario_feriado f
+            WHERE (f.uf_id IS NULL AND f.municipio_id IS NULL)
+                OR f.municipio_id = $2
+                OR f.uf_id = COALESCE($1, (SELECT uf_id FROM municipio WHERE id = $2))
+            "#,
+            uf_id,
+            municipio_id
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+}
@@ -1,11 +1,29 @@
-use axum::{Router, routing::get};
+use axum::{Router, middleware, routing::get};
 
 use shared::SharedState;
+use shared::admin::AdminResource;
+use shared::generic_list::{FormField, ListConfig};
+use utoipa::OpenApi;
 
+use crate::core::repository::{ExpedienteRepository, FeriadoRepository};
 use crate::core::view;
+use crate::middlewares;
+
+/// Rotas JSON do módulo, relativas a `/core` (ver `crate::openapi`)
+#[derive(OpenApi)]
+#[openapi(paths(
+    view::buscar_cep,
+    view::read_cidade_por_ibge,
+    view::cidade_br_list_api,
+    view::cidades_to_api,
+    view::somar_uteis,
+))]
+pub struct ApiDoc;
 
 pub fn router() -> Router<SharedState> {
-    Router::new().merge(router_municipio())
+    Router::new()
+        .merge(router_municipio())
+        .merge(router_calendario())
 }
 
 fn router_municipio() -> Router<SharedState> {
@@ -14,6 +32,51 @@ fn router_municipio() -> Router<SharedState> {
         .route("/cidades", get(view::list_municipio))
 }
 
+/// Expediente e feriados (ver core::calendario); a API de tempo útil é de qualquer usuário logado
+fn router_calendario() -> Router<SharedState> {
+    let expediente = AdminResource::new(
+        ExpedienteRepository,
+        ListConfig::new("expediente", "Expediente", "Expediente", "/core/expediente")
+            .field("id", "ID")
+            .field("dia_semana", "Dia da semana")
+            .field("inicio", "Início")
+            .field("fim", "Fim"),
+        vec![
+            FormField::number("dia_semana", "Dia da semana")
+                .required()
+                .placeholder("1 segunda ... 7 domingo"),
+            FormField::time("inicio", "Início").required(),
+            FormField::time("fim", "Fim").required(),
+        ],
+    )
+    .router();
+
+    let feriado = AdminResource::new(
+        FeriadoRepository,
+        ListConfig::new("feriado", "Feriado", "Feriados", "/core/feriado")
+            .field("id", "ID")
+            .field("nome", "Nome")
+            .field("data", "Data")
+            .field("recorrente", "Todo ano")
+            .field("uf_sigla", "UF")
+            .field("municipio_nome", "Município")
+            .searchable(&["nome"]),
+        vec![
+            FormField::text("nome", "Nome").required(),
+            FormField::date("data", "Data").required(),
+            FormField::checkbox("recorrente", "Repete todo ano"),
+            FormField::number("uf_id", "ID da UF").placeholder("Feriado estadual"),
+            FormField::number("municipio_id", "ID do município").placeholder("Feriado municipal"),
+        ],
+    )
+    .router();
+
+    expediente
+        .merge(feriado)
+        .layer(middleware::from_fn(middlewares::require_roles(vec!["admin"])))
+        .route("/calendario/somar-uteis", get(view::somar_uteis))
+}
+
 pub fn router_public() -> Router<SharedState> {
     Router::new()
         .route("/cidades-por-ibge", get(view::read_cidade_por_ibge))
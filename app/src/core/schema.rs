@@ -1,4 +1,9 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::utils::serde_utils::{bool_from_str, option_i64_from_str};
 
 /// Schema para criar uma UF
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,7 +26,7 @@ pub struct UpdateMunicipio {
 }
 
 /// Resposta de Município com UF (join)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MunicipioWithUf {
     pub id: i64,
     pub nome: String,
@@ -56,7 +61,7 @@ pub struct Mesorregiao {
     pub uf: UfIbge,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[warn(unused_imports)]
 pub struct UfIbge {
     pub id: i64,
@@ -65,7 +70,7 @@ pub struct UfIbge {
     pub regiao: Regiao,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[warn(unused_imports)]
 pub struct Regiao {
     pub id: i64,
@@ -85,7 +90,7 @@ pub struct StatusOpt {
 /*
 utilizado na API busca CEP
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct CepQuery {
     pub cep: String,
 }
@@ -93,7 +98,53 @@ pub struct CepQuery {
 /*
 utilizado na API busca cidades por IBGE
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct CidadeParams {
     pub ibge_id: i32,
 }
+
+/// Formulário da faixa de expediente (criação e edição)
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateExpediente {
+    #[validate(range(min = 1, max = 7, message = "Dia da semana de 1 (segunda) a 7 (domingo)"))]
+    pub dia_semana: i32,
+    pub inicio: NaiveTime,
+    pub fim: NaiveTime,
+}
+
+/// Formulário do feriado (criação e edição); sem UF nem município é nacional
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateFeriado {
+    #[validate(length(min = 1, message = "Informe o nome"))]
+    pub nome: String,
+    pub data: NaiveDate,
+    #[serde(default, deserialize_with = "bool_from_str")]
+    pub recorrente: bool,
+    #[serde(default, deserialize_with = "option_i64_from_str")]
+    pub uf_id: Option<i64>,
+    #[serde(default, deserialize_with = "option_i64_from_str")]
+    pub municipio_id: Option<i64>,
+}
+
+/*
+utilizado na API que soma tempo útil
+*/
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SomarUteisQuery {
+    /// instante inicial (RFC 3339)
+    pub inicio: DateTime<Utc>,
+    /// minutos de expediente a somar
+    pub minutos: i64,
+    /// feriados estaduais desta UF
+    pub uf_id: Option<i64>,
+    /// feriados municipais (e estaduais da UF do município)
+    pub municipio_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SomarUteisResposta {
+    pub inicio: DateTime<Utc>,
+    pub minutos: i64,
+    /// instante em que os minutos úteis terão passado
+    pub fim: DateTime<Utc>,
+}
@@ -1,9 +1,11 @@
 use anyhow::Result;
 use shared::PaginatedResponse;
-use sqlx::{PgPool, Pool};
+use sqlx::{PgConnection, PgPool};
 
 use crate::core::{
+    calendario::Calendario,
     model::{Municipio, Uf},
+    repository::{ExpedienteRepository, FeriadoRepository},
     schema::{CreateMunicipio, MunicipioWithUf, UpdateMunicipio},
 };
 
@@ -75,6 +77,7 @@ impl UfService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
 }
@@ -208,6 +211,7 @@ impl MunicipioService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
 
@@ -333,6 +337,50 @@ impl MunicipioService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
 }
+
+/// Município do expediente (feriados estaduais e municipais): `EXPEDIENTE_MUNICIPIO_ID`
+fn municipio_do_expediente() -> Option<i64> {
+    std::env::var("EXPEDIENTE_MUNICIPIO_ID")
+        .ok()
+        .and_then(|v| v.parse().ok())
+}
+
+pub struct CalendarioService;
+
+impl CalendarioService {
+    /*
+       monta o calendário com o expediente cadastrado e os feriados nacionais,
+       mais os da UF e do município quando informados
+    */
+    pub async fn carregar(
+        conn: &mut PgConnection,
+        uf_id: Option<i64>,
+        municipio_id: Option<i64>,
+    ) -> Result<Calendario> {
+        let mut calendario = Calendario::new();
+
+        for faixa in ExpedienteRepository.list_all(conn).await? {
+            match chrono::Weekday::try_from((faixa.dia_semana - 1) as u8) {
+                Ok(dia) => calendario = calendario.faixa(dia, faixa.inicio, faixa.fim),
+                Err(_) => continue,
+            }
+        }
+        for feriado in FeriadoRepository
+            .list_aplicaveis(conn, uf_id, municipio_id)
+            .await?
+        {
+            calendario = calendario.feriado(feriado.data, feriado.recorrente);
+        }
+
+        Ok(calendario)
+    }
+
+    /// Calendário do escritório, usado no SLA e nas métricas de tempo
+    pub async fn do_expediente(conn: &mut PgConnection) -> Result<Calendario> {
+        Self::carregar(conn, None, municipio_do_expediente()).await
+    }
+}
@@ -1,56 +1,44 @@
 use axum::{
     Json,
     extract::{Query, State},
-    response::{Html, IntoResponse, Redirect},
+    response::{Html, IntoResponse},
 };
 use regex::Regex;
-use reqwest::StatusCode;
 use serde_json::{Value, json};
-use shared::{FlashStatus, ListParams, PaginatedResponse, PaginationQuery, SharedState, helpers};
-use tracing::debug;
+use shared::{
+    AppError, ErrorResponse, ListParams, PaginatedResponse, PaginationQuery, SharedState, helpers,
+};
 
 use crate::core::{
     model::Municipio,
     repository::{fetch_municipios, fetch_ufs, upsert_municipios, upsert_ufs},
-    schema::{CepQuery, CidadeParams, MunicipioWithUf},
-    service::MunicipioService,
+    schema::{CepQuery, CidadeParams, MunicipioWithUf, SomarUteisQuery, SomarUteisResposta},
+    service::{CalendarioService, MunicipioService},
 };
 
 /*
     utilizado para buscar e atualizar cidade4s e estados do ibge
 */
-pub async fn atualizar_ibge(State(state): State<SharedState>) -> impl IntoResponse {
+pub async fn atualizar_ibge(State(state): State<SharedState>) -> Result<Json<Value>, AppError> {
     let pool = &*state.db;
 
     // Atualiza UFs
-    match fetch_ufs().await {
-        Ok(ufs) => {
-            if let Err(e) = upsert_ufs(pool, &ufs).await {
-                return Json(
-                    serde_json::json!({ "status": "error", "message": format!("Erro ao salvar UFs: {}", e) }),
-                );
-            }
-
-            // Para cada UF, atualiza os municípios
-            for uf in ufs {
-                if let Ok(municipios) = fetch_municipios(&uf.sigla).await {
-                    //debug!("Fetched {} municipios for UF {}", municipios.len(), uf.sigla);
-                    if let Err(e) = upsert_municipios(pool, municipios, &uf.id).await {
-                        return Json(
-                            serde_json::json!({ "status": "error", "message": format!("Erro ao salvar municípios de {}: {}", uf.sigla, e) }),
-                        );
-                    }
-                }
-            }
+    let ufs = fetch_ufs()
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Erro ao buscar UFs: {}", e)))?;
+    upsert_ufs(pool, &ufs).await?;
 
-            Json(
-                serde_json::json!({ "status": "ok", "message": "Base de UFs e Municípios atualizada com sucesso!" }),
-            )
+    // Para cada UF, atualiza os municípios
+    for uf in ufs {
+        if let Ok(municipios) = fetch_municipios(&uf.sigla).await {
+            //debug!("Fetched {} municipios for UF {}", municipios.len(), uf.sigla);
+            upsert_municipios(pool, municipios, &uf.id).await?;
         }
-        Err(e) => Json(
-            serde_json::json!({ "status": "error", "message": format!("Erro ao buscar UFs: {}", e) }),
-        ),
     }
+
+    Ok(Json(
+        serde_json::json!({ "status": "ok", "message": "Base de UFs e Municípios atualizada com sucesso!" }),
+    ))
 }
 
 /*
@@ -59,26 +47,9 @@ pub async fn atualizar_ibge(State(state): State<SharedState>) -> impl IntoRespon
 pub async fn list_municipio(
     State(state): State<SharedState>,
     Query(params): Query<ListParams>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service = MunicipioService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-    let mut context = minijinja::context! {};
-
-    context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
     let permissions_result = service
         .get_paginated(
             &state.db,
@@ -88,46 +59,31 @@ pub async fn list_municipio(
         )
         .await;
 
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
+    let paginated_response = permissions_result?;
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.unwrap_or_default(),
+    };
 
-            match state.templates.get_template("core/municipio_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar cidades: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/",
-                &format!("Erro ao carregar cidades: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
+    helpers::render(&state.templates, "core/municipio_list.html", context)
 }
 
 /* APIS */
+#[utoipa::path(
+    get,
+    path = "/buscar-cep",
+    tag = "core",
+    params(CepQuery),
+    responses(
+        (status = 200, description = "Resposta do ViaCEP, sem alterações", body = Object),
+        (status = 400, description = "CEP sem 8 dígitos", body = String),
+        (status = 502, description = "ViaCEP indisponível", body = String),
+    ),
+)]
 pub async fn buscar_cep(Query(params): Query<CepQuery>) -> impl IntoResponse {
     // Remove caracteres não numéricos
     let re = Regex::new(r"\D").unwrap();
@@ -181,41 +137,51 @@ pub async fn buscar_cep(Query(params): Query<CepQuery>) -> impl IntoResponse {
 utilizado no cadastro externo API de acesso publico
 não precisa ser usuario do sistema para consulta
 */
+#[utoipa::path(
+    get,
+    path = "/cidades-por-ibge",
+    tag = "core",
+    params(CidadeParams),
+    responses(
+        (status = 200, description = "`{id, nome, uf, uf_id}`", body = Object),
+        (status = 404, description = "Município não encontrado", body = ErrorResponse),
+    ),
+)]
 pub async fn read_cidade_por_ibge(
     State(state): State<SharedState>,
     Query(params): Query<CidadeParams>,
-) -> impl IntoResponse {
+) -> Result<Json<Value>, AppError> {
     let service = MunicipioService::new();
-    let row = service
+    let cidade = service
         .find_municipio_with_uf_by_id(&state.db, params.ibge_id as i64)
-        .await;
+        .await?
+        .ok_or(AppError::NotFound)?;
 
-    match row {
-        Ok(Some(cidade)) => Json(json!({
-            "id": cidade.id,
-            "nome": cidade.nome,
-            "uf": cidade.uf_sigla,
-            "uf_id": cidade.uf_id,
-        }))
-        .into_response(),
-
-        Ok(None) => (axum::http::StatusCode::NOT_FOUND, "Cidade não encontrada").into_response(),
-
-        Err(_) => (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            "Erro ao buscar cidade",
-        )
-            .into_response(),
-    }
+    Ok(Json(json!({
+        "id": cidade.id,
+        "nome": cidade.nome,
+        "uf": cidade.uf_sigla,
+        "uf_id": cidade.uf_id,
+    })))
 }
 
 /*
 api publica retorna cidades do brasil
 */
+#[utoipa::path(
+    get,
+    path = "/cidades-br-api",
+    tag = "core",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<MunicipioWithUf>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+)]
 pub async fn cidade_br_list_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<MunicipioWithUf>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<MunicipioWithUf>>, AppError> {
     let service = MunicipioService::new();
     let res = service
         .get_paginated(
@@ -224,11 +190,7 @@ pub async fn cidade_br_list_api(
             q.page.unwrap_or(1) as i32,
             q.page_size.unwrap_or(10) as i32,
         )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     Ok(Json(res))
 }
@@ -237,10 +199,20 @@ pub async fn cidade_br_list_api(
 api publica retorna cidades to tocantins
 
 */
+#[utoipa::path(
+    get,
+    path = "/cidades-to-api",
+    tag = "core",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<MunicipioWithUf>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+)]
 pub async fn cidades_to_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<MunicipioWithUf>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<MunicipioWithUf>>, AppError> {
     let service = MunicipioService::new();
     let res = service
         .get_paginated_cidades_to(
@@ -249,11 +221,39 @@ pub async fn cidades_to_api(
             q.page.unwrap_or(1) as i32,
             q.page_size.unwrap_or(10) as i32,
         )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     Ok(Json(res))
 }
+
+/// Soma minutos de expediente a um instante, pulando noites, fins de semana e feriados
+#[utoipa::path(
+    get,
+    path = "/calendario/somar-uteis",
+    tag = "core",
+    params(SomarUteisQuery),
+    responses(
+        (status = 200, description = "Instante final", body = SomarUteisResposta),
+        (status = 400, description = "Parâmetros inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
+pub async fn somar_uteis(
+    State(state): State<SharedState>,
+    Query(params): Query<SomarUteisQuery>,
+) -> Result<Json<SomarUteisResposta>, AppError> {
+    if params.minutos < 0 {
+        return Err(AppError::InvalidInput("minutos não pode ser negativo".into()));
+    }
+
+    let mut conn = state.db.acquire().await?;
+    let calendario =
+        CalendarioService::carregar(&mut conn, params.uf_id, params.municipio_id).await?;
+    let fim = calendario.somar(params.inicio, chrono::Duration::minutes(params.minutos));
+
+    Ok(Json(SomarUteisResposta {
+        inicio: params.inicio,
+        minutos: params.minutos,
+        fim,
+    }))
+}
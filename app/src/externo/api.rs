@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use shared::{
+    AppError, ErrorResponse, JsonValidado, PaginatedResponse, PaginationQuery, SharedState,
+};
+use uuid::Uuid;
+
+use crate::{
+    externo::{
+        model::{Contato, Linha, Regiao},
+        schema::{
+            CreateLinhaSchema, CreateRegiaoSchema, UpdateContato, UpdateLinhaSchema,
+            UpdateRegiaoSchema,
+        },
+        service::{ContatoService, LinhaService, RegiaoService},
+    },
+    middlewares::CurrentUser,
+};
+
+/*
+==========================================
+
+------------- API v1 LINHA -------------
+==========================================
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/linhas",
+    tag = "v1",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de linhas de crédito", body = PaginatedResponse<Linha>),
+        (status = 401, description = "Token ausente ou inválido", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn listar_linhas(
+    State(state): State<SharedState>,
+    Query(q): Query<PaginationQuery>,
+) -> Result<Json<PaginatedResponse<Linha>>, AppError> {
+    let res = LinhaService::new().list(&state.db, &q.list_query()).await?;
+    Ok(Json(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/linhas/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID da linha")),
+    responses(
+        (status = 200, description = "Linha de crédito", body = Linha),
+        (status = 404, description = "Linha não encontrada", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn obter_linha(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Linha>, AppError> {
+    let linha = LinhaService::new().get_by_id(&state.db, id).await?;
+    Ok(Json(linha))
+}
+
+#[utoipa::path(
+    post,
+    path = "/linhas",
+    tag = "v1",
+    request_body = CreateLinhaSchema,
+    responses(
+        (status = 201, description = "Linha criada", body = Linha),
+        (status = 403, description = "Sem a permissão admin", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn criar_linha(
+    State(state): State<SharedState>,
+    JsonValidado(input): JsonValidado<CreateLinhaSchema>,
+) -> Result<(StatusCode, Json<Linha>), AppError> {
+    let linha = LinhaService::new().create(&state.db, input).await?;
+    Ok((StatusCode::CREATED, Json(linha)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/linhas/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID da linha")),
+    request_body = UpdateLinhaSchema,
+    responses(
+        (status = 200, description = "Linha atualizada", body = Linha),
+        (status = 403, description = "Sem a permissão admin", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn atualizar_linha(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+    JsonValidado(input): JsonValidado<UpdateLinhaSchema>,
+) -> Result<Json<Linha>, AppError> {
+    let linha = LinhaService::new().update(&state.db, id, input).await?;
+    Ok(Json(linha))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/linhas/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID da linha")),
+    responses(
+        (status = 204, description = "Linha enviada para a lixeira"),
+        (status = 403, description = "Sem a permissão admin", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn excluir_linha(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    LinhaService::new().delete(&state.db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/*
+==========================================
+
+------------- API v1 REGIAO ------------
+==========================================
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/regioes",
+    tag = "v1",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de regiões", body = PaginatedResponse<Regiao>),
+        (status = 401, description = "Token ausente ou inválido", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn listar_regioes(
+    State(state): State<SharedState>,
+    Query(q): Query<PaginationQuery>,
+) -> Result<Json<PaginatedResponse<Regiao>>, AppError> {
+    let res = RegiaoService::new()
+        .list(&state.db, &q.list_query())
+        .await?;
+    Ok(Json(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/regioes/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID da região")),
+    responses(
+        (status = 200, description = "Região", body = Regiao),
+        (status = 404, description = "Região não encontrada", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn obter_regiao(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Regiao>, AppError> {
+    let regiao = RegiaoService::new().get_by_id(&state.db, id).await?;
+    Ok(Json(regiao))
+}
+
+#[utoipa::path(
+    post,
+    path = "/regioes",
+    tag = "v1",
+    request_body = CreateRegiaoSchema,
+    responses(
+        (status = 201, description = "Região criada", body = Regiao),
+        (status = 403, description = "Sem a permissão admin", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn criar_regiao(
+    State(state): State<SharedState>,
+    JsonValidado(input): JsonValidado<CreateRegiaoSchema>,
+) -> Result<(StatusCode, Json<Regiao>), AppError> {
+    let regiao = RegiaoService::new().create(&state.db, input).await?;
+    Ok((StatusCode::CREATED, Json(regiao)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/regioes/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID da região")),
+    request_body = UpdateRegiaoSchema,
+    responses(
+        (status = 200, description = "Região atualizada", body = Regiao),
+        (status = 403, description = "Sem a permissão admin", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn atualizar_regiao(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+    JsonValidado(input): JsonValidado<UpdateRegiaoSchema>,
+) -> Result<Json<Regiao>, AppError> {
+    let regiao = RegiaoService::new().update(&state.db, id, input).await?;
+    Ok(Json(regiao))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/regioes/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID da região")),
+    responses(
+        (status = 204, description = "Região enviada para a lixeira"),
+        (status = 403, description = "Sem a permissão admin", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn excluir_regiao(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    RegiaoService::new().delete(&state.db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/*
+==========================================
+
+------------- API v1 CONTATO -----------
+==========================================
+
+o cadastro de contatos continua pelos formulários públicos (com os documentos);
+a API lista, consulta, atualiza e exclui
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/contatos",
+    tag = "v1",
+    params(
+        PaginationQuery,
+        ("status_tramitacao" = Option<String>, Query, description = "Filtro, ex.: `4,5`"),
+        ("linha_id" = Option<i32>, Query, description = "Filtro por linha de crédito"),
+        ("created_at__gte" = Option<String>, Query, description = "Criados a partir da data, ex.: `2025-01-01`"),
+    ),
+    responses(
+        (status = 200, description = "Página de contatos", body = PaginatedResponse<Contato>),
+        (status = 400, description = "Filtro inválido", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn listar_contatos(
+    State(state): State<SharedState>,
+    Query(q): Query<PaginationQuery>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Result<Json<PaginatedResponse<Contato>>, AppError> {
+    let service = ContatoService::new();
+
+    // mesmos filtros da tela: ?status_tramitacao=4,5&linha_id=8&created_at__gte=2025-01-01
+    let filtros = service
+        .parse_filtros(&query)
+        .map_err(|err| AppError::InvalidInput(format!("Filtro inválido: {}", err)))?;
+
+    let res = service
+        .list(&state.db, &q.list_query().with_filters(filtros))
+        .await?;
+    Ok(Json(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/contatos/{id}",
+    tag = "v1",
+    params(("id" = Uuid, Path, description = "ID do contato")),
+    responses(
+        (status = 200, description = "Contato", body = Contato),
+        (status = 404, description = "Contato não encontrado", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn obter_contato(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Contato>, AppError> {
+    let contato = ContatoService::new().get_by_id(&state.db, id).await?;
+    Ok(Json(contato))
+}
+
+#[utoipa::path(
+    put,
+    path = "/contatos/{id}",
+    tag = "v1",
+    params(("id" = Uuid, Path, description = "ID do contato")),
+    request_body = UpdateContato,
+    responses(
+        (status = 200, description = "Contato atualizado", body = Contato),
+        (status = 403, description = "Sem a permissão admin", body = ErrorResponse),
+        (status = 409, description = "Alterado por outro usuário depois de `updatedAt`", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos ou sem `updatedAt`", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn atualizar_contato(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+    JsonValidado(mut input): JsonValidado<UpdateContato>,
+) -> Result<Json<Contato>, AppError> {
+    input.updated_by = Some(current_user.current_user.id);
+
+    let contato = ContatoService::new().update(&*state.db, id, input).await?;
+    Ok(Json(contato))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/contatos/{id}",
+    tag = "v1",
+    params(("id" = Uuid, Path, description = "ID do contato")),
+    responses(
+        (status = 204, description = "Contato excluído"),
+        (status = 403, description = "Sem a permissão admin", body = ErrorResponse),
+        (status = 422, description = "Contato em uso por outros cadastros", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn excluir_contato(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    ContatoService::new().delete(&*state.db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
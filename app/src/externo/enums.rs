@@ -202,6 +202,23 @@ impl StatusTramitacaoEnum {
         *self as i32
     }
 
+    pub fn all() -> Vec<Self> {
+        (1..=10).map(StatusTramitacaoEnum::from_i32).collect()
+    }
+
+    /*
+    para o select do html
+    */
+    pub fn status_options() -> Vec<StatusOpt> {
+        StatusTramitacaoEnum::all()
+            .into_iter()
+            .map(|s| StatusOpt {
+                value: s as i32,
+                label: s.to_string(),
+            })
+            .collect()
+    }
+
     pub fn color(&self) -> &'static str {
         match self {
             StatusTramitacaoEnum::AguardandoAtendimento => "bg-orange-300",
@@ -1,3 +1,4 @@
+mod api;
 mod enums;
 mod model;
 mod repository;
@@ -9,5 +10,5 @@ pub mod filters;
 
 pub use enums::*;
 pub use repository::LinhaRepository;
-pub use router::router;
+pub use router::{ApiDoc, ApiV1Doc, router, router_v1};
 pub use service::LinhaService;
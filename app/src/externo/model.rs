@@ -5,11 +5,12 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 use serde_json::Value;
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::externo::enums;
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Linha {
     pub id: i32,
     pub nome: String,
@@ -17,13 +18,15 @@ pub struct Linha {
     pub permite_cpf: bool,
     pub permite_avalista: bool,
     //#[serde(with = "bigdecimal::serde::json_num")]
+    #[schema(value_type = String, example = "15000.00")]
     pub valor_maximo: BigDecimal,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 /*
 
 
 */
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Contato {
     pub id: Uuid,
     pub linha_id: i32,
@@ -34,6 +37,7 @@ pub struct Contato {
     pub telefone: String,
     pub email: String,
     pub cidade_id: i64,
+    #[schema(value_type = String, example = "1500.00")]
     pub val_solicitado: BigDecimal,
     pub status_tramitacao: i32,
     pub campos: Value,
@@ -42,6 +46,7 @@ pub struct Contato {
     pub created_at: Option<DateTime<Utc>>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<DateTime<Utc>>,
+    pub updated_by: Option<i64>,
 }
 
 impl Contato {
@@ -219,12 +224,13 @@ pub struct ContatoAvalista {
     pub email_conj: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 #[sqlx(type_name = "emprestimo_regiao")] // nome da tabela no Postgres
 pub struct Regiao {
     pub id: i32,
     pub name: String,
     pub municipio_id: i32,
+    pub deleted_at: Option<DateTime<Utc>>,
 
     //campo extra nao exite na tabela
     pub municipio_nome: Option<String>
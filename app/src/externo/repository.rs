@@ -1,6 +1,5 @@
 use async_trait::async_trait;
-use shared::Repository;
-use sqlx::PgPool;
+use shared::{Db, FilterField, FilterKind, Repository};
 
 use regex::Regex;
 
@@ -44,14 +43,28 @@ impl Repository<Linha, i32> for LinhaRepository {
     }
 
     fn select_clause(&self) -> &str {
-        "m.id, m.nome, m.permite_cnpj, m.permite_cpf, m.permite_avalista, m.valor_maximo"
+        "m.id, m.nome, m.permite_cnpj, m.permite_cpf, m.permite_avalista, m.valor_maximo, m.deleted_at"
     }
 
     fn from_clause(&self) -> &str {
         "linha m"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<Linha> {
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[
+            ("id", "m.id"),
+            ("nome", "m.nome"),
+            ("valor_maximo", "m.valor_maximo"),
+            ("deleted_at", "m.deleted_at"),
+        ]
+    }
+
+    fn soft_delete_column(&self) -> Option<&str> {
+        Some("m.deleted_at")
+    }
+
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<Linha> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Linha,
             "INSERT INTO linha (nome, permite_cnpj, permite_cpf, permite_avalista, valor_maximo)
@@ -62,11 +75,17 @@ impl Repository<Linha, i32> for LinhaRepository {
             input.permite_avalista,
             input.valor_maximo
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i32, input: Self::UpdateInput) -> Result<Linha> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i32,
+        input: Self::UpdateInput,
+    ) -> Result<Linha> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Linha,
             r#"
@@ -77,7 +96,7 @@ impl Repository<Linha, i32> for LinhaRepository {
                 permite_cpf = COALESCE($3, permite_cpf),
                 permite_avalista = COALESCE($4, permite_avalista),
                 valor_maximo = COALESCE($5, valor_maximo)
-            WHERE id = $6
+            WHERE id = $6 AND deleted_at IS NULL
             RETURNING *"#,
             input.nome,
             input.permite_cnpj,
@@ -86,20 +105,27 @@ impl Repository<Linha, i32> for LinhaRepository {
             input.valor_maximo,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
-        sqlx::query!("DELETE FROM linha WHERE id = $1", id)
-            .execute(pool)
-            .await?;
-        Ok(())
+    /// vai para a lixeira; `purge` remove de vez
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        self.soft_delete(db, id).await
     }
 }
 
 pub struct ContatoRepository;
 
+const CONTATO_FILTROS: &[FilterField] = &[
+    FilterField::new("status_tramitacao", "m.status_tramitacao", FilterKind::Int),
+    FilterField::new("status_atendimento", "m.status_atendimento", FilterKind::Bool),
+    FilterField::new("linha_id", "m.linha_id", FilterKind::Int),
+    FilterField::new("cidade_id", "m.cidade_id", FilterKind::Int),
+    FilterField::new("val_solicitado", "m.val_solicitado", FilterKind::Decimal),
+    FilterField::new("created_at", "m.created_at", FilterKind::Date),
+];
+
 #[async_trait]
 impl Repository<Contato, Uuid> for ContatoRepository {
     type CreateInput = CreateContato;
@@ -113,16 +139,39 @@ impl Repository<Contato, Uuid> for ContatoRepository {
         &["m.nome"]
     }
 
+    // mesma expressão do índice idx_contato_busca
+    fn search_vector(&self) -> Option<&str> {
+        Some(
+            "setweight(to_tsvector('portuguese_unaccent', m.nome), 'A') \
+            || setweight(to_tsvector('portuguese_unaccent', regexp_replace(m.protocolo || ' ' || m.cpf_cnpj, '[^[:alnum:]]+', ' ', 'g')), 'A')",
+        )
+    }
+
     fn select_clause(&self) -> &str {
         "m.id, m.linha_id, m.protocolo, m.status_atendimento, m.cpf_cnpj, m.nome, m.telefone, m.email,
-            m.cidade_id, m.val_solicitado, m.status_tramitacao, m.campos, m.dados_imports, m.created_at, m.updated_at"
+            m.cidade_id, m.val_solicitado, m.status_tramitacao, m.campos, m.dados_imports, m.created_at, m.updated_at, m.updated_by"
     }
 
     fn from_clause(&self) -> &str {
         "contato m"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<Contato> {
+    fn filterable_fields(&self) -> &[FilterField] {
+        CONTATO_FILTROS
+    }
+
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[
+            ("nome", "m.nome"),
+            ("protocolo", "m.protocolo"),
+            ("val_solicitado", "m.val_solicitado"),
+            ("status_tramitacao", "m.status_tramitacao"),
+            ("created_at", "m.created_at"),
+        ]
+    }
+
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<Contato> {
+        let mut conn = db.acquire().await?;
         let re = Regex::new(r"[\.\-/]").unwrap();
         let cpf_cnpj_clean = re.replace_all(&input.cpf_cnpj, "").to_string();
         Ok(sqlx::query_as!(
@@ -143,12 +192,19 @@ impl Repository<Contato, Uuid> for ContatoRepository {
             input.campos,
             input.dados_imports
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: Uuid, input: Self::UpdateInput) -> Result<Contato> {
-        Ok(sqlx::query_as!(
+    /// falha com `ConflictError` se `input.updated_at` não é mais a versão do registro
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: Uuid,
+        input: Self::UpdateInput,
+    ) -> Result<Contato> {
+        let mut conn = db.acquire().await?;
+        let contato = sqlx::query_as!(
             Contato,
             r#"
             UPDATE contato
@@ -164,8 +220,9 @@ impl Repository<Contato, Uuid> for ContatoRepository {
                 status_tramitacao = COALESCE($9, status_tramitacao),
                 campos = COALESCE($10, campos),
                 dados_imports = COALESCE($11, dados_imports),
+                updated_by = $12,
                 updated_at = NOW()
-            WHERE id = $12
+            WHERE id = $13 AND updated_at IS NOT DISTINCT FROM $14
             RETURNING *"#,
             input.linha_id,
             input.status_atendimento,
@@ -178,15 +235,23 @@ impl Repository<Contato, Uuid> for ContatoRepository {
             input.status_tramitacao,
             input.campos,
             input.dados_imports,
-            id
+            input.updated_by,
+            id,
+            input.updated_at
         )
-        .fetch_one(pool)
-        .await?)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match contato {
+            Some(contato) => Ok(contato),
+            None => Err(self.stale_update(&mut *conn, id).await),
+        }
     }
 
-    async fn delete(&self, pool: &PgPool, id: Uuid) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: Uuid) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM contato WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -208,7 +273,7 @@ impl Repository<Regiao, i32> for RegiaoRepository {
     }
 
     fn select_clause(&self) -> &str {
-        "r.id, r.name, r.municipio_id, m.nome as municipio_nome"
+        "r.id, r.name, r.municipio_id, r.deleted_at, m.nome as municipio_nome"
     }
 
     fn from_clause(&self) -> &str {
@@ -221,22 +286,42 @@ impl Repository<Regiao, i32> for RegiaoRepository {
         "r.id"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<Regiao> {
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[
+            ("id", "r.id"),
+            ("nome", "r.name"),
+            ("cidade", "m.nome"),
+            ("deleted_at", "r.deleted_at"),
+        ]
+    }
+
+    fn soft_delete_column(&self) -> Option<&str> {
+        Some("r.deleted_at")
+    }
+
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<Regiao> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Regiao,
             r#"
             INSERT INTO emprestimo_regiao (name, municipio_id)
             VALUES ($1, $2)
-            RETURNING id, name, municipio_id, NULL as "municipio_nome?"
+            RETURNING id, name, municipio_id, deleted_at, NULL as "municipio_nome?"
             "#,
             input.name,
             input.municipio_id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i32, input: Self::UpdateInput) -> Result<Regiao> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i32,
+        input: Self::UpdateInput,
+    ) -> Result<Regiao> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Regiao,
             r#"
@@ -244,22 +329,20 @@ impl Repository<Regiao, i32> for RegiaoRepository {
             SET
                 name = COALESCE($1, name),
                 municipio_id = COALESCE($2, municipio_id)
-            WHERE id = $3
-            RETURNING id, name, municipio_id, NULL as "municipio_nome?"
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING id, name, municipio_id, deleted_at, NULL as "municipio_nome?"
             "#,
             input.name,
             input.municipio_id,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
-        sqlx::query!("DELETE FROM emprestimo_regiao WHERE id = $1", id)
-            .execute(pool)
-            .await?;
-        Ok(())
+    /// vai para a lixeira; `purge` remove de vez
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        self.soft_delete(db, id).await
     }
 }
 
@@ -302,7 +385,16 @@ impl Repository<RegiaoCidades, i32> for RegiaoCidadesRepository {
         "r.name, m.nome"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<RegiaoCidades> {
+    fn association_columns(&self) -> Option<(&str, &str)> {
+        Some(("regiao_id", "municipio_id"))
+    }
+
+    async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: Self::CreateInput,
+    ) -> Result<RegiaoCidades> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             RegiaoCidades,
             r#"INSERT INTO emprestimo_regiao_cidades (regiao_id, municipio_id) VALUES ($1, $2)
@@ -313,16 +405,17 @@ impl Repository<RegiaoCidades, i32> for RegiaoCidadesRepository {
             input.regiao_id,
             input.municipio_id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(
+    async fn update<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         id: i32,
         input: Self::UpdateInput,
     ) -> Result<RegiaoCidades> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             RegiaoCidades,
             r#"
@@ -338,13 +431,14 @@ impl Repository<RegiaoCidades, i32> for RegiaoCidadesRepository {
             input.municipio_id,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM emprestimo_regiao_cidades WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -389,7 +483,8 @@ impl Repository<UserRegiao, i32> for UserRegiaoRepository {
         "r.name, u.full_name"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<UserRegiao> {
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<UserRegiao> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             UserRegiao,
             r#"INSERT INTO emprestimo_user_regiao (regiao_id, user_id) VALUES ($1, $2)
@@ -400,11 +495,17 @@ impl Repository<UserRegiao, i32> for UserRegiaoRepository {
             input.regiao_id,
             input.user_id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i32, input: Self::UpdateInput) -> Result<UserRegiao> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i32,
+        input: Self::UpdateInput,
+    ) -> Result<UserRegiao> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             UserRegiao,
             r#"
@@ -420,13 +521,14 @@ impl Repository<UserRegiao, i32> for UserRegiaoRepository {
             input.user_id,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM emprestimo_user_regiao WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -471,7 +573,8 @@ impl Repository<UserLinha, i32> for UserLinhaRepository {
         "l.nome, u.full_name"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<UserLinha> {
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<UserLinha> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             UserLinha,
             r#"INSERT INTO emprestimo_user_linha (linha_id, user_id) VALUES ($1, $2)
@@ -482,11 +585,17 @@ impl Repository<UserLinha, i32> for UserLinhaRepository {
             input.linha_id,
             input.user_id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i32, input: Self::UpdateInput) -> Result<UserLinha> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i32,
+        input: Self::UpdateInput,
+    ) -> Result<UserLinha> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             UserLinha,
             r#"
@@ -502,13 +611,14 @@ impl Repository<UserLinha, i32> for UserLinhaRepository {
             input.user_id,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM emprestimo_user_linha WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -1,10 +1,43 @@
 use axum::{
-    Router,
-    routing::{delete, get, post},
+    Router, middleware,
+    routing::{delete, get, post, put},
 };
 use shared::SharedState;
+use shared::admin::AdminResource;
+use shared::generic_list::{FormField, ListConfig};
+use utoipa::OpenApi;
 
-use crate::externo::view;
+use crate::externo::{LinhaRepository, api, view};
+use crate::middlewares;
+
+/// Rotas JSON do módulo, relativas a `/externo` (ver `crate::openapi`)
+#[derive(OpenApi)]
+#[openapi(paths(
+    view::linha_api,
+    view::regiao_api,
+    view::create_contato_pronaf,
+))]
+pub struct ApiDoc;
+
+/// Rotas da API v1, relativas a `/api/v1` (ver `crate::api`)
+#[derive(OpenApi)]
+#[openapi(paths(
+    api::listar_linhas,
+    api::obter_linha,
+    api::criar_linha,
+    api::atualizar_linha,
+    api::excluir_linha,
+    api::listar_regioes,
+    api::obter_regiao,
+    api::criar_regiao,
+    api::atualizar_regiao,
+    api::excluir_regiao,
+    api::listar_contatos,
+    api::obter_contato,
+    api::atualizar_contato,
+    api::excluir_contato,
+))]
+pub struct ApiV1Doc;
 
 pub fn router() -> Router<SharedState> {
     Router::new()
@@ -16,16 +49,26 @@ pub fn router() -> Router<SharedState> {
 }
 
 fn router_tipo() -> Router<SharedState> {
-    Router::new()
-        .route("/linha", get(view::list_linha))
-        .route("/linha-form", get(view::linha_form))
-        .route("/linha-form", post(view::create_linha))
-        .route(
-            "/linha-form/{id}",
-            get(view::get_linha).post(view::update_linha),
-        )
-        .route("/linha/{id}", delete(view::delete_linha))
-        .merge(api_linha_router())
+    AdminResource::new(
+        LinhaRepository,
+        ListConfig::new("linha", "Linha", "Linhas", "/externo/linha")
+            .field("id", "ID")
+            .field("nome", "Nome")
+            .field("permite_cnpj", "Permite CNPJ")
+            .field("permite_cpf", "Permite CPF")
+            .field("permite_avalista", "Permite Avalista")
+            .field("valor_maximo", "Valor Maximo")
+            .searchable(&["nome"]),
+        vec![
+            FormField::text("nome", "Nome").required(),
+            FormField::money("valor_maximo", "Valor Maximo").required(),
+            FormField::checkbox("permite_cnpj", "Permite CNPJ"),
+            FormField::checkbox("permite_cpf", "Permite CPF"),
+            FormField::checkbox("permite_avalista", "Permite Avalista"),
+        ],
+    )
+    .router()
+    .merge(api_linha_router())
 }
 
 fn api_linha_router() -> Router<SharedState> {
@@ -36,6 +79,12 @@ fn api_linha_router() -> Router<SharedState> {
 fn router_contato() -> Router<SharedState> {
     Router::new()
         .route("/contato", get(view::list_contato))
+        .route(
+            "/contato/exportar",
+            get(view::export_contato).layer(middleware::from_fn(middlewares::require_roles(vec![
+                "admin",
+            ]))),
+        )
         .route("/contato-form", get(view::contato_form))
         .route("/contato-form", post(view::create_contato))
         .route("/contato-form-pronaf", post(view::create_contato_pronaf))
@@ -56,11 +105,15 @@ fn router_regiao() -> Router<SharedState> {
             get(view::get_regiao).post(view::update_regiao),
         )
         .route("/regiao/{id}", delete(view::delete_regiao))
+        .route("/regiao/lixeira", get(view::lixeira_regiao))
+        .route("/regiao/{id}/restaurar", post(view::restore_regiao))
+        .route("/regiao/{id}/excluir-definitivo", post(view::purge_regiao))
         .merge(api_regiao_router())
         .route(
             "/regiao-gestao",
             get(view::get_gestao_regiao).post(view::create_gestao_regiao),
         )
+        .route("/regiao-gestao/lote", post(view::lote_gestao_regiao))
         .route("/regiao-gestao/{id}", delete(view::delete_gestao_regiao))
 }
 
@@ -87,3 +140,34 @@ fn router_linha_user() -> Router<SharedState> {
         )
         .route("/linha-user/{id}", delete(view::delete_linha_por_usuario))
 }
+
+/// API v1: leitura para qualquer token; alterações exigem a permissão admin
+pub fn router_v1() -> Router<SharedState> {
+    let escrita = Router::new()
+        .route("/linhas", post(api::criar_linha))
+        .route(
+            "/linhas/{id}",
+            put(api::atualizar_linha).delete(api::excluir_linha),
+        )
+        .route("/regioes", post(api::criar_regiao))
+        .route(
+            "/regioes/{id}",
+            put(api::atualizar_regiao).delete(api::excluir_regiao),
+        )
+        .route(
+            "/contatos/{id}",
+            put(api::atualizar_contato).delete(api::excluir_contato),
+        )
+        .layer(middleware::from_fn(middlewares::require_roles(vec![
+            "admin",
+        ])));
+
+    Router::new()
+        .route("/linhas", get(api::listar_linhas))
+        .route("/linhas/{id}", get(api::obter_linha))
+        .route("/regioes", get(api::listar_regioes))
+        .route("/regioes/{id}", get(api::obter_regiao))
+        .route("/contatos", get(api::listar_contatos))
+        .route("/contatos/{id}", get(api::obter_contato))
+        .merge(escrita)
+}
@@ -3,11 +3,13 @@ use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateLinhaSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub nome: String,
     #[serde(default, deserialize_with = "bool_from_str")]
     pub permite_cnpj: bool,
@@ -15,13 +17,16 @@ pub struct CreateLinhaSchema {
     pub permite_cpf: bool,
     #[serde(default, deserialize_with = "bool_from_str")]
     pub permite_avalista: bool,
+    /// número, ou texto no formato brasileiro ("15.000,00")
     #[serde(deserialize_with = "crate::utils::serde_utils::brl_to_bigdecimal")]
+    #[schema(value_type = String, example = "15.000,00")]
     pub valor_maximo: BigDecimal,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateLinhaSchema {
     pub id: i32,
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub nome: String,
     #[serde(default, deserialize_with = "bool_from_str")]
     pub permite_cnpj: bool,
@@ -29,7 +34,9 @@ pub struct UpdateLinhaSchema {
     pub permite_cpf: bool,
     #[serde(default, deserialize_with = "bool_from_str")]
     pub permite_avalista: bool,
+    /// número, ou texto no formato brasileiro ("15.000,00")
     #[serde(deserialize_with = "crate::utils::serde_utils::brl_to_bigdecimal")]
+    #[schema(value_type = String, example = "15.000,00")]
     pub valor_maximo: BigDecimal,
 }
 
@@ -215,28 +222,39 @@ pub struct CreateContato {
     pub dados_imports: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateContato {
     pub id: Uuid,
     pub linha_id: i32,
     pub protocolo: String,
     pub status_atendimento: bool,
     pub cpf_cnpj: String,
+    #[validate(length(min = 1, message = "nome não pode ser vazio"))]
     pub nome: String,
     pub telefone: String,
+    #[validate(regex(path=*EMAIL_RX, message = "Invalid email"))]
     pub email: String,
     pub cidade_id: i64,
+    #[schema(value_type = String, example = "1500.00")]
     pub val_solicitado: BigDecimal,
     pub status_tramitacao: i32,
     pub campos: Value,
     pub dados_imports: Option<Value>,
     #[serde(rename = "createdAt")]
     pub created_at: Option<DateTime<Utc>>,
+    /// versão lida pelo formulário; o update falha com conflito se o registro mudou
+    /// (obrigatória na API; o formulário omite quando o registro não tem versão)
     #[serde(rename = "updatedAt")]
+    #[validate(required(message = "updatedAt é obrigatório"))]
+    #[schema(required = true)]
     pub updated_at: Option<DateTime<Utc>>,
+    /// preenchido pela view com o usuário logado
+    #[serde(skip_deserializing)]
+    #[schema(ignore)]
+    pub updated_by: Option<i64>,
 }
 
-#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+#[derive(Debug, Clone, Validate, Serialize, Deserialize, ToSchema)]
 pub struct CreateContatoSchema {
     #[validate(length(min = 1, message = " não pode ser vazio"))]
     pub cpf_cnpj: String,
@@ -248,6 +266,8 @@ pub struct CreateContatoSchema {
     pub email: String,
     #[validate(range(min = 1, message = "cidade_id não localizada"))]
     pub cidade_id: i64,
+    /// aceita o formato brasileiro ("1.500,00")
+    #[schema(value_type = String, example = "1.500,00")]
     pub val_solicitado: BigDecimal,
 }
 
@@ -275,7 +295,7 @@ pub enum TipoContatoExtra {
     CreditoOnline(CreditoOnline),
 }
 
-#[derive(Debug, Validate, Default, Clone, Serialize, Deserialize)]
+#[derive(Debug, Validate, Default, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PronafB {
     pub nome_tecnico: String,
     pub orgao_associacao_tecnico: String,
@@ -285,6 +305,7 @@ pub struct PronafB {
     #[validate(length(min = 9, max = 9, message = "Cep invalido"))]
     pub cep: String, //len 9
     pub endereco: String,
+    #[schema(value_type = String, example = "1.500,00")]
     pub prev_aumento_fat: BigDecimal,
     pub cpf_conj: Option<String>,
     pub nome_conj: Option<String>,
@@ -292,6 +313,7 @@ pub struct PronafB {
     pub email_conj: Option<String>,
     #[validate(regex(path=*EMAIL_RX, message = "Invalid email"))]
     pub email: Option<String>,
+    #[schema(value_type = Option<String>, example = "80.000,00")]
     pub valor_estimado_imovel: Option<BigDecimal>,
     #[validate(length(min = 8, message = "Detalhe a atividade melhor"))]
     pub desc_atividade: String,
@@ -364,16 +386,33 @@ pub struct AplicacaoRecursos {
     pub valor_total: BigDecimal,
 }
 
+/*
+corpo multipart de /externo/contato-form-pronaf, usado somente no OpenAPI
+além destes campos vão os itens de aplicação numerados (descricao_0, quantidade_0,
+valor_unitario_0, valor_total_0, ...) e um arquivo por documento de DOC_PRONAF,
+com o id do documento como nome do campo
+*/
+#[allow(dead_code)]
+#[derive(Deserialize, ToSchema)]
+pub struct ContatoPronafForm {
+    #[serde(flatten)]
+    pub contato: CreateContatoSchema,
+    #[serde(flatten)]
+    pub pronaf: PronafB,
+}
 
-#[derive(Debug, Serialize, Deserialize)]
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateRegiaoSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub name: String,
     pub municipio_id: i32
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateRegiaoSchema {
     pub id: i32,
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub name: String,
     pub municipio_id: i32
 }
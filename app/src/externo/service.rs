@@ -1,10 +1,10 @@
 use anyhow::{Ok, Result};
-use std::fs;
+use std::{collections::HashMap, fs};
 
 use chrono::Datelike;
 use chrono::Local;
-use shared::{PaginatedResponse, Repository};
-use sqlx::{PgPool, Transaction};
+use shared::{Db, Filters, ListQuery, OpcaoLote, PaginatedResponse, Repository, UnitOfWork};
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 use crate::externo::model::Regiao;
@@ -68,6 +68,10 @@ impl LinhaService {
     ) -> Result<PaginatedResponse<Linha>> {
         Ok(self.repo.get_paginated(pool, find, page, page_size).await?)
     }
+
+    pub async fn list(&self, pool: &PgPool, query: &ListQuery) -> Result<PaginatedResponse<Linha>> {
+        Ok(self.repo.list(pool, query).await?)
+    }
 }
 
 /*
@@ -99,122 +103,117 @@ impl ContatoService {
        list_item_recurso: Vec<AplicacaoRecursos>,
        arquivos: Vec<(String, (String, Vec<u8>))>, // (id tipo do arquivo(categria do arquivo), (nome do arquivo, dados))
     */
-    pub async fn create(
+    pub async fn create<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         tipo: TipoContatoExtra,
         linha: Linha,
         data_contato: CreateContatoSchema,
         list_item_recurso: Option<Vec<AplicacaoRecursos>>,
         arquivos: Option<Vec<(String, (String, Vec<u8>))>>,
     ) -> Result<Contato> {
-        let mut tx: Transaction<'_, sqlx::Postgres> = pool.begin().await?;
-
-        let result = async {
-
-            let campos = match &tipo {
-                TipoContatoExtra::PronafB(p) => serde_json::to_value(p).unwrap(),
-                TipoContatoExtra::MicroCreditoOnline(m) => serde_json::to_value(m).unwrap(),
-                TipoContatoExtra::CreditoPopular(c) => serde_json::to_value(c).unwrap(),
-                TipoContatoExtra::MaosQueCriam(m) => serde_json::to_value(m).unwrap(),
-                TipoContatoExtra::CreditoOnline(c) => serde_json::to_value(c).unwrap()
-            };
-
-            //gerar protocolo
-            let mut protocolo = Contato::gerar_codigo_protocolo();
-            loop {
-                if !Self::exists_by_protocolo(&self, pool, &protocolo).await? {
-                    break;
-                } else {
-                    protocolo = Contato::gerar_codigo_protocolo();
-                }
+        // contato, recursos e documentos na mesma transação
+        let mut uow = UnitOfWork::begin(db).await?;
+
+        let campos = match &tipo {
+            TipoContatoExtra::PronafB(p) => serde_json::to_value(p).unwrap(),
+            TipoContatoExtra::MicroCreditoOnline(m) => serde_json::to_value(m).unwrap(),
+            TipoContatoExtra::CreditoPopular(c) => serde_json::to_value(c).unwrap(),
+            TipoContatoExtra::MaosQueCriam(m) => serde_json::to_value(m).unwrap(),
+            TipoContatoExtra::CreditoOnline(c) => serde_json::to_value(c).unwrap()
+        };
+
+        //gerar protocolo
+        let mut protocolo = Contato::gerar_codigo_protocolo();
+        loop {
+            if !self.exists_by_protocolo(uow.conn(), &protocolo).await? {
+                break;
+            } else {
+                protocolo = Contato::gerar_codigo_protocolo();
             }
+        }
 
-            //inserir contato
-            let contato = sqlx::query_as!(
-                Contato,
-                "INSERT INTO contato (linha_id, protocolo, status_atendimento, cpf_cnpj, nome, telefone, email,
-                cidade_id, val_solicitado, status_tramitacao, campos, created_at, updated_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW(), NOW()) RETURNING *",
-                linha.id,
-                protocolo,
-                false,
-                data_contato.cpf_cnpj,
-                data_contato.nome,
-                data_contato.telefone,
-                data_contato.email,
-                data_contato.cidade_id,
-                data_contato.val_solicitado,
-                StatusTramitacaoEnum::AguardandoAtendimento.to_i32(),
-                campos
+        //inserir contato
+        let contato = sqlx::query_as!(
+            Contato,
+            "INSERT INTO contato (linha_id, protocolo, status_atendimento, cpf_cnpj, nome, telefone, email,
+            cidade_id, val_solicitado, status_tramitacao, campos, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW(), NOW()) RETURNING *",
+            linha.id,
+            protocolo,
+            false,
+            data_contato.cpf_cnpj,
+            data_contato.nome,
+            data_contato.telefone,
+            data_contato.email,
+            data_contato.cidade_id,
+            data_contato.val_solicitado,
+            StatusTramitacaoEnum::AguardandoAtendimento.to_i32(),
+            campos
+        )
+        .fetch_one(uow.conn())
+        .await?;
+
+        //inserir recursos
+        for recurso in &list_item_recurso.unwrap_or_default() {
+            sqlx::query!(
+                r#"
+                INSERT INTO aplicacao_recurso(
+                descricao, quantidade, valor_unitario, valor_total, contato_id)
+                    VALUES ($1, $2, $3, $4, $5);
+                "#,
+                recurso.descricao.clone(),
+                recurso.quantidade,
+                recurso.valor_unitario.clone(),
+                recurso.valor_total.clone(),
+                contato.id
             )
-            .fetch_one(pool)
+            .execute(uow.conn())
             .await?;
+        }
 
-            //inserir recursos
-            for recurso in &list_item_recurso.unwrap_or_default() {
-                sqlx::query!(
-                    r#"
-                    INSERT INTO aplicacao_recurso(
-	                descricao, quantidade, valor_unitario, valor_total, contato_id)
-	                    VALUES ($1, $2, $3, $4, $5);
-                    "#,
-                    recurso.descricao.clone(),
-                    recurso.quantidade,
-                    recurso.valor_unitario.clone(),
-                    recurso.valor_total.clone(),
-                    contato.id
-                )
-                .execute(&mut *tx)
-                .await?;
-            }
-
-            //inserir arquivos
-            for (tipo_arquivo, (nome_arquivo, dados_arquivo)) in arquivos.unwrap_or_default() {
-                // upload do arquivo
-                let end_arquivo = Self::upload_arquivo(contato.id, nome_arquivo, dados_arquivo).await;
-                sqlx::query!(
-                    r#"
-                    INSERT INTO doc_solicitante(
-	                contato_id, arquivo, status_arquivo, observacao, tipo)
-	                VALUES ($1, $2, $3, $4, $5);
-                    "#,
-                    contato.id,
-                    end_arquivo,
-                    StatusDocumentoEnum::Pendente.to_i32(),
-                    "",
-                    tipo_arquivo
-                )
-                .execute(&mut *tx)
-                .await?;
-            }
-            Ok(contato)
+        //inserir arquivos
+        for (tipo_arquivo, (nome_arquivo, dados_arquivo)) in arquivos.unwrap_or_default() {
+            // upload do arquivo
+            let end_arquivo = Self::upload_arquivo(contato.id, nome_arquivo, dados_arquivo).await;
+            sqlx::query!(
+                r#"
+                INSERT INTO doc_solicitante(
+                contato_id, arquivo, status_arquivo, observacao, tipo)
+                VALUES ($1, $2, $3, $4, $5);
+                "#,
+                contato.id,
+                end_arquivo,
+                StatusDocumentoEnum::Pendente.to_i32(),
+                "",
+                tipo_arquivo
+            )
+            .execute(uow.conn())
+            .await?;
+        }
 
-        }.await;
+        // qualquer `?` acima descarta a uow sem commit (rollback)
+        uow.commit().await?;
 
-        match result {
-            std::result::Result::Ok(result) => {
-                tx.commit().await?;
-                Ok(result)
-            }
-            Err(e) => {
-                tx.rollback().await?;
-                return Err(e);
-            }
-        }
+        Ok(contato)
     }
 
-    pub async fn update(&self, pool: &PgPool, id: Uuid, input: UpdateContato) -> Result<Contato> {
-        Ok(self.repo.update(pool, id, input).await?)
+    pub async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: Uuid,
+        input: UpdateContato,
+    ) -> Result<Contato> {
+        Ok(self.repo.update(db, id, input).await?)
     }
 
-    pub async fn delete(&self, pool: &PgPool, id: Uuid) -> Result<()> {
-        Ok(self.repo.delete(pool, id).await?)
+    pub async fn delete<'c, A: Db<'c>>(&self, db: A, id: Uuid) -> Result<()> {
+        Ok(self.repo.delete(db, id).await?)
     }
 
     pub async fn exists_by_protocolo(
         &self,
-        pool: &PgPool,
+        conn: &mut PgConnection,
         protocolo: &str,
     ) -> anyhow::Result<bool> {
         let count: i64 = sqlx::query_scalar!(
@@ -225,21 +224,20 @@ impl ContatoService {
         "#,
             protocolo
         )
-        .fetch_one(pool)
+        .fetch_one(conn)
         .await?
         .unwrap_or(0);
 
         Ok(count > 0)
     }
 
-    pub async fn get_paginated(
-        &self,
-        pool: &PgPool,
-        find: Option<&str>,
-        page: i32,
-        page_size: i32,
-    ) -> Result<PaginatedResponse<Contato>> {
-        Ok(self.repo.get_paginated(pool, find, page, page_size).await?)
+    /// Filtros de contato lidos da query string (status_tramitacao, linha_id, created_at...)
+    pub fn parse_filtros(&self, params: &HashMap<String, String>) -> Result<Filters> {
+        Filters::parse(self.repo.filterable_fields(), params)
+    }
+
+    pub async fn list(&self, pool: &PgPool, query: &ListQuery) -> Result<PaginatedResponse<Contato>> {
+        Ok(self.repo.list(pool, query).await?)
     }
 
     /*
@@ -323,14 +321,16 @@ impl RegiaoService {
         Ok(self.repo.delete(pool, id).await?)
     }
 
-    pub async fn get_paginated(
-        &self,
-        pool: &PgPool,
-        find: Option<&str>,
-        page: i32,
-        page_size: i32,
-    ) -> Result<PaginatedResponse<Regiao>> {
-        Ok(self.repo.get_paginated(pool, find, page, page_size).await?)
+    pub async fn restore(&self, pool: &PgPool, id: i32) -> Result<()> {
+        Ok(self.repo.restore(pool, id).await?)
+    }
+
+    pub async fn purge(&self, pool: &PgPool, id: i32) -> Result<()> {
+        Ok(self.repo.purge(pool, id).await?)
+    }
+
+    pub async fn list(&self, pool: &PgPool, query: &ListQuery) -> Result<PaginatedResponse<Regiao>> {
+        Ok(self.repo.list(pool, query).await?)
     }
 }
 
@@ -428,8 +428,67 @@ impl RegiaoCidadesService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
+
+    /// Associa vários municípios à região (ignora os que já estão associados)
+    pub async fn create_many(
+        &self,
+        pool: &PgPool,
+        regiao_id: i32,
+        municipio_ids: &[i32],
+    ) -> Result<u64> {
+        Ok(self.repo.bulk_create(pool, regiao_id, municipio_ids).await?)
+    }
+
+    pub async fn delete_many(
+        &self,
+        pool: &PgPool,
+        regiao_id: i32,
+        municipio_ids: &[i32],
+    ) -> Result<u64> {
+        Ok(self.repo.bulk_delete(pool, regiao_id, municipio_ids).await?)
+    }
+
+    /// Associa todos os municípios do Tocantins à região
+    pub async fn create_all(&self, pool: &PgPool, regiao_id: i32) -> Result<u64> {
+        let ids: Vec<i32> = self
+            .municipios_disponiveis(pool, regiao_id)
+            .await?
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        self.create_many(pool, regiao_id, &ids).await
+    }
+
+    pub async fn delete_all(&self, pool: &PgPool, regiao_id: i32) -> Result<u64> {
+        Ok(self.repo.bulk_delete_all(pool, regiao_id).await?)
+    }
+
+    /// Municípios do Tocantins que ainda não estão na região
+    pub async fn municipios_disponiveis(
+        &self,
+        pool: &PgPool,
+        regiao_id: i32,
+    ) -> Result<Vec<OpcaoLote>> {
+        Ok(sqlx::query_as!(
+            OpcaoLote,
+            r#"
+            SELECT m.id::int AS "id!", m.nome AS "nome!"
+            FROM municipio m
+            WHERE m.uf_id = 17
+              AND NOT EXISTS (
+                SELECT 1 FROM emprestimo_regiao_cidades rc
+                WHERE rc.regiao_id = $1 AND rc.municipio_id = m.id
+              )
+            ORDER BY m.nome
+            "#,
+            regiao_id
+        )
+        .fetch_all(pool)
+        .await?)
+    }
 }
 
 
@@ -534,6 +593,7 @@ impl UserRegiaoService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
 }
@@ -640,6 +700,7 @@ impl UserLinhaService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
 }
@@ -7,19 +7,22 @@ use axum::{
     Extension, Form,
     extract::{Multipart, Path, Query, State},
     http::StatusCode,
-    response::{Html, IntoResponse, Redirect, Response},
+    response::{Html, IntoResponse, Response},
 };
 use bigdecimal::BigDecimal;
 use minijinja::context;
 use regex::Regex;
 use serde_json::{Map, Value};
+use shared::export::{self, ColumnKind, ExportConfig, ExportParams};
 use shared::{
-    FlashStatus, IdParams, ListParams, PaginatedResponse, PaginationQuery, SharedState, helpers,
+    AcaoLote, AppError, ConflictError, ErrorResponse, FlashStatus, IdParams, ListParams, LoteForm,
+    PaginatedResponse, PaginationQuery, SharedState, flash_redirect, helpers, mensagem_erro,
 };
 use tracing::debug;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::externo::filters::contato_status_filter;
 use crate::externo::model::{Linha, Regiao};
 use crate::externo::schema::{
     AplicacaoRecursos, CreateRegiaoCidades, CreateRegiaoSchema, CreateUserLinha, CreateUserRegiao,
@@ -29,301 +32,41 @@ use crate::externo::service::{
     RegiaoCidadesService, RegiaoService, UserLinhaService, UserRegiaoService,
 };
 use crate::permissao::UserService;
+use crate::utils::conflito::conflito_response;
 use crate::{
     externo::{
-        LinhaService, StatusCivil, TypeContato,
+        LinhaService, StatusCivil, StatusTramitacaoEnum, TypeContato,
         schema::{
-            ContatoSchema, CreateContatoSchema, CreateLinhaSchema, DOC_AGRICULTURA, DOC_CAPITAL,
+            ContatoPronafForm, ContatoSchema, CreateContatoSchema, DOC_AGRICULTURA, DOC_CAPITAL,
             DOC_EMERGINCIAL, DOC_MAOS_QUE, DOC_MICRO_CREDITO, DOC_ONLINE, DOC_POPULAR, DOC_PRONAF,
-            DocumentoRequerido, PronafB, UpdateContato, UpdateLinhaSchema,
+            DocumentoRequerido, PronafB, UpdateContato,
         },
         service::ContatoService,
     },
     middlewares::CurrentUser,
 };
 
-pub async fn list_linha(
-    State(state): State<SharedState>,
-    Query(params): Query<ListParams>,
-) -> impl IntoResponse {
-    let service = LinhaService::new();
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let mut context = minijinja::context! {};
-
-    context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    // Usar o PermissionService para buscar dados paginados
-    let permissions_result = service
-        .get_paginated(
-            &state.db,
-            params.find.as_deref(),
-            params.page.unwrap_or(1),
-            params.page_size.unwrap_or(10),
-        )
-        .await;
-
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-
-            match state.templates.get_template("externo/linha_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar linhas: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/",
-                &format!("Erro ao carregar linhas: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
-}
-
-pub async fn linha_form(
-    State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    match state.templates.get_template("externo/linha_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
-}
-
-pub async fn create_linha(
-    State(state): State<SharedState>,
-    Extension(current_user): Extension<CurrentUser>,
-    Form(mut body): Form<CreateLinhaSchema>,
-) -> impl IntoResponse {
-    if !current_user.current_user.is_superuser {
-        let flash_url = helpers::create_flash_url(
-            &format!("/externo/linha"),
-            &"Você não tem permissão para criar uma linha".to_string(),
-            FlashStatus::Error,
-        );
-        return Redirect::to(&flash_url).into_response();
-    }
-
-    let service = LinhaService::new();
-
-    match service.create(&*state.db, body).await {
-        Ok(linha) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/linha-form/{}", linha.id),
-                "Linha criada com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/linha-form",
-                &format!("Erro ao criar linha: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-    }
-}
-
-pub async fn get_linha(
-    State(state): State<SharedState>,
-    Path(id): Path<i32>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    let service = LinhaService::new();
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    // Carregar o template
-    let template = match state.templates.get_template("externo/linha_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
-
-    let linha = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar linha: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/externo/linha-form",
-                &format!("linha não encontrada: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
-
-    // Preparar o contexto
-    let ctx = context! {
-        row => linha,
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
-}
-
-pub async fn update_linha(
-    State(state): State<SharedState>,
-    Extension(current_user): Extension<CurrentUser>,
-    Path(id): Path<i32>,
-    Form(input): Form<UpdateLinhaSchema>,
-) -> impl IntoResponse {
-    let service = LinhaService::new();
-
-    match service.update(&*state.db, id, input).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/linha"),
-                &format!("Linha atualizada com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/linha-form/{}", id),
-                &format!("Erro ao atualizar linha: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-    }
-}
-
-pub async fn delete_linha(
-    State(state): State<SharedState>,
-    Extension(current_user): Extension<CurrentUser>,
-    Path(id): Path<i32>,
-) -> impl IntoResponse {
-    let service = LinhaService::new();
-
-    match service.delete(&*state.db, id).await {
-        Ok(()) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/linha",
-                "Linha excluída com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/linha",
-                &format!("Erro ao excluir linha: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-    }
-}
-
 /*
 api regiao
 
 */
+#[utoipa::path(
+    get,
+    path = "/linha-api",
+    tag = "externo",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<Linha>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn linha_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<Linha>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<Linha>>, AppError> {
     let service = LinhaService::new();
-    let res = service
-        .get_paginated(
-            &state.db,
-            q.find.as_deref(),
-            q.page.unwrap_or(1) as i32,
-            q.page_size.unwrap_or(10) as i32,
-        )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let res = service.list(&state.db, &q.list_query()).await?;
 
     Ok(Json(res))
 }
@@ -337,69 +80,102 @@ pub async fn linha_api(
 
 pub async fn list_contato(
     State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
     Query(params): Query<ListParams>,
-) -> impl IntoResponse {
+    Query(query): Query<HashMap<String, String>>,
+) -> Result<Response, AppError> {
     let service = ContatoService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    // Usar o PermissionService para buscar dados paginados
-    let permissions_result = service
-        .get_paginated(
-            &state.db,
-            params.find.as_deref(),
-            params.page.unwrap_or(1),
-            params.page_size.unwrap_or(10),
-        )
-        .await;
-
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                tipo_contato => TypeContato::tipo_contato_options(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-
-            match state.templates.get_template("externo/contato_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
+    // Filtros: ?status_tramitacao=4,5&linha_id=8&created_at__gte=2025-01-01
+    let filtros = match service.parse_filtros(&query) {
+        Ok(filtros) => filtros,
         Err(err) => {
-            debug!("Erro ao buscar contatos: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/",
-                &format!("Erro ao carregar contatos: {}", err),
+            return Ok(flash_redirect(
+                "/externo/contato",
+                &format!("Filtro inválido: {}", err),
                 FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
+            ));
         }
-    }
+    };
+
+    let list_query = params.list_query().with_filters(filtros.clone());
+
+    let linhas = LinhaService::new()
+        .get_paginated(&state.db, None, 1, 100)
+        .await
+        .map(|r| r.data)
+        .unwrap_or_default();
+
+    let paginated_response = service.list(&state.db, &list_query).await?;
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        cursor => list_query.cursor,
+        next_cursor => paginated_response.next_cursor,
+        find => params.find.unwrap_or_default(),
+        ordem => minijinja::context! {
+            sort => list_query.sort,
+            dir => list_query.dir,
+            query => list_query.base_query_string(),
+        },
+        sort_query => list_query.sort_query_string(),
+        filtros => filtros.values(),
+        filter_query => filtros.query_string(),
+        status_tramitacao_options => StatusTramitacaoEnum::status_options(),
+        linhas => linhas,
+        tipo_contato => TypeContato::tipo_contato_options(),
+        colunas_exportacao => contato_export().options(),
+        pode_exportar => current_user.tem_permissao("admin"),
+    };
+
+    Ok(helpers::render(&state.templates, "externo/contato_list.html", context)?.into_response())
+}
+
+/// Colunas da exportação de contatos
+fn contato_export() -> ExportConfig {
+    ExportConfig::new("contatos")
+        .column("protocolo", "Protocolo", ColumnKind::Text)
+        .column("nome", "Nome", ColumnKind::Text)
+        .column("cpf_cnpj", "CPF/CNPJ", ColumnKind::Text)
+        .column("telefone", "Telefone", ColumnKind::Text)
+        .column("email", "E-mail", ColumnKind::Text)
+        .column("linha_id", "Linha", ColumnKind::Int)
+        .column("cidade_id", "Cidade (IBGE)", ColumnKind::Int)
+        .column("val_solicitado", "Valor Solicitado", ColumnKind::Money)
+        .column_map("status_tramitacao", "Status", |v| {
+            v.as_i64()
+                .map(|status| contato_status_filter(status as i32))
+                .unwrap_or_default()
+        })
+        .column("status_atendimento", "Atendido", ColumnKind::Bool)
+        .column("createdAt", "Criado em", ColumnKind::DateTime)
+}
+
+/*
+ exportação CSV/XLSX da listagem de contatos com a mesma busca, filtros e ordenação
+ ?formato=xlsx&colunas=nome,email&status_tramitacao=4,5
+*/
+pub async fn export_contato(
+    State(state): State<SharedState>,
+    Query(params): Query<ListParams>,
+    Query(query): Query<HashMap<String, String>>,
+    Query(pares): Query<Vec<(String, String)>>,
+) -> Result<Response, AppError> {
+    let export = ExportParams::parse(&pares)?;
+    let filtros = ContatoService::new()
+        .parse_filtros(&query)
+        .map_err(|err| AppError::InvalidInput(format!("Filtro inválido: {}", err)))?;
+    let list_query = params.list_query().with_filters(filtros);
+
+    let db = state.db.clone();
+    export::exportar(&contato_export(), &export, list_query, move |query| {
+        let db = db.clone();
+        async move { ContatoService::new().list(&db, &query).await }
+    })
+    .await
 }
 
 /*
@@ -408,62 +184,31 @@ pub async fn list_contato(
 pub async fn contato_form(
     State(state): State<SharedState>,
     Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
+) -> Result<Response, AppError> {
     let type_contato = params.get("type_contato");
 
-    let template;
-    let documentos;
-
-    if let Some(tipo) = type_contato
+    let Some(tipo) = type_contato
         .and_then(|s| s.parse::<i32>().ok())
         .and_then(TypeContato::from_i32)
-    {
-        template = template_contato(tipo as i32);
-        documentos = get_list_documento(tipo as i32);
-    } else {
+    else {
         eprintln!("Tipo contato inválido");
-        let flash_url = helpers::create_flash_url(
+        return Ok(flash_redirect(
             "http://fomento.to.gov.br",
-            &format!("Tipo de Contato não permitido"),
+            "Tipo de Contato não permitido",
             FlashStatus::Error,
-        );
-        return Err(Redirect::to(&flash_url).into_response());
-    }
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
+        ));
+    };
+    let template = template_contato(tipo as i32);
+    let documentos = get_list_documento(tipo as i32);
 
     let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-        type_contato => type_contato.unwrap().parse::<i32>().ok(),
+        type_contato => tipo as i32,
         estado_civil => StatusCivil::estado_civil_options(),
         tipo_contato => TypeContato::tipo_contato_options(),
         documentos => documentos,
     };
 
-    match state.templates.get_template(template) {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(helpers::render(&state.templates, template, context)?.into_response())
 }
 
 fn template_contato(type_contato: i32) -> &'static str {
@@ -500,11 +245,22 @@ fn get_list_documento(type_contato: i32) -> Option<&'static [DocumentoRequerido]
 /*
  cria contato interno pronaf
 */
+#[utoipa::path(
+    post,
+    path = "/contato-form-pronaf",
+    tag = "externo",
+    request_body(content = ContatoPronafForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "`{code, status: \"success\", data}` com o contato criado", body = Object),
+        (status = 400, description = "`{status: \"error\", detail}` com os erros de validação", body = Object),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn create_contato_pronaf(
     State(state): State<SharedState>,
     Extension(current_user): Extension<CurrentUser>,
     mut multipart: Multipart,
-) -> impl IntoResponse {
+) -> Result<(StatusCode, Json<Value>), AppError> {
     let service = ContatoService::new();
     let service_linha = LinhaService::new();
 
@@ -550,12 +306,20 @@ pub async fn create_contato_pronaf(
     let mut arquivos: Vec<(String, (String, Vec<u8>))> = vec![];
 
     // Itera pelos campos do multipart
-    while let Some(mut field) = multipart.next_field().await.unwrap() {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::InvalidInput(e.body_text()))?
+    {
         let name = field.name().unwrap_or("").to_string();
 
         if let Some(file_name) = field.file_name().map(|s| s.to_string()) {
             // lê os bytes do arquivo
-            let data = field.bytes().await.unwrap().to_vec();
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::InvalidInput(e.body_text()))?
+                .to_vec();
             arquivos.push((name, (file_name, data)));
         } else {
             // campo de texto
@@ -563,13 +327,6 @@ pub async fn create_contato_pronaf(
 
             // Preencher os campos específicos de AplicacaoRecursos lista
             if name.starts_with("descricao_") {
-                /* let idx = name
-                    .trim_start_matches("descricao_")
-                    .parse::<usize>()
-                    .unwrap_or(0);
-                println!("descricao {} => {}", idx, text);
-                // aqui você pode inserir em um vetor de itens, ex:
-                // itens[idx].descricao = text; */
                 item_recurso.descricao = text
             } else if name.starts_with("quantidade_") {
                 item_recurso.quantidade = text.parse().unwrap_or(0);
@@ -669,36 +426,36 @@ pub async fn create_contato_pronaf(
     match data_contato.validate() {
         Ok(_) => {}
         Err(errors) => {
-            return (
+            return Ok((
                 StatusCode::BAD_REQUEST,
                 Json(serde_json::json!({
                     "status": "error",
                     "detail": errors
                 })),
-            );
+            ));
         }
     }
 
-    if data_contato.val_solicitado > BigDecimal::from_str("15000").unwrap() {
-        return (
+    if data_contato.val_solicitado > BigDecimal::from(15000) {
+        return Ok((
             StatusCode::BAD_REQUEST,
             Json(serde_json::json!({
                 "status": "error",
                 "detail": "Valor solicitado não pode ser superior a R$ 15.000,00"
             })),
-        );
+        ));
     }
 
     match form_data.validate() {
         Ok(_) => {}
         Err(errors) => {
-            return (
+            return Ok((
                 StatusCode::BAD_REQUEST,
                 Json(serde_json::json!({
                     "status": "error",
                     "detail": errors
                 })),
-            );
+            ));
         }
     }
 
@@ -707,34 +464,31 @@ pub async fn create_contato_pronaf(
         if let Some(doc) = DOC_PRONAF.iter().find(|d| d.id == name) {
             if doc.obrigatorio {
                 if data.is_empty() {
-                    return (
+                    return Ok((
                         StatusCode::BAD_REQUEST,
                         Json(serde_json::json!({
                             "status": "error",
                             "errors": format!("Documento {} é obrigatório", doc.id)
                         })),
-                    );
+                    ));
                 }
             }
         }
     }
 
-    /* println!("valor: {:?}", Some(&form_data.valor_estimado_imovel));
-    println!("Form Data: {:?}", form_data); */
-
     //obten linha
     let linha;
 
     match service_linha.get_by_id(&*state.db, 8).await {
         Ok(l) => linha = l,
         Err(err) => {
-            return (
+            return Ok((
                 StatusCode::BAD_REQUEST,
                 Json(serde_json::json!({
                     "status": "error",
-                    "detail": format!("Linha Pronaf não encontrada: {}", err)
+                    "detail": format!("Linha Pronaf não encontrada: {}", mensagem_erro(err))
                 })),
-            );
+            ));
         }
     }
 
@@ -749,31 +503,22 @@ pub async fn create_contato_pronaf(
         )
         .await
     {
-        Ok(contato) => {
-            return (
-                StatusCode::CREATED,
-                Json(serde_json::json!({
-                    "code": 200,
-                    "status": "success",
-                    "data": contato
-                })),
-            );
-        }
-        Err(err) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "status": "error",
-                    "detail": err.to_string()
-                })),
-            );
-        }
+        Ok(contato) => Ok((
+            StatusCode::CREATED,
+            Json(serde_json::json!({
+                "code": 200,
+                "status": "success",
+                "data": contato
+            })),
+        )),
+        Err(err) => Ok((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "status": "error",
+                "detail": mensagem_erro(err)
+            })),
+        )),
     }
-
-    /*
-    let resultado = agrupa_items(itens);
-    println!("{}", serde_json::to_string_pretty(&resultado).unwrap());
-     */
 }
 
 /*
@@ -818,29 +563,26 @@ pub async fn create_contato(
 ) -> impl IntoResponse {
     let service = ContatoService::new();
 
-    let flash_url = helpers::create_flash_url(
-        &format!("/externo/contato-form/"),
+    flash_redirect(
+        "/externo/contato-form/",
         "Contato criado com sucesso!",
         FlashStatus::Success,
-    );
-    Redirect::to(&flash_url).into_response()
+    )
 
     /* match service.create(&*state.db, body).await {
         Ok(contato) => {
-            let flash_url = helpers::create_flash_url(
+            flash_redirect(
                 &format!("/externo/contato-form/{}", contato.id),
                 "Contato criado com sucesso!",
                 FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
+            )
         }
         Err(err) => {
-            let flash_url = helpers::create_flash_url(
+            flash_redirect(
                 "/externo/contato-form",
-                &format!("Erro ao criar contato: {}", err),
+                &format!("Erro ao criar contato: {}", mensagem_erro(err)),
                 FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
+            )
         }
     } */
 }
@@ -848,88 +590,51 @@ pub async fn create_contato(
 pub async fn get_contato(
     State(state): State<SharedState>,
     Path(id): Path<Uuid>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
+) -> Result<Html<String>, AppError> {
     let service = ContatoService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Carregar o template
-    let template = match state.templates.get_template("externo/contato_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
+    let template = state.templates.get_template("externo/contato_form.html")?;
 
-    let contato = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar contato: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/externo/contato-form",
-                &format!("contato não encontrado: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
+    let contato = service.get_by_id(&state.db, id).await?;
 
     // Preparar o contexto
     let ctx = context! {
         row => contato,
-        flash_message => flash_message,
-        flash_status => flash_status,
     };
 
     // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(Html(template.render(&ctx)?))
 }
 
 pub async fn update_contato(
     State(state): State<SharedState>,
     Extension(current_user): Extension<CurrentUser>,
     Path(id): Path<Uuid>,
-    Form(input): Form<UpdateContato>,
+    Form(mut input): Form<UpdateContato>,
 ) -> impl IntoResponse {
     let service = ContatoService::new();
 
+    input.updated_by = Some(current_user.current_user.id);
+    let enviado = serde_json::to_value(&input).unwrap_or_default();
+
     match service.update(&*state.db, id, input).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/contato"),
-                &format!("Contato atualizado com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/externo/contato",
+            "Contato atualizado com sucesso!",
+            FlashStatus::Success,
+        ),
         Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/linha-form/{}", id),
-                &format!("Erro ao atualizar linha: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
+            let url_form = format!("/externo/contato-form/{}", id);
+            if let Some(conflito) = err.downcast_ref::<ConflictError>() {
+                conflito_response(&state, conflito, &enviado, &url_form).await
+            } else {
+                flash_redirect(
+                    &url_form,
+                    &format!("Erro ao atualizar contato: {}", mensagem_erro(err)),
+                    FlashStatus::Error,
+                )
+            }
         }
     }
 }
@@ -942,22 +647,16 @@ pub async fn delete_contato(
     let service = ContatoService::new();
 
     match service.delete(&*state.db, id).await {
-        Ok(()) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/contato",
-                "Contato excluído com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/contato",
-                &format!("Erro ao excluir contato: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(()) => flash_redirect(
+            "/externo/contato",
+            "Contato excluído com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/contato",
+            &format!("Erro ao excluir contato: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -972,103 +671,39 @@ pub async fn delete_contato(
 pub async fn list_regiao(
     State(state): State<SharedState>,
     Query(params): Query<ListParams>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service = RegiaoService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
+    let list_query = params.list_query();
 
     // Usar o PermissionService para buscar dados paginados
-    let permissions_result = service
-        .get_paginated(
-            &state.db,
-            params.find.as_deref(),
-            params.page.unwrap_or(1),
-            params.page_size.unwrap_or(10),
-        )
-        .await;
-
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-
-            match state.templates.get_template("externo/regiao_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar regiões: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/",
-                &format!("Erro ao carregar regiões: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
+    let permissions_result = service.list(&state.db, &list_query).await;
+
+    let paginated_response = permissions_result?;
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.unwrap_or_default(),
+        ordem => minijinja::context! {
+            sort => list_query.sort,
+            dir => list_query.dir,
+            query => list_query.base_query_string(),
+        },
+        sort_query => list_query.sort_query_string(),
+    };
+
+    helpers::render(&state.templates, "externo/regiao_list.html", context)
 }
 
 pub async fn regiao_form(
     State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
+) -> Result<Html<String>, AppError> {
+    let context = minijinja::context! {};
 
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    match state.templates.get_template("externo/regiao_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "externo/regiao_form.html", context)
 }
 
 pub async fn create_regiao(
@@ -1077,95 +712,47 @@ pub async fn create_regiao(
     Form(mut body): Form<CreateRegiaoSchema>,
 ) -> impl IntoResponse {
     if !current_user.current_user.is_superuser {
-        let flash_url = helpers::create_flash_url(
-            &format!("/externo/regiao"),
+        return flash_redirect(
+            "/externo/regiao",
             &"Você não tem permissão para criar uma região".to_string(),
             FlashStatus::Error,
         );
-        return Redirect::to(&flash_url).into_response();
     }
 
     let service = RegiaoService::new();
 
     match service.create(&*state.db, body).await {
-        Ok(regiao) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/regiao-form/{}", regiao.id),
-                "Região criada com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao-form",
-                &format!("Erro ao criar região: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(regiao) => flash_redirect(
+            &format!("/externo/regiao-form/{}", regiao.id),
+            "Região criada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao-form",
+            &format!("Erro ao criar região: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
 pub async fn get_regiao(
     State(state): State<SharedState>,
     Path(id): Path<i32>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
+) -> Result<Html<String>, AppError> {
     let service = RegiaoService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Carregar o template
-    let template = match state.templates.get_template("externo/regiao_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
+    let template = state.templates.get_template("externo/regiao_form.html")?;
 
-    let regiao = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar regiao: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao-form",
-                &format!("regiao não encontrada: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
+    let regiao = service.get_by_id(&state.db, id).await?;
 
     // Preparar o contexto
     let ctx = context! {
         row => regiao,
-        flash_message => flash_message,
-        flash_status => flash_status,
     };
 
     // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(Html(template.render(&ctx)?))
 }
 
 pub async fn update_regiao(
@@ -1177,22 +764,16 @@ pub async fn update_regiao(
     let service = RegiaoService::new();
 
     match service.update(&*state.db, id, input).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/regiao-form/{}", id),
-                &format!("Região atualizada com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/regiao"),
-                &format!("Erro ao atualizar região: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            &format!("/externo/regiao-form/{}", id),
+            "Região atualizada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao",
+            &format!("Erro ao atualizar região: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1204,22 +785,86 @@ pub async fn delete_regiao(
     let service = RegiaoService::new();
 
     match service.delete(&*state.db, id).await {
-        Ok(()) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao",
-                "Região excluída com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao",
-                &format!("Erro ao excluir região: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(()) => flash_redirect(
+            "/externo/regiao",
+            "Região excluída com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao",
+            &format!("Erro ao excluir região: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
+    }
+}
+
+pub async fn lixeira_regiao(
+    State(state): State<SharedState>,
+    Query(params): Query<ListParams>,
+) -> Result<Html<String>, AppError> {
+    let service = RegiaoService::new();
+
+    let list_query = params.list_query().trash();
+
+    let paginated_response = service.list(&state.db, &list_query).await?;
+    let context = minijinja::context! {
+        titulo => "Regiões",
+        base_url => "/externo/regiao",
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.unwrap_or_default(),
+        ordem => minijinja::context! {
+            sort => list_query.sort,
+            dir => list_query.dir,
+            query => list_query.base_query_string(),
+        },
+        sort_query => list_query.sort_query_string(),
+    };
+
+    helpers::render(&state.templates, "shared/lixeira.html", context)
+}
+
+pub async fn restore_regiao(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let service = RegiaoService::new();
+    match service.restore(&state.db, id).await {
+        Ok(()) => flash_redirect(
+            "/externo/regiao",
+            "Região restaurada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao/lixeira",
+            &format!("Erro ao restaurar região: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
+    }
+}
+
+pub async fn purge_regiao(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    let service = RegiaoService::new();
+    match service.purge(&state.db, id).await {
+        Ok(()) => flash_redirect(
+            "/externo/regiao/lixeira",
+            "Região excluída definitivamente!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao/lixeira",
+            &format!(
+                "Erro ao excluir região definitivamente: {}",
+                mensagem_erro(err)
+            ),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1227,23 +872,23 @@ pub async fn delete_regiao(
 api regiao
 
 */
+#[utoipa::path(
+    get,
+    path = "/regiao-api",
+    tag = "externo",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<Regiao>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn regiao_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<Regiao>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<Regiao>>, AppError> {
     let service = RegiaoService::new();
-    let res = service
-        .get_paginated(
-            &state.db,
-            q.find.as_deref(),
-            q.page.unwrap_or(1) as i32,
-            q.page_size.unwrap_or(10) as i32,
-        )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let res = service.list(&state.db, &q.list_query()).await?;
 
     Ok(Json(res))
 }
@@ -1259,7 +904,7 @@ pub async fn get_gestao_regiao(
     Query(params): Query<ListParams>,
     Query(form): Query<IdParams>,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service_regiao = RegiaoService::new();
     let service = RegiaoCidadesService::new();
 
@@ -1267,53 +912,38 @@ pub async fn get_gestao_regiao(
 
     let mut context = minijinja::context! {};
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     match form.id {
         Some(id) => {
-            regiao = Some(
-                service_regiao
-                    .get_by_id(&*state.db, id as i32)
-                    .await
-                    .unwrap(),
-            );
+            regiao = Some(service_regiao.get_by_id(&state.db, id as i32).await?);
             let result = service
                 .get_paginated_by_regiao_id(
                     &state.db,
-                    regiao.clone().unwrap().id,
+                    id as i32,
                     params.page.unwrap_or(1),
                     params.page_size.unwrap_or(10),
                 )
                 .await;
+            let disponiveis = service
+                .municipios_disponiveis(&state.db, id as i32)
+                .await
+                .unwrap_or_default();
 
             match result {
                 Ok(paginated_response) => {
                     context = minijinja::context! {
                         regiao => Some(regiao),
+                        disponiveis => disponiveis,
                         rows => paginated_response.data,
                         current_page => paginated_response.page,
                         total_pages => paginated_response.total_pages,
                         page_size => paginated_response.page_size,
                         total_records => paginated_response.total_records,
                         find => params.find.unwrap_or_default(),
-                        flash_message => flash_message,
-                        flash_status => flash_status,
                     };
                 }
                 Err(_err) => {
                     context = minijinja::context! {
                         regiao => Some(regiao),
-                        flash_message => flash_message,
-                        flash_status => flash_status,
                     };
                 }
             }
@@ -1321,21 +951,7 @@ pub async fn get_gestao_regiao(
         None => (),
     }
 
-    match state.templates.get_template("externo/regiao_gestao.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "externo/regiao_gestao.html", context)
 }
 
 pub async fn create_gestao_regiao(
@@ -1347,47 +963,79 @@ pub async fn create_gestao_regiao(
     let id: i32 = body.regiao_id;
 
     match service.create(&state.db, body).await {
-        Ok(regiao_cidades) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/regiao-gestao?id={}", id.to_string()),
-                "Municipio adicionada com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao-gestao",
-                &format!("Erro ao adicionar municipio: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            &format!("/externo/regiao-gestao?id={}", id),
+            "Municipio adicionada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao-gestao",
+            &format!("Erro ao adicionar municipio: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
 pub async fn delete_gestao_regiao(
     State(state): State<SharedState>,
-    Path(id): Path<i64>,
+    Path(id): Path<i32>,
 ) -> Response {
     let service = RegiaoCidadesService::new();
 
-    match service.delete(&state.db, id.try_into().unwrap()).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao-gestao",
-                "Municipio removido com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+    match service.delete(&state.db, id).await {
+        Ok(_) => flash_redirect(
+            "/externo/regiao-gestao",
+            "Municipio removido com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao-gestao",
+            &format!("Erro ao remover região: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
+    }
+}
+
+/*
+inclusão/remoção em lote: selecionados (ids) ou todos os municípios
+*/
+pub async fn lote_gestao_regiao(
+    State(state): State<SharedState>,
+    Form(body): Form<LoteForm>,
+) -> Response {
+    let service = RegiaoCidadesService::new();
+
+    let regiao_id = body.owner_id;
+    let url = format!("/externo/regiao-gestao?id={}", regiao_id);
+
+    let result = match body.ids() {
+        Ok(ids) => match body.acao {
+            AcaoLote::Adicionar => service.create_many(&state.db, regiao_id, &ids).await,
+            AcaoLote::Remover => service.delete_many(&state.db, regiao_id, &ids).await,
+            AcaoLote::AdicionarTodos => service.create_all(&state.db, regiao_id).await,
+            AcaoLote::RemoverTodos => service.delete_all(&state.db, regiao_id).await,
+        },
+        Err(err) => Err(err),
+    };
+
+    let verbo = match body.acao {
+        AcaoLote::Adicionar | AcaoLote::AdicionarTodos => "adicionado(s)",
+        AcaoLote::Remover | AcaoLote::RemoverTodos => "removido(s)",
+    };
+
+    match result {
+        Ok(total) => flash_redirect(
+            &url,
+            &format!("{} município(s) {}", total, verbo),
+            FlashStatus::Success,
+        ),
         Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao-gestao",
-                &format!("Erro ao remover região: {}", err),
+            debug!("Erro na gestão em lote da região: {}", err);
+            flash_redirect(
+                &url,
+                &format!("Erro ao atualizar municípios: {}", mensagem_erro(err)),
                 FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
+            )
         }
     }
 }
@@ -1403,7 +1051,7 @@ pub async fn get_regiao_por_usuario(
     Query(params): Query<ListParams>,
     Query(form): Query<IdParams>,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service_user = UserService::new();
     let service = UserRegiaoService::new();
 
@@ -1411,20 +1059,9 @@ pub async fn get_regiao_por_usuario(
 
     let mut context = minijinja::context! {};
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     match form.id {
         Some(id) => {
-            usuario = Some(service_user.get_by_id(&*state.db, id).await.unwrap());
+            usuario = Some(service_user.get_by_id(&state.db, id).await?);
             let result = service
                 .get_paginated_by_user_id(
                     &state.db,
@@ -1444,15 +1081,11 @@ pub async fn get_regiao_por_usuario(
                         page_size => paginated_response.page_size,
                         total_records => paginated_response.total_records,
                         find => params.find.unwrap_or_default(),
-                        flash_message => flash_message,
-                        flash_status => flash_status,
                     };
                 }
                 Err(_err) => {
                     context = minijinja::context! {
                         usuario => Some(usuario),
-                        flash_message => flash_message,
-                        flash_status => flash_status,
                     };
                 }
             }
@@ -1460,21 +1093,7 @@ pub async fn get_regiao_por_usuario(
         None => (),
     }
 
-    match state.templates.get_template("externo/regiao_user.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "externo/regiao_user.html", context)
 }
 
 pub async fn create_regiao_por_usuario(
@@ -1486,48 +1105,36 @@ pub async fn create_regiao_por_usuario(
     let id: i32 = body.regiao_id;
 
     match service.create(&state.db, body).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/regiao-user?id={}", id.to_string()),
-                "Região adicionada com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao-user",
-                &format!("Erro ao adicionar região: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            &format!("/externo/regiao-user?id={}", id),
+            "Região adicionada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao-user",
+            &format!("Erro ao adicionar região: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
 pub async fn delete_regiao_por_usuario(
     State(state): State<SharedState>,
-    Path(id): Path<i64>,
+    Path(id): Path<i32>,
 ) -> Response {
     let service = UserRegiaoService::new();
 
-    match service.delete(&state.db, id.try_into().unwrap()).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao-user",
-                "Região removida com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/regiao-user",
-                &format!("Erro ao remover região: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+    match service.delete(&state.db, id).await {
+        Ok(_) => flash_redirect(
+            "/externo/regiao-user",
+            "Região removida com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/regiao-user",
+            &format!("Erro ao remover região: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1542,7 +1149,7 @@ pub async fn get_linha_por_usuario(
     Query(params): Query<ListParams>,
     Query(form): Query<IdParams>,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service_user = UserService::new();
     let service = UserLinhaService::new();
 
@@ -1550,25 +1157,9 @@ pub async fn get_linha_por_usuario(
 
     let mut context = minijinja::context! {};
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
     match form.id {
         Some(id) => {
-            usuario = Some(service_user.get_by_id(&*state.db, id).await.unwrap());
+            usuario = Some(service_user.get_by_id(&state.db, id).await?);
             let result = service
                 .get_paginated_by_user_id(
                     &state.db,
@@ -1588,15 +1179,11 @@ pub async fn get_linha_por_usuario(
                         page_size => paginated_response.page_size,
                         total_records => paginated_response.total_records,
                         find => params.find.unwrap_or_default(),
-                        flash_message => flash_message,
-                        flash_status => flash_status,
                     };
                 }
                 Err(_err) => {
                     context = minijinja::context! {
                         usuario => Some(usuario),
-                        flash_message => flash_message,
-                        flash_status => flash_status,
                     };
                 }
             }
@@ -1604,21 +1191,7 @@ pub async fn get_linha_por_usuario(
         None => (),
     }
 
-    match state.templates.get_template("externo/linha_user.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "externo/linha_user.html", context)
 }
 
 pub async fn create_linha_por_usuario(
@@ -1630,47 +1203,35 @@ pub async fn create_linha_por_usuario(
     let id: i32 = body.user_id;
 
     match service.create(&state.db, body).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/externo/linha-user?id={}", id.to_string()),
-                "Linha adicionada com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/linha-user",
-                &format!("Erro ao adicionar linha: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            &format!("/externo/linha-user?id={}", id),
+            "Linha adicionada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/linha-user",
+            &format!("Erro ao adicionar linha: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
 pub async fn delete_linha_por_usuario(
     State(state): State<SharedState>,
-    Path(id): Path<i64>,
+    Path(id): Path<i32>,
 ) -> Response {
     let service = UserLinhaService::new();
 
-    match service.delete(&state.db, id.try_into().unwrap()).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/linha-user",
-                "Linha removida com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/externo/linha-user",
-                &format!("Erro ao remover região: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+    match service.delete(&state.db, id).await {
+        Ok(_) => flash_redirect(
+            "/externo/linha-user",
+            "Linha removida com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/externo/linha-user",
+            &format!("Erro ao remover região: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
@@ -11,7 +11,7 @@ pub use string::*;
 use minijinja::Environment;
 
 use crate::{
-    chamado::status_filter, externo::filters::{contato_color_filter, contato_status_filter}, filters::number::{currency_brl, format_decimal}
+    chamado::{duracao_filter, nivel_filter, prioridade_badge_filter, status_filter}, externo::filters::{contato_color_filter, contato_status_filter}, filters::number::{currency_brl, format_decimal}
 };
 
 /// Registra todos os filtros no ambiente MiniJinja
@@ -38,6 +38,9 @@ pub fn register_filters(env: &mut Environment) {
 
     // Chamado filters
     env.add_filter("status_label", status_filter);
+    env.add_filter("duracao", duracao_filter);
+    env.add_filter("prioridade_badge", prioridade_badge_filter);
+    env.add_filter("nivel_label", nivel_filter);
 
     //modulos
     env.add_filter("contato_color_filter", contato_color_filter);
@@ -72,4 +75,12 @@ mod tests {
             "\"R$ 0,00\""
         );
     }
+
+    #[test]
+    fn test_duracao() {
+        assert_eq!(super::duracao_filter(30), "menos de 1min");
+        assert_eq!(super::duracao_filter(45 * 60), "45min");
+        assert_eq!(super::duracao_filter(2 * 3600 + 5 * 60), "2h 5min");
+        assert_eq!(super::duracao_filter(3 * 86400 + 4 * 3600 + 59), "3d 4h");
+    }
 }
@@ -1,19 +1,23 @@
+mod api;
 mod chamado;
+mod cli;
 mod core;
 mod externo;
 mod filters;
 mod middlewares;
+mod notificacao;
+mod openapi;
 mod permissao;
 mod utils;
 
-use std::{collections::HashMap, env, sync::Arc};
+use std::{env, sync::Arc};
 
 use axum::{
-    Form, Router,
+    Extension, Form, Router,
     body::Body,
-    extract::{Query, State},
+    extract::State,
     http::{
-        HeaderValue, Method, Response, StatusCode,
+        HeaderValue, Method, Response,
         header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, SET_COOKIE},
     },
     middleware::{self},
@@ -28,7 +32,7 @@ use time::{Duration, OffsetDateTime, format_description::well_known::Rfc2822};
 use tokio;
 use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
 use tower_sessions::{MemoryStore, SessionManagerLayer};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use dotenv::dotenv;
@@ -38,13 +42,16 @@ use chamado::router as router_chamado;
 use core::{router as router_core, router_public as router_public_core};
 use externo::router as router_externo;
 use permissao::router as router_permissao;
-use shared::{AppState, FlashStatus, MessageResponse, SharedState, helpers};
+use shared::{
+    AppError, AppState, FlashStatus, MessageResponse, SharedState, flash_redirect, helpers,
+};
 
 use crate::{
     core::serve_upload,
     filters::register_filters,
     middlewares::handle_forbidden,
     permissao::{Module, UserService},
+    utils::shutdown::{Workers, shutdown_signal, shutdown_timeout},
 };
 
 async fn hello_world() -> &'static str {
@@ -62,6 +69,20 @@ struct LoginPayload {
 async fn main() {
     dotenv().ok();
 
+    // Comandos administrativos: `app migrate`, `app seed-dev`, ...
+    let args: Vec<String> = env::args().skip(1).collect();
+    let comando = match cli::Comando::parse(&args) {
+        Ok(comando) => comando,
+        Err(e) => {
+            eprintln!("Erro: {}\n\n{}", e, cli::uso());
+            std::process::exit(2);
+        }
+    };
+    if comando == cli::Comando::Help {
+        println!("{}", cli::uso());
+        return;
+    }
+
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let db_pool = PgPoolOptions::new()
         .max_connections(10)
@@ -69,6 +90,14 @@ async fn main() {
         .await
         .expect("Failed to connect to the database");
 
+    if comando != cli::Comando::Serve {
+        if let Err(e) = cli::run(&db_pool, comando).await {
+            eprintln!("Erro: {:#}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Carrega os templates
     // Crie o ambiente MiniJinja
     let mut env = Environment::new();
@@ -76,10 +105,28 @@ async fn main() {
     // Registre os filtros
     register_filters(&mut env);
 
+    // mensagens flash da sessão, disponíveis em qualquer template
+    env.add_function("flash_messages", shared::flash::flash_messages);
+
     let templates = Arc::new(env);
 
+    // tarefas em segundo plano, encerradas junto com o servidor
+    let workers = Workers::new();
+    workers.spawn("verificador_sla", |token| {
+        chamado::verificador_sla(db_pool.clone(), token)
+    });
+    workers.spawn("entregador_email", |token| {
+        notificacao::entregador_email(db_pool.clone(), templates.clone(), token)
+    });
+
+    // eventos de chamados (LISTEN/NOTIFY) repassados às listagens abertas via SSE
+    let canal_chamados = chamado::CanalChamados::new(workers.token());
+    workers.spawn("ouvinte_chamados", |token| {
+        chamado::ouvinte_chamados(db_pool.clone(), canal_chamados.clone(), token)
+    });
+
     let state = Arc::new(AppState {
-        db: Arc::new(db_pool),
+        db: Arc::new(db_pool.clone()),
         templates,
         message: Arc::new(MessageResponse {
             status: "info".to_string(),
@@ -99,15 +146,6 @@ async fn main() {
 
     let server_dir = ServeDir::new("static");
 
-    let store = MemoryStore::default();
-    let session_layer = SessionManagerLayer::new(store).with_secure(false); // true em produção
-
-    let cors = CorsLayer::new()
-        .allow_origin("http://localhost:2000".parse::<HeaderValue>().unwrap())
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_credentials(true)
-        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
-
     let rotas_privadas = Router::new()
         .route("/", get(index))
         .route(
@@ -122,87 +160,105 @@ async fn main() {
         .nest("/chamado", router_chamado())
         .nest("/core", router_core())
         .nest("/externo", router_externo())
+        .nest("/notificacao", notificacao::router())
+        .layer(Extension(canal_chamados))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             middlewares::autenticar,
         ));
 
-    let app = Router::new()
+    let rotas = Router::new()
         .route("/hello", get(hello_world))
         .route("/login", get(get_login).post(login))
         .nest("/core", router_public_core()) //apis publicas
+        .merge(openapi::router()) // /api/openapi.json e /api/docs
+        .merge(api::router(state.clone())) // /api/v1, autenticação Bearer
         .nest_service("/static", server_dir)
-        .layer(session_layer) // Sessões devem vir antes do CORS
-        .layer(cors)
-        .layer(TraceLayer::new_for_http())
-        .layer(middleware::from_fn(handle_forbidden)) // Middleware para 403
-        .merge(rotas_privadas)
+        .merge(rotas_privadas);
+
+    let app = com_camadas(rotas)
         .fallback(page_not_found_handler)
         //.method_not_allowed_fallback(page_metodo_proibido_handler)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            shared::negociar_erro,
+        )) // AppError vira página HTML para navegadores
         .with_state(state.clone());
 
     info!("Starting server on http://0.0.0.0:2000");
     debug!("Server running");
     //println!("Server running on http://0.0.0.0:2000");
-    axum::serve(listener, app).await.unwrap();
+
+    // Ao receber SIGTERM/SIGINT para de aceitar conexões e aguarda as requisições
+    // em andamento (inclusive uploads) até o tempo limite
+    let token = workers.token();
+    let mut server = tokio::spawn(
+        axum::serve(listener, app)
+            .with_graceful_shutdown(token.clone().cancelled_owned())
+            .into_future(),
+    );
+
+    // um só prazo para o servidor e as tarefas em segundo plano, contado a partir do sinal
+    let limite = tokio::select! {
+        resultado = &mut server => {
+            if let Ok(Err(e)) = resultado {
+                warn!("servidor encerrado com erro: {}", e);
+            }
+            tokio::time::Instant::now() + shutdown_timeout()
+        }
+        _ = shutdown_signal() => {
+            token.cancel();
+            let timeout = shutdown_timeout();
+            let limite = tokio::time::Instant::now() + timeout;
+            if tokio::time::timeout_at(limite, &mut server).await.is_err() {
+                warn!("requisições em andamento não concluíram em {:?}, abortando", timeout);
+                server.abort();
+            }
+            limite
+        }
+    };
+
+    workers
+        .shutdown(limite.saturating_duration_since(tokio::time::Instant::now()))
+        .await;
+    db_pool.close().await;
+    info!("Servidor encerrado");
+}
+
+/*
+sessão, flash, CORS, trace e 403 para todas as rotas
+deve ser aplicado depois do último merge: um layer só envolve as rotas já adicionadas
+*/
+fn com_camadas(rotas: Router<SharedState>) -> Router<SharedState> {
+    let store = MemoryStore::default();
+    let session_layer = SessionManagerLayer::new(store).with_secure(false); // true em produção
+
+    let cors = CorsLayer::new()
+        .allow_origin("http://localhost:2000".parse::<HeaderValue>().unwrap())
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_credentials(true)
+        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
+
+    rotas
+        .layer(middleware::from_fn(shared::flash_middleware)) // mensagens flash usam a sessão
+        .layer(session_layer) // Sessões devem vir antes do CORS
+        .layer(cors)
+        .layer(TraceLayer::new_for_http())
+        .layer(middleware::from_fn(handle_forbidden)) // Middleware para 403
 }
 
 async fn rota_privada() -> &'static str {
     "Acesso privado: você está autenticado!"
 }
 
-async fn index(State(state): State<SharedState>) -> Result<Html<String>, impl IntoResponse> {
-    match state.templates.get_template("principal.html") {
-        Ok(template) => match template.render({}) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+async fn index(State(state): State<SharedState>) -> Result<Html<String>, AppError> {
+    helpers::render(&state.templates, "principal.html", minijinja::context! {})
 }
 
-async fn get_login(
-    State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, impl IntoResponse> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
+async fn get_login(State(state): State<SharedState>) -> Result<Html<String>, AppError> {
+    let context = minijinja::context! {};
 
-    match state.templates.get_template("login.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "login.html", context)
 }
 
 async fn login(
@@ -211,34 +267,13 @@ async fn login(
 ) -> Response<Body> {
     match UserService::get_by_username(&state.db, &payload.username).await {
         Ok(user) => {
-            if !user.is_active {
-                let flash_url = helpers::create_flash_url(
-                    "/login",
-                    "Incorrect username or password",
-                    FlashStatus::Error,
-                );
-                return Redirect::to(&flash_url).into_response();
-            }
-
-            if let Ok(false) | Err(_) =
-                UserService::verify_password(&payload.password, &user.password)
-            {
-                let flash_url = helpers::create_flash_url(
-                    "/login",
-                    "Incorrect username or password",
-                    FlashStatus::Error,
-                );
-                return Redirect::to(&flash_url).into_response();
-            }
-
-            if !UserService::is_valid_otp(&payload.client_secret, &user.otp_base32.clone().unwrap())
+            if !UserService::credenciais_validas(&user, &payload.password, &payload.client_secret)
             {
-                let flash_url = helpers::create_flash_url(
+                return flash_redirect(
                     "/login",
                     "Incorrect username or password",
                     FlashStatus::Error,
                 );
-                return Redirect::to(&flash_url).into_response();
             }
 
             let access_token = middlewares::gerar_token(&user.username);
@@ -314,14 +349,11 @@ async fn login(
 
             response
         }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/login",
-                &format!("Senha não atualizada: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Err(err) => flash_redirect(
+            "/login",
+            &format!("Senha não atualizada: {}", err),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -356,67 +388,105 @@ async fn logout() -> impl IntoResponse {
 
 pub async fn page_not_found_handler(
     State(state): State<SharedState>,
-) -> Result<Html<String>, impl IntoResponse> {
-    match state.templates.get_template("404.html") {
-        Ok(template) => match template.render({}) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+) -> Result<Html<String>, AppError> {
+    helpers::render(&state.templates, "404.html", minijinja::context! {})
 }
 
 pub async fn page_metodo_proibido_handler(
     State(state): State<SharedState>,
-) -> Result<Html<String>, impl IntoResponse> {
-    match state.templates.get_template("403.html") {
-        Ok(template) => match template.render({}) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+) -> Result<Html<String>, AppError> {
+    helpers::render(&state.templates, "403.html", minijinja::context! {})
 }
 
 // Handler para renderizar erro 500 como página HTML
-pub async fn error_500_handler(state: Arc<AppState>) -> Result<Html<String>, impl IntoResponse> {
-    match state.templates.get_template("500.html") {
-        Ok(template) => match template.render({}) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+pub async fn error_500_handler(state: Arc<AppState>) -> Result<Html<String>, AppError> {
+    helpers::render(&state.templates, "500.html", minijinja::context! {})
 }
 
 #[cfg(test)]
 mod tests {
+    use axum::{body::Body, http::Request, routing::post};
+    use tower::ServiceExt;
+
+    use super::*;
+
     #[test]
     fn test_soma() {
         assert_eq!(1 + 1, 2);
     }
+
+    #[tokio::test]
+    async fn test_flash_de_rota_privada_aparece_na_pagina_seguinte() {
+        let mut env = Environment::new();
+        env.add_function("flash_messages", shared::flash::flash_messages);
+        env.add_template(
+            "pagina.html",
+            "{% for m in flash_messages() %}{{ m.message }}{% endfor %}",
+        )
+        .unwrap();
+        let state = Arc::new(AppState {
+            db: Arc::new(
+                PgPoolOptions::new()
+                    .connect_lazy("postgres://localhost/nada")
+                    .unwrap(),
+            ),
+            templates: Arc::new(env),
+            message: Arc::new(MessageResponse {
+                status: "info".to_string(),
+                message: String::new(),
+            }),
+        });
+
+        // privadas entram por merge, como em main
+        let privadas = Router::new()
+            .route(
+                "/privado/acao",
+                post(|| async {
+                    flash_redirect("/privado/pagina", "Salvo!", FlashStatus::Success)
+                }),
+            )
+            .route(
+                "/privado/pagina",
+                get(|State(state): State<SharedState>| async move {
+                    helpers::render(&state.templates, "pagina.html", minijinja::context! {})
+                }),
+            );
+        let app = com_camadas(
+            Router::new()
+                .route("/hello", get(hello_world))
+                .merge(privadas),
+        )
+        .with_state(state);
+
+        let res = app
+            .clone()
+            .oneshot(Request::post("/privado/acao").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 303);
+        let cookie = res
+            .headers()
+            .get(SET_COOKIE)
+            .expect("rota privada sem sessão")
+            .to_str()
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap()
+            .to_string();
+
+        let res = app
+            .oneshot(
+                Request::get("/privado/pagina")
+                    .header("cookie", cookie)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let corpo = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(std::str::from_utf8(&corpo).unwrap(), "Salvo!");
+    }
 }
@@ -5,7 +5,7 @@ use axum::{
     extract::State,
     http::{
         Request, Response, StatusCode,
-        header::{AUTHORIZATION, COOKIE},
+        header::{AUTHORIZATION, CONTENT_TYPE, COOKIE},
     },
     middleware::Next,
     response::Html,
@@ -13,7 +13,7 @@ use axum::{
 };
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
-use shared::SharedState;
+use shared::{AppError, SharedState};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info};
 
@@ -25,6 +25,14 @@ pub struct CurrentUser {
     pub permissions: Vec<String>,
 }
 
+impl CurrentUser {
+    /// Mesma regra de `require_roles`: superusuário ou permissão com o nome informado
+    pub fn tem_permissao(&self, nome: &str) -> bool {
+        self.current_user.is_superuser
+            || self.permissions.iter().any(|p| p.eq_ignore_ascii_case(nome))
+    }
+}
+
 // Middleware de autenticação JWT
 pub async fn autenticar(
     State(state): State<SharedState>,
@@ -60,68 +68,101 @@ pub async fn autenticar(
     // Usa o token do header ou do cookie
     let token = auth_header.or(cookie_token);
 
-    match token {
-        Some(token) => {
-            // Decodifica o token percent-encoded se necessário
-            let decoded_token = percent_encoding::percent_decode_str(&token)
-                .decode_utf8()
-                .unwrap_or_default()
-                .to_string();
-
-            match decode::<Claims>(
-                &decoded_token,
-                &DecodingKey::from_secret(SECRET),
-                &Validation::default(),
-            ) {
-                Ok(data) => {
-                    // Adiciona as claims do usuário às extensões da requisição
-                    let mut req = req;
-                    req.extensions_mut().insert(data.claims.clone());
-
-                    //busca usuario:
-                    let mut user = UserService::get_by_username(&*state.db, &data.claims.sub)
-                        .await
-                        .map_err(|_| Redirect::to("/login").into_response());
-
-                    let user_id = user.as_mut().unwrap().id;
-                    // Busca permissões do usuário
-                    let permissions = UserService::get_user_permissions(&*state.db, user_id).await;
-
-                    // Adiciona o usuário logado às extensões
-                    req.extensions_mut().insert(CurrentUser {
-                        current_user: user.unwrap(),
-                        permissions,
-                    });
-
-                    next.run(req).await
-                }
-                Err(e) => {
-                    debug!("Erro ao decodificar token: {}", e);
-                    //(StatusCode::UNAUTHORIZED, "Token inválido").into_response()
-                    Redirect::to("/login").into_response()
-                }
-            }
-        }
-        None => {
-            debug!("Token não encontrado no header Authorization nem no cookie");
-            //(StatusCode::UNAUTHORIZED, "Token ausente").into_response()
-            Redirect::to("/login").into_response()
+    let Some(token) = token else {
+        debug!("Token não encontrado no header Authorization nem no cookie");
+        return Redirect::to("/login").into_response();
+    };
+
+    match usuario_do_token(&state, &token).await {
+        Some((claims, current_user)) => {
+            // Adiciona as claims e o usuário logado às extensões da requisição
+            let mut req = req;
+            req.extensions_mut().insert(claims);
+            req.extensions_mut().insert(current_user);
+
+            next.run(req).await
         }
+        None => Redirect::to("/login").into_response(),
     }
 }
 
+/*
+middleware das rotas /api/v1: somente `Authorization: Bearer` (sem cookie, então
+sem risco de CSRF) e resposta 401 em JSON no lugar do redirect para /login
+usuário desativado perde o acesso mesmo com token ainda válido
+*/
+pub async fn autenticar_api(
+    State(state): State<SharedState>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<Response<Body>, AppError> {
+    let token = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or(AppError::UserNotAuthenticated)?;
+
+    let (claims, current_user) = usuario_do_token(&state, token)
+        .await
+        .filter(|(_, current_user)| current_user.current_user.is_active)
+        .ok_or(AppError::UserNotAuthenticated)?;
+
+    req.extensions_mut().insert(claims);
+    req.extensions_mut().insert(current_user);
+
+    Ok(next.run(req).await)
+}
+
+/// Valida o JWT e carrega o usuário com as permissões; `None` se inválido, expirado ou sem usuário
+async fn usuario_do_token(state: &SharedState, token: &str) -> Option<(Claims, CurrentUser)> {
+    // Decodifica o token percent-encoded se necessário
+    let decoded_token = percent_encoding::percent_decode_str(token)
+        .decode_utf8()
+        .unwrap_or_default()
+        .to_string();
+
+    let data = decode::<Claims>(
+        &decoded_token,
+        &DecodingKey::from_secret(SECRET),
+        &Validation::default(),
+    )
+    .map_err(|e| debug!("Erro ao decodificar token: {}", e))
+    .ok()?;
+
+    //busca usuario:
+    let user = UserService::get_by_username(&state.db, &data.claims.sub)
+        .await
+        .map_err(|e| debug!("Usuário do token não encontrado: {}", e))
+        .ok()?;
+
+    // Busca permissões do usuário
+    let permissions = UserService::get_user_permissions(&state.db, user.id).await;
+
+    Some((
+        data.claims,
+        CurrentUser {
+            current_user: user,
+            permissions,
+        },
+    ))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Claims {
     sub: String,
     exp: usize,
 }
 
+/// Validade do token JWT, em segundos
+pub const VALIDADE_TOKEN: u64 = 3600;
+
 pub fn gerar_token(usuario: &str) -> String {
     let expiracao = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_secs()
-        + 3600;
+        + VALIDADE_TOKEN;
 
     let claims = Claims {
         sub: usuario.to_string(),
@@ -195,7 +236,7 @@ pub async fn role_check(
                 next.run(req).await
             } else {
                 debug!("Usuário não tem permissão para acessar este recurso");
-                (StatusCode::FORBIDDEN, "Acesso negado").into_response()
+                AppError::PermissionDenied.into_response()
             }
         }
         None => {
@@ -225,7 +266,13 @@ pub fn require_roles(
 pub async fn handle_forbidden(req: Request<Body>, next: Next) -> ResponseExt {
     let res = next.run(req).await;
 
-    if res.status() == StatusCode::FORBIDDEN {
+    // erros JSON (AppError, API v1) seguem como estão; `negociar_erro` cuida dos navegadores
+    let json = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+
+    if res.status() == StatusCode::FORBIDDEN && !json {
         return (
             StatusCode::FORBIDDEN,
             Html(
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use lettre::message::{Mailbox, header::ContentType};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use minijinja::{Environment, Value, context};
+use shared::UnitOfWork;
+use sqlx::PgPool;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::notificacao::repository::EmailRepository;
+
+/// E-mails entregues por rodada; cada um fica travado só durante o próprio envio
+const LOTE: i64 = 20;
+
+/// Transporte SMTP e remetente lidos do ambiente
+struct Smtp {
+    transporte: AsyncSmtpTransport<Tokio1Executor>,
+    remetente: Mailbox,
+}
+
+/*
+configuração pelo ambiente; sem SMTP_HOST não há entrega e os e-mails ficam na caixa de saída
+  SMTP_HOST, SMTP_PORT (padrão 587), SMTP_USUARIO, SMTP_SENHA,
+  SMTP_REMETENTE ("Nome <email>"), SMTP_TLS: starttls (padrão), tls ou nenhum
+*/
+fn configurar() -> Result<Option<Smtp>> {
+    let Ok(host) = std::env::var("SMTP_HOST") else {
+        return Ok(None);
+    };
+
+    let mut builder = match std::env::var("SMTP_TLS").as_deref() {
+        Ok("tls") => AsyncSmtpTransport::<Tokio1Executor>::relay(&host)?,
+        Ok("nenhum") => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+        _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)?,
+    };
+    if let Some(porta) = std::env::var("SMTP_PORT").ok().and_then(|p| p.parse().ok()) {
+        builder = builder.port(porta);
+    }
+    if let (Ok(usuario), Ok(senha)) = (std::env::var("SMTP_USUARIO"), std::env::var("SMTP_SENHA")) {
+        builder = builder.credentials(Credentials::new(usuario, senha));
+    }
+
+    let remetente = std::env::var("SMTP_REMETENTE")
+        .context("SMTP_REMETENTE não definido")?
+        .parse()
+        .map_err(|e| anyhow!("SMTP_REMETENTE inválido: {}", e))?;
+
+    Ok(Some(Smtp {
+        transporte: builder.build(),
+        remetente,
+    }))
+}
+
+/// Lê `EMAIL_INTERVALO_SECS` (padrão 30s)
+fn intervalo_entrega() -> std::time::Duration {
+    std::env::var("EMAIL_INTERVALO_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(std::time::Duration::from_secs(30))
+}
+
+/// Endereço do sistema nos links do e-mail, `APP_URL` (padrão http://localhost:2000)
+fn app_url() -> String {
+    std::env::var("APP_URL").unwrap_or_else(|_| "http://localhost:2000".to_string())
+}
+
+/// Espera antes da próxima tentativa: 1, 2, 4, 8... minutos
+fn espera_min(tentativas: i32) -> i32 {
+    1 << tentativas.clamp(0, 10)
+}
+
+/*
+assunto e corpo de templates/email/<evento>.html
+o assunto é o bloco "assunto"; o corpo, o template inteiro
+*/
+pub fn renderizar(
+    templates: &Environment<'_>,
+    evento: &str,
+    contexto: Value,
+) -> Result<(String, String)> {
+    let template = templates.get_template(&format!("email/{}.html", evento))?;
+    let assunto = template.eval_to_state(&contexto)?.render_block("assunto")?;
+    let corpo = template.render(&contexto)?;
+
+    Ok((assunto.trim().to_string(), corpo))
+}
+
+/*
+entregador em segundo plano (ver utils::shutdown::Workers):
+envia os e-mails da caixa de saída; a falha adia o e-mail
+até MAX_TENTATIVAS (ver notificacao::repository)
+*/
+pub async fn entregador(
+    pool: PgPool,
+    templates: Arc<Environment<'static>>,
+    token: CancellationToken,
+) {
+    let smtp = match configurar() {
+        Ok(Some(smtp)) => smtp,
+        Ok(None) => {
+            info!("SMTP_HOST não definido: e-mails de notificação ficam na caixa de saída");
+            return;
+        }
+        Err(e) => {
+            error!("configuração SMTP inválida: {:#}", e);
+            return;
+        }
+    };
+    let mut intervalo = tokio::time::interval(intervalo_entrega());
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = intervalo.tick() => {}
+        }
+
+        if let Err(e) = entregar(&pool, &templates, &smtp).await {
+            error!("falha ao entregar e-mails: {:#}", e);
+        }
+    }
+}
+
+/*
+uma rodada: até LOTE e-mails, cada um na própria transação
+o envio é registrado logo depois do SMTP, então um erro no meio da rodada
+não desfaz o que já saiu e nenhum e-mail é enviado duas vezes
+*/
+async fn entregar(pool: &PgPool, templates: &Environment<'_>, smtp: &Smtp) -> Result<()> {
+    let repo = EmailRepository;
+    let app_url = app_url();
+
+    for _ in 0..LOTE {
+        let mut uow = UnitOfWork::begin(pool).await?;
+        let Some(email) = repo.pendentes_for_update(uow.conn(), 1).await?.pop() else {
+            break;
+        };
+
+        let contexto = context! { app_url => app_url, ..Value::from_serialize(&email.contexto) };
+        let resultado = async {
+            let (assunto, corpo) = renderizar(templates, &email.evento, contexto)?;
+            let mensagem = Message::builder()
+                .from(smtp.remetente.clone())
+                .to(email.destinatario.parse()?)
+                .subject(assunto)
+                .header(ContentType::TEXT_HTML)
+                .body(corpo)?;
+            smtp.transporte.send(mensagem).await?;
+            anyhow::Ok(())
+        }
+        .await;
+
+        match resultado {
+            Ok(()) => repo.marcar_enviado(uow.conn(), email.id).await?,
+            Err(e) => {
+                warn!(
+                    "e-mail {} para {} não enviado: {:#}",
+                    email.id, email.destinatario, e
+                );
+                repo.marcar_falha(
+                    uow.conn(),
+                    email.id,
+                    &format!("{:#}", e),
+                    espera_min(email.tentativas),
+                )
+                .await?
+            }
+        }
+
+        uow.commit().await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_espera_dobra_a_cada_tentativa() {
+        assert_eq!(espera_min(0), 1);
+        assert_eq!(espera_min(1), 2);
+        assert_eq!(espera_min(4), 16);
+        assert_eq!(espera_min(50), 1024);
+    }
+
+    #[test]
+    fn test_renderizar_templates_de_email() {
+        let mut env = Environment::new();
+        env.set_loader(minijinja::path_loader("../templates"));
+        let contexto = context! {
+            app_url => "http://exemplo",
+            nome => "Ana",
+            titulo => "Chamado #7: Em Atendimento",
+            mensagem => "Aberto → Em Atendimento",
+            url => "/chamado/chamado-form/7",
+            chamado_id => 7,
+        };
+
+        for evento in crate::notificacao::Evento::TODOS {
+            let (assunto, corpo) = renderizar(&env, evento.codigo(), contexto.clone()).unwrap();
+            assert!(assunto.contains("#7"), "{}", assunto);
+            // o autoescape de .html troca "/" por &#x2f;, que o cliente de e-mail decodifica
+            assert!(
+                corpo.contains("exemplo&#x2f;chamado&#x2f;chamado-form&#x2f;7"),
+                "{}",
+                corpo
+            );
+        }
+    }
+}
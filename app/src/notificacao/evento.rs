@@ -0,0 +1,48 @@
+use serde::Serialize;
+
+/// Eventos que geram notificação; o código vai para o banco e nomeia o template de e-mail
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Evento {
+    /// status do chamado do solicitante mudou
+    Status,
+    /// comentário novo num chamado em que o usuário é solicitante ou responsável
+    Comentario,
+    /// chamado atribuído ou transferido para o atendente
+    Atribuido,
+}
+
+impl Evento {
+    pub const TODOS: [Evento; 3] = [Evento::Status, Evento::Comentario, Evento::Atribuido];
+
+    pub fn codigo(self) -> &'static str {
+        match self {
+            Evento::Status => "chamado_status",
+            Evento::Comentario => "chamado_comentario",
+            Evento::Atribuido => "chamado_atribuido",
+        }
+    }
+
+    /// Texto da tela de preferências
+    pub fn descricao(self) -> &'static str {
+        match self {
+            Evento::Status => "Mudança de status dos meus chamados",
+            Evento::Comentario => "Comentários nos chamados que acompanho",
+            Evento::Atribuido => "Chamados atribuídos a mim",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codigos_unicos() {
+        // o código identifica a preferência gravada e o template de e-mail
+        for (i, a) in Evento::TODOS.iter().enumerate() {
+            for b in &Evento::TODOS[i + 1..] {
+                assert_ne!(a.codigo(), b.codigo());
+            }
+        }
+    }
+}
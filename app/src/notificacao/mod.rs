@@ -0,0 +1,14 @@
+mod email;
+mod evento;
+mod model;
+mod repository;
+mod router;
+mod schema;
+mod service;
+mod view;
+
+pub use email::entregador as entregador_email;
+pub use evento::Evento;
+pub use router::{ApiDoc, router};
+pub use schema::NovaNotificacao;
+pub use service::NotificacaoService;
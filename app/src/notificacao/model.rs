@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Notificação interna (sino da barra superior)
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct Notificacao {
+    pub id: i64,
+    pub user_id: i64,
+    /// código do evento (ver notificacao::Evento)
+    pub evento: String,
+    pub titulo: String,
+    pub mensagem: Option<String>,
+    /// página aberta ao clicar
+    pub url: Option<String>,
+    pub lida_em: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// E-mail da caixa de saída aguardando entrega
+#[derive(Debug, FromRow)]
+pub struct EmailPendente {
+    pub id: i64,
+    pub destinatario: String,
+    pub evento: String,
+    pub contexto: Value,
+    pub tentativas: i32,
+}
+
+/// Canais escolhidos pelo usuário para um evento
+#[derive(Debug, Serialize, FromRow)]
+pub struct Preferencia {
+    pub evento: String,
+    pub interna: bool,
+    pub email: bool,
+}
+
+#[derive(Debug, FromRow)]
+pub struct Destinatario {
+    pub email: String,
+    pub full_name: String,
+}
@@ -0,0 +1,257 @@
+use anyhow::Result;
+use serde_json::Value;
+use sqlx::PgConnection;
+
+use crate::notificacao::model::{Destinatario, EmailPendente, Notificacao, Preferencia};
+use crate::notificacao::schema::NovaNotificacao;
+
+/// Tentativas de entrega antes de o e-mail ficar parado na caixa de saída
+pub const MAX_TENTATIVAS: i32 = 5;
+
+pub struct NotificacaoRepository;
+
+impl NotificacaoRepository {
+    /// Canais do evento (interna, email); sem preferência gravada, os dois
+    pub async fn canais(
+        &self,
+        conn: &mut PgConnection,
+        user_id: i64,
+        evento: &str,
+    ) -> Result<(bool, bool)> {
+        let preferencia = sqlx::query_as!(
+            Preferencia,
+            r#"
+            SELECT evento, interna, email
+            FROM notificacao_preferencia
+            WHERE user_id = $1 AND evento = $2"#,
+            user_id,
+            evento
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(preferencia.map_or((true, true), |p| (p.interna, p.email)))
+    }
+
+    /// Usuário ativo que recebe a notificação
+    pub async fn destinatario(
+        &self,
+        conn: &mut PgConnection,
+        user_id: i64,
+    ) -> Result<Option<Destinatario>> {
+        Ok(sqlx::query_as!(
+            Destinatario,
+            "SELECT email, full_name FROM users WHERE id = $1 AND is_active",
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?)
+    }
+
+    pub async fn create(&self, conn: &mut PgConnection, input: &NovaNotificacao) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO notificacao (user_id, evento, titulo, mensagem, url)
+            VALUES ($1, $2, $3, $4, $5)"#,
+            input.user_id,
+            input.evento.codigo(),
+            input.titulo,
+            input.mensagem,
+            input.url
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_by_user(
+        &self,
+        conn: &mut PgConnection,
+        user_id: i64,
+        page: i32,
+        page_size: i32,
+    ) -> Result<(Vec<Notificacao>, i64)> {
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "total!" FROM notificacao WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let linhas = sqlx::query_as!(
+            Notificacao,
+            r#"
+            SELECT id, user_id, evento, titulo, mensagem, url, lida_em, created_at
+            FROM notificacao
+            WHERE user_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2 OFFSET $3"#,
+            user_id,
+            page_size as i64,
+            ((page - 1) * page_size) as i64
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok((linhas, total))
+    }
+
+    pub async fn nao_lidas(&self, conn: &mut PgConnection, user_id: i64) -> Result<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "total!" FROM notificacao WHERE user_id = $1 AND lida_em IS NULL"#,
+            user_id
+        )
+        .fetch_one(conn)
+        .await?)
+    }
+
+    /// Marca como lida; só a notificação do próprio usuário
+    pub async fn marcar_lida(
+        &self,
+        conn: &mut PgConnection,
+        user_id: i64,
+        id: i64,
+    ) -> Result<Option<Notificacao>> {
+        Ok(sqlx::query_as!(
+            Notificacao,
+            r#"
+            UPDATE notificacao SET lida_em = COALESCE(lida_em, NOW())
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, evento, titulo, mensagem, url, lida_em, created_at"#,
+            id,
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?)
+    }
+
+    pub async fn marcar_todas(&self, conn: &mut PgConnection, user_id: i64) -> Result<()> {
+        sqlx::query!(
+            "UPDATE notificacao SET lida_em = NOW() WHERE user_id = $1 AND lida_em IS NULL",
+            user_id
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn preferencias(
+        &self,
+        conn: &mut PgConnection,
+        user_id: i64,
+    ) -> Result<Vec<Preferencia>> {
+        Ok(sqlx::query_as!(
+            Preferencia,
+            "SELECT evento, interna, email FROM notificacao_preferencia WHERE user_id = $1",
+            user_id
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+
+    pub async fn salvar_preferencia(
+        &self,
+        conn: &mut PgConnection,
+        user_id: i64,
+        preferencia: &Preferencia,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO notificacao_preferencia (user_id, evento, interna, email)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, evento) DO UPDATE SET
+                interna = EXCLUDED.interna,
+                email = EXCLUDED.email"#,
+            user_id,
+            preferencia.evento,
+            preferencia.interna,
+            preferencia.email
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Caixa de saída de e-mails; entregue por notificacao::email::entregador
+pub struct EmailRepository;
+
+impl EmailRepository {
+    pub async fn create(
+        &self,
+        conn: &mut PgConnection,
+        user_id: i64,
+        destinatario: &str,
+        evento: &str,
+        contexto: &Value,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO notificacao_email (user_id, destinatario, evento, contexto)
+            VALUES ($1, $2, $3, $4)"#,
+            user_id,
+            destinatario,
+            evento,
+            contexto
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Próximos a entregar, travados para que outra instância não envie o mesmo e-mail
+    pub async fn pendentes_for_update(
+        &self,
+        conn: &mut PgConnection,
+        limite: i64,
+    ) -> Result<Vec<EmailPendente>> {
+        Ok(sqlx::query_as!(
+            EmailPendente,
+            r#"
+            SELECT id, destinatario, evento, contexto, tentativas
+            FROM notificacao_email
+            WHERE enviado_em IS NULL AND tentativas < $1 AND enviar_apos <= NOW()
+            ORDER BY enviar_apos, id
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED"#,
+            MAX_TENTATIVAS,
+            limite
+        )
+        .fetch_all(conn)
+        .await?)
+    }
+
+    pub async fn marcar_enviado(&self, conn: &mut PgConnection, id: i64) -> Result<()> {
+        sqlx::query!(
+            "UPDATE notificacao_email SET enviado_em = NOW(), ultimo_erro = NULL WHERE id = $1",
+            id
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Registra a falha e adia a próxima tentativa em `espera_min` minutos
+    pub async fn marcar_falha(
+        &self,
+        conn: &mut PgConnection,
+        id: i64,
+        erro: &str,
+        espera_min: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE notificacao_email
+            SET tentativas = tentativas + 1,
+                ultimo_erro = $2,
+                enviar_apos = NOW() + make_interval(mins => $3)
+            WHERE id = $1"#,
+            id,
+            erro,
+            espera_min
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+}
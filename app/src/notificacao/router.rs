@@ -0,0 +1,26 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use shared::SharedState;
+use utoipa::OpenApi;
+
+use crate::notificacao::view;
+
+/// Rotas JSON do módulo, relativas a `/notificacao` (ver `crate::openapi`)
+#[derive(OpenApi)]
+#[openapi(paths(view::resumo_notificacao))]
+pub struct ApiDoc;
+
+/// Notificações e preferências do próprio usuário; qualquer usuário logado
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(view::list_notificacao))
+        .route("/resumo", get(view::resumo_notificacao))
+        .route("/lidas", post(view::marcar_todas_lidas))
+        .route("/{id}", get(view::abrir_notificacao))
+        .route(
+            "/preferencias",
+            get(view::show_preferencias).post(view::save_preferencias),
+        )
+}
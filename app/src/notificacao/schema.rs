@@ -0,0 +1,33 @@
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::notificacao::{Evento, model::Notificacao};
+
+/// Notificação gerada por um evento; os canais saem das preferências do destinatário
+#[derive(Debug)]
+pub struct NovaNotificacao {
+    pub user_id: i64,
+    pub evento: Evento,
+    pub titulo: String,
+    pub mensagem: Option<String>,
+    pub url: Option<String>,
+    /// variáveis extras do template de e-mail (titulo, mensagem e url já vão)
+    pub contexto: Value,
+}
+
+/// Contador e últimas notificações do sino
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResumoNotificacoes {
+    pub nao_lidas: i64,
+    pub recentes: Vec<Notificacao>,
+}
+
+/// Linha da tela de preferências: um evento e os dois canais
+#[derive(Debug, Serialize)]
+pub struct PreferenciaEvento {
+    pub evento: &'static str,
+    pub descricao: &'static str,
+    pub interna: bool,
+    pub email: bool,
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use serde_json::{Value, json};
+use shared::{PaginatedResponse, UnitOfWork, limites_pagina};
+use sqlx::{PgConnection, PgPool};
+
+use crate::notificacao::Evento;
+use crate::notificacao::model::{Notificacao, Preferencia};
+use crate::notificacao::repository::{EmailRepository, NotificacaoRepository};
+use crate::notificacao::schema::{NovaNotificacao, PreferenciaEvento, ResumoNotificacoes};
+
+/// Quantas notificações o sino mostra
+const RECENTES: i32 = 8;
+
+pub struct NotificacaoService {
+    repo: NotificacaoRepository,
+}
+
+impl NotificacaoService {
+    pub fn new() -> Self {
+        Self {
+            repo: NotificacaoRepository,
+        }
+    }
+
+    /*
+       grava a notificação interna e/ou enfileira o e-mail, conforme as preferências
+       roda na transação do evento: se a mudança for desfeita, a notificação também é
+       usuário inativo não recebe nada
+    */
+    pub async fn notificar(conn: &mut PgConnection, input: NovaNotificacao) -> Result<()> {
+        let repo = NotificacaoRepository;
+        let Some(destinatario) = repo.destinatario(conn, input.user_id).await? else {
+            return Ok(());
+        };
+        let (interna, email) = repo
+            .canais(conn, input.user_id, input.evento.codigo())
+            .await?;
+
+        if interna {
+            repo.create(conn, &input).await?;
+        }
+        if email && !destinatario.email.is_empty() {
+            let mut contexto = json!({
+                "nome": destinatario.full_name,
+                "titulo": input.titulo,
+                "mensagem": input.mensagem,
+                "url": input.url,
+            });
+            if let (Value::Object(base), Value::Object(extra)) = (&mut contexto, input.contexto) {
+                base.extend(extra);
+            }
+            EmailRepository
+                .create(
+                    conn,
+                    input.user_id,
+                    &destinatario.email,
+                    input.evento.codigo(),
+                    &contexto,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn list(
+        &self,
+        pool: &PgPool,
+        user_id: i64,
+        page: i32,
+        page_size: i32,
+    ) -> Result<PaginatedResponse<Notificacao>> {
+        let (page, page_size) = limites_pagina(page, page_size);
+        let mut conn = pool.acquire().await?;
+        let (data, total_records) = self
+            .repo
+            .list_by_user(&mut conn, user_id, page, page_size)
+            .await?;
+
+        Ok(PaginatedResponse::offset(
+            data,
+            total_records,
+            page,
+            page_size,
+        ))
+    }
+
+    pub async fn resumo(&self, pool: &PgPool, user_id: i64) -> Result<ResumoNotificacoes> {
+        let mut conn = pool.acquire().await?;
+        let nao_lidas = self.repo.nao_lidas(&mut conn, user_id).await?;
+        let (recentes, _) = self
+            .repo
+            .list_by_user(&mut conn, user_id, 1, RECENTES)
+            .await?;
+
+        Ok(ResumoNotificacoes {
+            nao_lidas,
+            recentes,
+        })
+    }
+
+    /// Marca como lida e devolve a notificação (para seguir o link)
+    pub async fn marcar_lida(
+        &self,
+        pool: &PgPool,
+        user_id: i64,
+        id: i64,
+    ) -> Result<Option<Notificacao>> {
+        let mut conn = pool.acquire().await?;
+        self.repo.marcar_lida(&mut conn, user_id, id).await
+    }
+
+    pub async fn marcar_todas(&self, pool: &PgPool, user_id: i64) -> Result<()> {
+        let mut conn = pool.acquire().await?;
+        self.repo.marcar_todas(&mut conn, user_id).await
+    }
+
+    /// Todos os eventos, com os canais gravados ou o padrão (os dois)
+    pub async fn preferencias(
+        &self,
+        pool: &PgPool,
+        user_id: i64,
+    ) -> Result<Vec<PreferenciaEvento>> {
+        let mut conn = pool.acquire().await?;
+        let gravadas = self.repo.preferencias(&mut conn, user_id).await?;
+
+        Ok(Evento::TODOS
+            .into_iter()
+            .map(|evento| {
+                let gravada = gravadas.iter().find(|p| p.evento == evento.codigo());
+                PreferenciaEvento {
+                    evento: evento.codigo(),
+                    descricao: evento.descricao(),
+                    interna: gravada.is_none_or(|p| p.interna),
+                    email: gravada.is_none_or(|p| p.email),
+                }
+            })
+            .collect())
+    }
+
+    pub async fn salvar_preferencias(
+        &self,
+        pool: &PgPool,
+        user_id: i64,
+        preferencias: Vec<Preferencia>,
+    ) -> Result<()> {
+        let mut uow = UnitOfWork::begin(pool).await?;
+        for preferencia in &preferencias {
+            self.repo
+                .salvar_preferencia(uow.conn(), user_id, preferencia)
+                .await?;
+        }
+        uow.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Form, Json,
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+use shared::{
+    AppError, ErrorResponse, FlashStatus, ListParams, SharedState, flash_redirect, helpers,
+};
+
+use crate::{
+    middlewares::CurrentUser,
+    notificacao::{
+        Evento, model::Preferencia, schema::ResumoNotificacoes, service::NotificacaoService,
+    },
+};
+
+/// Notificações do usuário logado, da mais recente para a mais antiga
+pub async fn list_notificacao(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(params): Query<ListParams>,
+) -> Result<Html<String>, AppError> {
+    let list_query = params.list_query();
+    let paginated_response = NotificacaoService::new()
+        .list(
+            &state.db,
+            current_user.current_user.id,
+            list_query.page,
+            list_query.page_size,
+        )
+        .await?;
+
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+    };
+
+    helpers::render(&state.templates, "notificacao/lista.html", context)
+}
+
+#[utoipa::path(
+    get,
+    path = "/resumo",
+    tag = "notificacao",
+    responses(
+        (status = 200, description = "Não lidas e últimas notificações do usuário", body = ResumoNotificacoes),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
+pub async fn resumo_notificacao(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ResumoNotificacoes>, AppError> {
+    let resumo = NotificacaoService::new()
+        .resumo(&state.db, current_user.current_user.id)
+        .await?;
+    Ok(Json(resumo))
+}
+
+/// Marca como lida e segue para a página da notificação
+pub async fn abrir_notificacao(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Response, AppError> {
+    let notificacao = NotificacaoService::new()
+        .marcar_lida(&state.db, current_user.current_user.id, id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let destino = notificacao
+        .url
+        .unwrap_or_else(|| "/notificacao".to_string());
+    Ok(Redirect::to(&destino).into_response())
+}
+
+pub async fn marcar_todas_lidas(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Response, AppError> {
+    NotificacaoService::new()
+        .marcar_todas(&state.db, current_user.current_user.id)
+        .await?;
+
+    Ok(flash_redirect(
+        "/notificacao",
+        "Notificações marcadas como lidas",
+        FlashStatus::Success,
+    ))
+}
+
+pub async fn show_preferencias(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Html<String>, AppError> {
+    let preferencias = NotificacaoService::new()
+        .preferencias(&state.db, current_user.current_user.id)
+        .await?;
+
+    helpers::render(
+        &state.templates,
+        "notificacao/preferencias.html",
+        minijinja::context! { preferencias => preferencias },
+    )
+}
+
+/*
+ grava os canais de todos os eventos
+ campos: interna_<evento> e email_<evento>; checkbox desmarcado não é enviado
+*/
+pub async fn save_preferencias(
+    State(state): State<SharedState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Result<Response, AppError> {
+    let preferencias = Evento::TODOS
+        .into_iter()
+        .map(|evento| Preferencia {
+            evento: evento.codigo().to_string(),
+            interna: form.contains_key(&format!("interna_{}", evento.codigo())),
+            email: form.contains_key(&format!("email_{}", evento.codigo())),
+        })
+        .collect();
+
+    Ok(
+        match NotificacaoService::new()
+            .salvar_preferencias(&state.db, current_user.current_user.id, preferencias)
+            .await
+        {
+            Ok(()) => flash_redirect(
+                "/notificacao/preferencias",
+                "Preferências salvas",
+                FlashStatus::Success,
+            ),
+            Err(err) => flash_redirect(
+                "/notificacao/preferencias",
+                &format!(
+                    "Erro ao salvar preferências: {}",
+                    shared::mensagem_erro(err)
+                ),
+                FlashStatus::Error,
+            ),
+        },
+    )
+}
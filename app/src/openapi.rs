@@ -0,0 +1,96 @@
+use axum::{Json, Router, routing::get};
+use shared::SharedState;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+use utoipa_scalar::{Scalar, Servable};
+
+use crate::{api, chamado, core, externo, notificacao, permissao};
+
+/*
+contrato das rotas JSON, gerado a partir dos schemas (ToSchema/IntoParams)
+e das anotações #[utoipa::path] nas views; cada módulo expõe o seu ApiDoc
+com caminhos relativos, aninhado aqui com o mesmo prefixo do Router
+o arquivo app/openapi.json é a cópia versionada; o teste abaixo falha quando
+o código muda sem atualizar o arquivo (UPDATE_OPENAPI=1 cargo test regrava)
+*/
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "API", description = "Rotas JSON do sistema"),
+    nest(
+        (path = "/chamado", api = chamado::ApiDoc),
+        (path = "/core", api = core::ApiDoc),
+        (path = "/externo", api = externo::ApiDoc),
+        (path = "/notificacao", api = notificacao::ApiDoc),
+        (path = "/permissao", api = permissao::ApiDoc),
+        (path = "/api/v1", api = api::ApiDoc),
+        (path = "/api/v1", api = chamado::ApiV1Doc),
+        (path = "/api/v1", api = externo::ApiV1Doc),
+        (path = "/api/v1", api = permissao::ApiV1Doc),
+    ),
+    modifiers(&Seguranca),
+    tags(
+        (name = "chamado", description = "Tipos, categorias e serviços de chamado"),
+        (name = "core", description = "Municípios e CEP (rotas públicas)"),
+        (name = "externo", description = "Linhas de crédito, regiões e contatos"),
+        (name = "notificacao", description = "Notificações do usuário logado"),
+        (name = "permissao", description = "Módulos, permissões, perfis e usuários"),
+        (name = "v1", description = "API REST versionada; token em /api/v1/auth/token"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Token JWT do login: cabeçalho `Authorization: Bearer` ou cookie `access_token`
+struct Seguranca;
+
+impl Modify for Seguranca {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("access_token"))),
+        );
+    }
+}
+
+/// Rotas públicas: especificação em `/api/openapi.json` e visualizador em `/api/docs`
+pub fn router() -> Router<SharedState> {
+    Router::new()
+        .route(
+            "/api/openapi.json",
+            get(|| async { Json(ApiDoc::openapi()) }),
+        )
+        .merge(Scalar::with_url("/api/docs", ApiDoc::openapi()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiDoc;
+    use utoipa::OpenApi;
+
+    #[test]
+    fn test_openapi_sincronizado() {
+        let caminho = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+        let gerado = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(caminho, &gerado).unwrap();
+            return;
+        }
+        let versionado = std::fs::read_to_string(caminho).unwrap_or_default();
+        assert!(
+            versionado == gerado,
+            "app/openapi.json desatualizado; rode `UPDATE_OPENAPI=1 cargo test -p app openapi`"
+        );
+    }
+}
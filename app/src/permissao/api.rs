@@ -0,0 +1,221 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use shared::{
+    AppError, ErrorResponse, JsonValidado, PaginatedResponse, PaginationQuery, SharedState,
+};
+
+use crate::permissao::{
+    model::module::{Perfil, User},
+    schema::{PerfilCreateSchema, PerfilUpdateSchema, UserCreateSchema, UserUpdateSchema},
+    service::{PerfilService, UserService},
+};
+
+/*
+==========================================
+
+------------- API v1 PERFIL ------------
+==========================================
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/perfis",
+    tag = "v1",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de perfis", body = PaginatedResponse<Perfil>),
+        (status = 403, description = "Sem a permissão permissao_admin", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn listar_perfis(
+    State(state): State<SharedState>,
+    Query(q): Query<PaginationQuery>,
+) -> Result<Json<PaginatedResponse<Perfil>>, AppError> {
+    let res = PerfilService::new()
+        .get_paginated(
+            &state.db,
+            q.find.as_deref(),
+            q.page.unwrap_or(1) as i32,
+            q.page_size.unwrap_or(10) as i32,
+        )
+        .await?;
+    Ok(Json(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/perfis/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID do perfil")),
+    responses(
+        (status = 200, description = "Perfil", body = Perfil),
+        (status = 404, description = "Perfil não encontrado", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn obter_perfil(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Perfil>, AppError> {
+    let perfil = PerfilService::new().get_by_id(&state.db, id).await?;
+    Ok(Json(perfil))
+}
+
+#[utoipa::path(
+    post,
+    path = "/perfis",
+    tag = "v1",
+    request_body = PerfilCreateSchema,
+    responses(
+        (status = 201, description = "Perfil criado", body = Perfil),
+        (status = 409, description = "Perfil já existe", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn criar_perfil(
+    State(state): State<SharedState>,
+    JsonValidado(input): JsonValidado<PerfilCreateSchema>,
+) -> Result<(StatusCode, Json<Perfil>), AppError> {
+    let perfil = PerfilService::new().create(&state.db, input).await?;
+    Ok((StatusCode::CREATED, Json(perfil)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/perfis/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID do perfil")),
+    request_body = PerfilUpdateSchema,
+    responses(
+        (status = 200, description = "Perfil atualizado", body = Perfil),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn atualizar_perfil(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+    JsonValidado(input): JsonValidado<PerfilUpdateSchema>,
+) -> Result<Json<Perfil>, AppError> {
+    let perfil = PerfilService::new().update(&state.db, id, input).await?;
+    Ok(Json(perfil))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/perfis/{id}",
+    tag = "v1",
+    params(("id" = i32, Path, description = "ID do perfil")),
+    responses(
+        (status = 204, description = "Perfil excluído"),
+        (status = 422, description = "Perfil em uso por usuários ou permissões", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn excluir_perfil(
+    State(state): State<SharedState>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    PerfilService::new().delete(&state.db, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/*
+==========================================
+
+------------- API v1 USER --------------
+==========================================
+
+sem exclusão, como na tela: o acesso é retirado com is_active = false
+senha e OTP são gerados no cadastro e trocados pelas rotas próprias
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/users",
+    tag = "v1",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de usuários", body = PaginatedResponse<User>),
+        (status = 403, description = "Sem a permissão permissao_admin", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn listar_users(
+    State(state): State<SharedState>,
+    Query(q): Query<PaginationQuery>,
+) -> Result<Json<PaginatedResponse<User>>, AppError> {
+    let res = UserService::new().list(&state.db, &q.list_query()).await?;
+    Ok(Json(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do usuário")),
+    responses(
+        (status = 200, description = "Usuário", body = User),
+        (status = 404, description = "Usuário não encontrado", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn obter_user(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+) -> Result<Json<User>, AppError> {
+    let user = UserService::new().get_by_id(&state.db, id).await?;
+    Ok(Json(user))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users",
+    tag = "v1",
+    request_body = UserCreateSchema,
+    responses(
+        (status = 201, description = "Usuário criado", body = User),
+        (status = 409, description = "Usuário já existe", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn criar_user(
+    State(state): State<SharedState>,
+    JsonValidado(input): JsonValidado<UserCreateSchema>,
+) -> Result<(StatusCode, Json<User>), AppError> {
+    let user = UserService::new().create(&state.db, input).await?;
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/{id}",
+    tag = "v1",
+    params(("id" = i64, Path, description = "ID do usuário")),
+    request_body = UserUpdateSchema,
+    responses(
+        (status = 200, description = "Usuário atualizado; campos de texto ausentes mantêm o valor atual", body = User),
+        (status = 404, description = "Usuário não encontrado", body = ErrorResponse),
+        (status = 422, description = "Dados inválidos", body = ErrorResponse),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn atualizar_user(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    JsonValidado(mut input): JsonValidado<UserUpdateSchema>,
+) -> Result<Json<User>, AppError> {
+    input.otp_base32 = None;
+    input.ip_last_login = None;
+
+    let user = UserService::new().update(&state.db, id, input).await?;
+    Ok(Json(user))
+}
@@ -1,3 +1,4 @@
+mod api;
 mod model;
 mod repository;
 mod router;
@@ -7,5 +8,5 @@ mod view;
 
 pub use model::module::{Module, User};
 pub use repository::ModuleRepository;
-pub use router::router;
+pub use router::{ApiDoc, ApiV1Doc, router, router_v1};
 pub use service::{UserRolesService, UserService};
@@ -4,12 +4,13 @@ use sqlx::FromRow;
 //use uuid::Uuid;
 use regex::Regex;
 use std::sync::LazyLock;
+use utoipa::ToSchema;
 //use validator::Validate;
 
 static EMAIL_RX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap());
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Module {
     pub id: i32,
     pub title: String,
@@ -19,7 +20,7 @@ pub struct Module {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Permission {
     pub id: i32,
     pub name: String,
@@ -43,21 +44,23 @@ pub struct PermissionWithModule {
 }
 
 //role
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Perfil {
     pub id: i32,
     pub name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct User {
     pub id: i64,
     pub username: String,
     #[serde(skip_serializing)] // nunca aparece no JSON utilizado na api
+    #[schema(ignore)]
     pub password: String,
     pub email: String,
     pub full_name: String,
     #[serde(skip_serializing)] // nunca aparece no JSON utilizado na api
+    #[schema(ignore)]
     pub otp_base32: Option<String>,
     pub is_active: bool,
     pub is_staff: bool,
@@ -1,8 +1,7 @@
 use anyhow::Ok;
 use anyhow::Result;
 use async_trait::async_trait;
-use shared::Repository;
-use sqlx::PgPool;
+use shared::{Db, Repository};
 
 use crate::permissao::model::module::RolePermission;
 use crate::permissao::model::module::UserRoles;
@@ -41,30 +40,38 @@ impl Repository<Module, i32> for ModuleRepository {
         "module m"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<Module> {
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<Module> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Module,
             "INSERT INTO module (title) VALUES ($1) RETURNING id, title, created_at, updated_at",
             input.title.to_string()
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i32, input: Self::UpdateInput) -> Result<Module> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i32,
+        input: Self::UpdateInput,
+    ) -> Result<Module> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Module,
             r#"UPDATE module SET title = $1 WHERE id = $2 RETURNING id, title, created_at, updated_at"#,
             input.title,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM module WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -93,7 +100,8 @@ impl Repository<Permission, i32> for PermissionRepository {
         "permission p"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<Permission> {
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<Permission> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Permission,
             r#"INSERT INTO permission (name, description, module_id) 
@@ -103,11 +111,17 @@ impl Repository<Permission, i32> for PermissionRepository {
             input.description,
             input.module_id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i32, input: Self::UpdateInput) -> Result<Permission> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i32,
+        input: Self::UpdateInput,
+    ) -> Result<Permission> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Permission,
             r#"UPDATE permission 
@@ -119,13 +133,14 @@ impl Repository<Permission, i32> for PermissionRepository {
             input.module_id,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM permission WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -154,7 +169,8 @@ impl Repository<Perfil, i32> for PerfilRepository {
         "roles p"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<Perfil> {
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<Perfil> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Perfil,
             r#"INSERT INTO roles (name) 
@@ -162,11 +178,17 @@ impl Repository<Perfil, i32> for PerfilRepository {
                RETURNING id, name"#,
             input.name,
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i32, input: Self::UpdateInput) -> Result<Perfil> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i32,
+        input: Self::UpdateInput,
+    ) -> Result<Perfil> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             Perfil,
             r#"UPDATE roles 
@@ -176,13 +198,14 @@ impl Repository<Perfil, i32> for PerfilRepository {
             input.name,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM roles WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -203,6 +226,14 @@ impl Repository<User, i64> for UserRepository {
         &["u.username", "u.email", "u.full_name"]
     }
 
+    // mesma expressão do índice idx_users_busca
+    fn search_vector(&self) -> Option<&str> {
+        Some(
+            "setweight(to_tsvector('portuguese_unaccent', u.full_name), 'A') \
+            || setweight(to_tsvector('portuguese_unaccent', regexp_replace(u.username || ' ' || u.email, '[^[:alnum:]]+', ' ', 'g')), 'B')",
+        )
+    }
+
     fn select_clause(&self) -> &str {
         "u.id, u.username, u.password, u.email, u.full_name, u.otp_base32, \
         u.is_active, u.is_staff, u.is_superuser, u.ip_last_login, \
@@ -213,7 +244,8 @@ impl Repository<User, i64> for UserRepository {
         "users u"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> anyhow::Result<User> {
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> anyhow::Result<User> {
+        let mut conn = db.acquire().await?;
         // 1. Checar se já existe usuário com email ou username
         if let Some(db_user) = sqlx::query_as!(
             User,
@@ -224,7 +256,7 @@ impl Repository<User, i64> for UserRepository {
             input.email,
             input.username
         )
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?
         {
             if db_user.email == input.email {
@@ -249,13 +281,19 @@ impl Repository<User, i64> for UserRepository {
             input.is_staff,
             input.is_superuser
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(new_user)
     }
 
-    async fn update(&self, pool: &PgPool, id: i64, input: Self::UpdateInput) -> Result<User> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i64,
+        input: Self::UpdateInput,
+    ) -> Result<User> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             User,
             r#"
@@ -284,13 +322,14 @@ impl Repository<User, i64> for UserRepository {
             input.is_superuser,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!(r#"DELETE FROM users WHERE id = $1"#, id as i64)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -319,7 +358,8 @@ impl Repository<UserRoles, i32> for UserRolesRepository {
         "user_roles p"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<UserRoles> {
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<UserRoles> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             UserRoles,
             r#"INSERT INTO user_roles (user_id, role_id) 
@@ -328,11 +368,17 @@ impl Repository<UserRoles, i32> for UserRolesRepository {
             input.user_id,
             input.role_id,
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(&self, pool: &PgPool, id: i32, input: Self::UpdateInput) -> Result<UserRoles> {
+    async fn update<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        id: i32,
+        input: Self::UpdateInput,
+    ) -> Result<UserRoles> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             UserRoles,
             r#"UPDATE user_roles 
@@ -342,13 +388,14 @@ impl Repository<UserRoles, i32> for UserRolesRepository {
             input.role_id,
             id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i32) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i32) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM user_roles WHERE id = $1", id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -377,7 +424,16 @@ impl Repository<RolePermission, i64> for RolePermissionRepository {
         "role_permissions p"
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<RolePermission> {
+    fn association_columns(&self) -> Option<(&str, &str)> {
+        Some(("role_id", "permission_id"))
+    }
+
+    async fn create<'c, A: Db<'c>>(
+        &self,
+        db: A,
+        input: Self::CreateInput,
+    ) -> Result<RolePermission> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             RolePermission,
             r#"INSERT INTO role_permissions (role_id, permission_id) 
@@ -386,16 +442,17 @@ impl Repository<RolePermission, i64> for RolePermissionRepository {
             input.role_id,
             input.permission_id,
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn update(
+    async fn update<'c, A: Db<'c>>(
         &self,
-        pool: &PgPool,
+        db: A,
         id: i64,
         input: Self::UpdateInput,
     ) -> Result<RolePermission> {
+        let mut conn = db.acquire().await?;
         Ok(sqlx::query_as!(
             RolePermission,
             r#"UPDATE role_permissions 
@@ -406,13 +463,14 @@ impl Repository<RolePermission, i64> for RolePermissionRepository {
             input.permission_id,
             id as i32
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await?)
     }
 
-    async fn delete(&self, pool: &PgPool, id: i64) -> Result<()> {
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: i64) -> Result<()> {
+        let mut conn = db.acquire().await?;
         sqlx::query!("DELETE FROM role_permissions WHERE id = $1", id as i32)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         Ok(())
     }
@@ -1,10 +1,39 @@
 use axum::{
-    Router,
+    Router, middleware,
     routing::{delete, get, post},
 };
 
-use crate::permissao::view;
+use crate::middlewares;
+use crate::permissao::{ModuleRepository, api, view};
 use shared::SharedState;
+use shared::admin::AdminResource;
+use shared::generic_list::{FormField, ListConfig};
+use utoipa::OpenApi;
+
+/// Rotas JSON do módulo, relativas a `/permissao` (ver `crate::openapi`)
+#[derive(OpenApi)]
+#[openapi(paths(
+    view::modules_list_api,
+    view::permission_list_api,
+    view::perfil_list_api,
+    view::users_list_api,
+))]
+pub struct ApiDoc;
+
+/// Rotas da API v1, relativas a `/api/v1` (ver `crate::api`)
+#[derive(OpenApi)]
+#[openapi(paths(
+    api::listar_perfis,
+    api::obter_perfil,
+    api::criar_perfil,
+    api::atualizar_perfil,
+    api::excluir_perfil,
+    api::listar_users,
+    api::obter_user,
+    api::criar_user,
+    api::atualizar_user,
+))]
+pub struct ApiV1Doc;
 
 pub fn router() -> Router<SharedState> {
     Router::new()
@@ -18,19 +47,21 @@ pub fn router() -> Router<SharedState> {
 }
 
 fn modulo_router() -> Router<SharedState> {
-    Router::new()
-        .route("/modulo", get(view::list_modules))
-        .route("/modulo-list", get(view::list_modulo))
-        .route(
-            "/modulo-form",
-            get(view::show_module_form).post(view::create_model),
-        )
-        .route(
-            "/modulo-form/{id}",
-            get(view::get_modulo).post(view::update_modulo),
-        )
-        .route("/modulo/{id}", delete(view::delete_module))
-        .merge(api_modulo_router())
+    AdminResource::new(
+        ModuleRepository,
+        ListConfig::new("modulo", "Módulo", "Módulos", "/permissao/modulo")
+            .field("id", "ID")
+            .field("title", "Título")
+            .searchable(&["título"]),
+        vec![
+            FormField::text("title", "Título")
+                .required()
+                .placeholder("Digite o título"),
+        ],
+    )
+    .router()
+    .route("/modulo-list", get(view::list_modulo))
+    .merge(api_modulo_router())
 }
 
 fn api_modulo_router() -> Router<SharedState> {
@@ -78,6 +109,12 @@ fn api_perfil_router() -> Router<SharedState> {
 fn user_router() -> Router<SharedState> {
     Router::new()
         .route("/user", get(view::list_user))
+        .route(
+            "/user/exportar",
+            get(view::export_user).layer(middleware::from_fn(middlewares::require_roles(vec![
+                "admin",
+            ]))),
+        )
         .route(
             "/user-form",
             get(view::show_user_form).post(view::create_user),
@@ -115,5 +152,23 @@ fn user_gestao_perfil_router() -> Router<SharedState> {
             "/gestao-perfil",
             get(view::get_gestao_perfil).post(view::create_gestao_perfil),
         )
+        .route("/gestao-perfil/lote", post(view::lote_gestao_perfil))
         .route("/gestao-perfil/{id}", delete(view::delete_gestao_perfil))
 }
+
+/// API v1: perfis e usuários somente com a permissão permissao_admin (ou superusuário)
+pub fn router_v1() -> Router<SharedState> {
+    Router::new()
+        .route("/perfis", get(api::listar_perfis).post(api::criar_perfil))
+        .route(
+            "/perfis/{id}",
+            get(api::obter_perfil)
+                .put(api::atualizar_perfil)
+                .delete(api::excluir_perfil),
+        )
+        .route("/users", get(api::listar_users).post(api::criar_user))
+        .route("/users/{id}", get(api::obter_user).put(api::atualizar_user))
+        .layer(middleware::from_fn(middlewares::require_roles(vec![
+            "permissao_admin",
+        ])))
+}
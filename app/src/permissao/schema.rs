@@ -4,6 +4,7 @@ use chrono::{DateTime, Utc};
 use regex::Regex;
 use sqlx::FromRow;
 use std::sync::LazyLock;
+use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::utils::serde_utils::bool_from_str;
@@ -35,13 +36,15 @@ pub struct ParamOptions {
     pub id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Validate)]
 pub struct CreateModuleSchema {
+    #[validate(length(min = 1, message = "Informe o título"))]
     pub title: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Validate)]
 pub struct UpdateModuleSchema {
+    #[validate(length(min = 1, message = "Informe o título"))]
     pub title: String,
 }
 
@@ -71,24 +74,31 @@ pub struct PermissionUpdateSchema {
     pub module_id: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Validate, ToSchema)]
 pub struct PerfilCreateSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub name: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Validate, ToSchema)]
 pub struct PerfilUpdateSchema {
+    #[validate(length(min = 1, message = "Informe o nome"))]
     pub name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UserCreateSchema {
+    #[validate(length(min = 1, message = "Informe o usuário"))]
     pub username: String,
+    /// gerada pelo service
     #[validate(length(min = 8, message = "Password must have at least 8 characters"))]
+    #[schema(ignore)]
     pub password: Option<String>,
     #[validate(regex(path=*EMAIL_RX, message = "Invalid email"))]
     pub email: String,
     pub full_name: String,
+    /// gerado pelo service
+    #[schema(ignore)]
     pub otp_base32: Option<String>,
     #[serde(default, deserialize_with = "bool_from_str")]
     pub is_active: bool,
@@ -98,12 +108,14 @@ pub struct UserCreateSchema {
     pub is_superuser: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UserUpdateSchema {
     pub username: Option<String>,
     #[validate(regex(path=*EMAIL_RX, message = "Invalid email"))]
     pub email: Option<String>,
     pub full_name: Option<String>,
+    /// regerado pela rota própria de OTP
+    #[schema(ignore)]
     pub otp_base32: Option<String>,
     #[serde(default, deserialize_with = "bool_from_str")]
     pub is_active: bool,
@@ -111,6 +123,7 @@ pub struct UserUpdateSchema {
     pub is_staff: bool,
     #[serde(default, deserialize_with = "bool_from_str")]
     pub is_superuser: bool,
+    #[schema(ignore)]
     pub ip_last_login: Option<String>,
 }
 
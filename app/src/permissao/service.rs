@@ -26,7 +26,7 @@ use sqlx::PgPool;
 //use validator::Validate;
 
 use axum::{extract::State, response::Html};
-use shared::{PaginatedResponse, Repository, SharedState};
+use shared::{ListQuery, OpcaoLote, PaginatedResponse, Repository, SharedState};
 
 use crate::{
     permissao::model::module::Module,
@@ -183,6 +183,7 @@ impl PermissionService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
 }
@@ -347,6 +348,10 @@ impl UserService {
         Ok(self.repo.get_paginated(pool, find, page, page_size).await?)
     }
 
+    pub async fn list(&self, pool: &PgPool, query: &ListQuery) -> Result<PaginatedResponse<User>> {
+        self.repo.list(pool, query).await
+    }
+
     pub fn get_password_hash(password: &str) -> Result<String, password_hash::Error> {
         let salt = SaltString::generate(&mut OsRng);
 
@@ -385,14 +390,18 @@ impl UserService {
         // - SHA1
         // - 6 dígitos
         // - Intervalo de 30 segundos
-        let totp = TOTP::from_base32(secret_base32).unwrap();
+        let Some(totp) = TOTP::from_base32(secret_base32) else {
+            return false;
+        };
 
         // Gerar código atual
         //let code = totp.generate(30, Utc::now().timestamp() as u64);
         //println!("Código gerado: {}", code);
 
         // Converter entrada do cliente
-        let codigo: u32 = otp.parse().unwrap();
+        let Ok(codigo) = otp.trim().parse::<u32>() else {
+            return false;
+        };
 
         //debug!("Código gerado: {}, codigo enviado: {}", code, codigo);
 
@@ -405,9 +414,10 @@ impl UserService {
         totp.generate(30, Utc::now().timestamp() as u64).to_string()
     }
 
-    pub fn get_otp_url(username: &str, otp_base32: &str) -> String {
-        let totp = TOTP::new(otp_base32.to_string());
-        totp.to_uri("YourApp", username)
+    /// URI `otpauth://` do segredo gravado; mesma decodificação base32 de `is_valid_otp`
+    pub fn get_otp_url(username: &str, otp_base32: &str) -> Option<String> {
+        let totp = TOTP::from_base32(otp_base32)?;
+        Some(totp.to_uri("YourApp", username))
     }
 
     pub async fn update_password(
@@ -437,6 +447,19 @@ impl UserService {
         .await?)
     }
 
+    /*
+       checagens do login (tela e token da API): usuário ativo, senha e código OTP
+       não diz qual falhou, a mensagem para o usuário é a mesma
+    */
+    pub fn credenciais_validas(user: &User, password: &str, otp: &str) -> bool {
+        user.is_active
+            && Self::verify_password(password, &user.password).unwrap_or(false)
+            && user
+                .otp_base32
+                .as_deref()
+                .is_some_and(|otp_base32| Self::is_valid_otp(otp, otp_base32))
+    }
+
     pub async fn get_by_username(pool: &PgPool, username: &str) -> Result<User> {
         let query = format!("SELECT * FROM users WHERE username = $1 LIMIT 1");
 
@@ -450,9 +473,8 @@ impl UserService {
        Utilizado somente por admins super user
     */
     pub async fn update_otp(pool: &PgPool, id: i64) -> Result<User> {
-        let base = &Self::random_base32().to_string();
-
-        let hash = Self::gerar_otp(base);
+        // grava o segredo base32, o codigo de 6 digitos é derivado dele no login
+        let base = Self::random_base32();
 
         Ok(sqlx::query_as!(
             User,
@@ -467,7 +489,7 @@ impl UserService {
                 is_active, is_staff, is_superuser, ip_last_login,
                 last_login, created_at, updated_at
             "#,
-            hash,
+            base,
             id
         )
         .fetch_one(pool)
@@ -645,6 +667,7 @@ impl UserRolesService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
 }
@@ -758,8 +781,66 @@ impl RolePermissionService {
             page,
             page_size,
             total_pages,
+            next_cursor: None,
         })
     }
+
+    /// Adiciona várias permissões ao perfil (ignora as que ele já possui)
+    pub async fn create_many(
+        &self,
+        pool: &PgPool,
+        role_id: i32,
+        permission_ids: &[i32],
+    ) -> Result<u64> {
+        self.repo.bulk_create(pool, role_id, permission_ids).await
+    }
+
+    pub async fn delete_many(
+        &self,
+        pool: &PgPool,
+        role_id: i32,
+        permission_ids: &[i32],
+    ) -> Result<u64> {
+        self.repo.bulk_delete(pool, role_id, permission_ids).await
+    }
+
+    /// Adiciona todas as permissões cadastradas ao perfil
+    pub async fn create_all(&self, pool: &PgPool, role_id: i32) -> Result<u64> {
+        let ids: Vec<i32> = self
+            .permissoes_disponiveis(pool, role_id)
+            .await?
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        self.create_many(pool, role_id, &ids).await
+    }
+
+    pub async fn delete_all(&self, pool: &PgPool, role_id: i32) -> Result<u64> {
+        self.repo.bulk_delete_all(pool, role_id).await
+    }
+
+    /// Permissões que o perfil ainda não possui
+    pub async fn permissoes_disponiveis(
+        &self,
+        pool: &PgPool,
+        role_id: i32,
+    ) -> Result<Vec<OpcaoLote>> {
+        Ok(sqlx::query_as!(
+            OpcaoLote,
+            r#"
+            SELECT p.id, p.name AS "nome!"
+            FROM permission p
+            WHERE NOT EXISTS (
+                SELECT 1 FROM role_permissions rp
+                WHERE rp.role_id = $1 AND rp.permission_id = p.id
+            )
+            ORDER BY p.name
+            "#,
+            role_id
+        )
+        .fetch_all(pool)
+        .await?)
+    }
 }
 
 pub async fn home(State(state): State<SharedState>) -> Html<String> {
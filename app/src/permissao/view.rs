@@ -1,17 +1,19 @@
 use axum::{
     Extension, Json,
     extract::{Form, Path, Query, State},
-    http::StatusCode,
-    response::{Html, IntoResponse, Redirect, Response},
+    response::{Html, Response},
 };
 
 use minijinja::Value;
 use minijinja::context;
+use shared::export::{self, ColumnKind, ExportConfig, ExportParams};
 use shared::{
-    FlashStatus, ListParams, PaginatedResponse, PaginationQuery, SharedState,
+    AcaoLote, AppError, ErrorResponse, FlashStatus, ListParams, LoteForm, PaginatedResponse,
+    PaginationQuery, SharedState, flash_redirect,
     helpers::{self, get_qr_code_base64},
+    mensagem_erro,
 };
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use tracing::debug;
 use validator::Validate;
 
@@ -35,155 +37,29 @@ use crate::{
     },
 };
 use crate::{
-    permissao::schema::{CreateModuleSchema, PermissionCreateSchema, PermissionUpdateSchema},
+    permissao::schema::{PermissionCreateSchema, PermissionUpdateSchema},
     permissao::service::ModuleService,
 };
 
-pub async fn home(State(state): State<SharedState>) -> Html<String> {
-    let template = state.templates.get_template("index.html").unwrap();
-    let html = template.render(()).unwrap();
-    Html(html)
-}
-
-pub async fn list_modules(
-    State(state): State<SharedState>,
-    Query(params): Query<ListParams>,
-) -> impl IntoResponse {
-    let page = params.page.unwrap_or(1);
-    let per_page = 10; // Itens por página
-    let search_term = params.find.unwrap_or_default();
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    // Buscar módulos com filtro e paginação
-    let (modules, total_records) = if search_term.is_empty() {
-        // Buscar todos os módulos
-        let count_result = sqlx::query_scalar!("SELECT COUNT(*) FROM module")
-            .fetch_one(&*state.db)
-            .await;
-
-        let modules_result = sqlx::query_as!(
-            Module,
-            "SELECT * FROM module ORDER BY id DESC LIMIT $1 OFFSET $2",
-            per_page as i64,
-            ((page - 1) * per_page) as i64
-        )
-        .fetch_all(&*state.db)
-        .await;
-
-        match (count_result, modules_result) {
-            (Ok(count), Ok(modules)) => (modules, count.unwrap_or(0) as usize),
-            _ => (Vec::new(), 0),
-        }
-    } else {
-        // Buscar com filtro
-        let search_pattern = format!("%{}%", search_term);
-
-        let count_result = sqlx::query_scalar!(
-            "SELECT COUNT(*) FROM module WHERE title ILIKE $1",
-            search_pattern
-        )
-        .fetch_one(&*state.db)
-        .await;
-
-        let modules_result = sqlx::query_as!(
-            Module,
-            "SELECT * FROM module WHERE title ILIKE $1 ORDER BY id DESC LIMIT $2 OFFSET $3",
-            search_pattern,
-            per_page as i64,
-            ((page - 1) * per_page) as i64
-        )
-        .fetch_all(&*state.db)
-        .await;
-
-        match (count_result, modules_result) {
-            (Ok(count), Ok(modules)) => (modules, count.unwrap_or(0) as usize),
-            _ => (Vec::new(), 0),
-        }
-    };
-
-    let total_pages = (total_records + per_page as usize - 1) / per_page as usize;
-
-    let ctx = context! {
-        title => "Lista de Módulos",
-        rows => modules,
-        current_page => page,
-        total_pages => total_pages,
-        total_records => total_records,
-        find => if search_term.is_empty() { None } else { Some(search_term) },
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    match state.templates.get_template("permissao/modulo_list.html") {
-        Ok(template) => match template.render(ctx) {
-            Ok(html) => Html(html).into_response(),
-            Err(err) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao renderizar template: {}", err),
-            )
-                .into_response(),
-        },
-        Err(err) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao carregar template: {}", err),
-        )
-            .into_response(),
-    }
-}
-
-pub async fn create_model(
-    State(state): State<SharedState>,
-    Form(body): Form<CreateModuleSchema>,
-) -> Response {
-    let service = ModuleService::new();
-
-    match service.create(&state.db, body).await {
-        Ok(module) => {
-            // Redirecionar com mensagem de sucesso
-            let redirect_url = helpers::create_flash_url(
-                &format!("/permissao/modulo-form/{}", module.id),
-                "Módulo criado com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-        Err(err) => {
-            let error_message = if err
-                .to_string()
-                .contains("duplicate key value violates unique constraint")
-            {
-                "Este módulo já existe"
-            } else {
-                "Ocorreu um erro ao criar o módulo"
-            };
-
-            // Redirecionar com mensagem de erro
-            let redirect_url = helpers::create_flash_url(
-                "/permissao/modulo-form",
-                error_message,
-                FlashStatus::Error,
-            );
-
-            debug!("Erro ao criar módulo: {}", err);
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
+pub async fn home(State(state): State<SharedState>) -> Result<Html<String>, AppError> {
+    helpers::render(&state.templates, "index.html", ())
 }
 
+#[utoipa::path(
+    get,
+    path = "/modulo-api",
+    tag = "permissao",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<Module>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn modules_list_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<Module>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<Module>>, AppError> {
     let service = ModuleService::new();
     let res = service
         .get_paginated(
@@ -192,63 +68,20 @@ pub async fn modules_list_api(
             q.page.unwrap_or(1) as i32,
             q.page_size.unwrap_or(10) as i32,
         )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     Ok(Json(res))
 }
 
-pub async fn saudacao(State(state): State<SharedState>) -> Html<String> {
+pub async fn saudacao(State(state): State<SharedState>) -> Result<Html<String>, AppError> {
     let context = [("nome", "João")];
-    let template = state.templates.get_template("saudacao.html").unwrap();
-    let html = template.render(context).unwrap();
-    Html(html)
-}
-
-pub async fn show_module_form(
-    State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> impl IntoResponse {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let ctx = context! {
-        title => "Cadastro de Módulo",
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    match state.templates.get_template("permissao/modulo_form.html") {
-        Ok(template) => match template.render(ctx) {
-            Ok(html) => Html(html).into_response(),
-            Err(err) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao renderizar template: {}", err),
-            )
-                .into_response(),
-        },
-        Err(err) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao carregar template: {}", err),
-        )
-            .into_response(),
-    }
+    helpers::render(&state.templates, "saudacao.html", context)
 }
 
 pub async fn list_modulo(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Html<String>, StatusCode> {
+) -> Result<Html<String>, AppError> {
     let service = ModuleService::new();
 
     let res = service
@@ -258,11 +91,7 @@ pub async fn list_modulo(
             q.page.unwrap_or(1) as i32,
             q.page_size.unwrap_or(10) as i32,
         )
-        .await
-        .map_err(|err| {
-            debug!("Erro ao buscar módulos: {}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     let _dados_formatados: Vec<Value> = res
         .data
@@ -301,176 +130,15 @@ pub async fn list_modulo(
         total_paginas => res.total_pages,
     };
 
-    let template = state
-        .templates
-        .get_template("permissao/modulo.html")
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    let html = template.render(&context).map_err(|err| {
-        debug!("Erro ao renderizar template: {}", err);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
-
-    Ok(Html(html))
-}
-
-pub async fn get_modulo(
-    State(state): State<SharedState>,
-    Path(id): Path<i32>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, Response> {
-    // Buscar o módulo no banco de dados
-    let module_result = sqlx::query_as::<_, Module>("SELECT * FROM module WHERE id = $1")
-        .bind(id)
-        .fetch_one(&*state.db)
-        .await;
-
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    // Carregar o template
-    let template = match state.templates.get_template("permissao/modulo_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
-
-    match module_result {
-        Ok(module) => {
-            let ctx = context! {
-                row => module,
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-            match template.render(ctx) {
-                Ok(html) => Ok(Html(html)),
-                Err(err) => Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Falha ao renderizar template: {}", err),
-                )
-                    .into_response()),
-            }
-        }
-        Err(e) => {
-            debug!("Erro ao buscar módulo: {}", e);
-            Err(Redirect::to("/permissao/modulo").into_response())
-        }
-    }
-}
-
-pub async fn update_modulo(
-    State(state): State<SharedState>,
-    Path(id): Path<i32>,
-    Form(body): Form<CreateModuleSchema>,
-) -> Response {
-    let query_result = sqlx::query_as!(
-        Module,
-        "UPDATE module SET title = $1 WHERE id = $2 RETURNING *",
-        body.title.to_string(),
-        id
-    )
-    .fetch_one(&*state.db)
-    .await;
-
-    match query_result {
-        Ok(_) => {
-            // Redirecionar com mensagem de sucesso
-            let redirect_url = helpers::create_flash_url(
-                &format!("/permissao/modulo-form/{}", id),
-                "Módulo atualizado com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-        Err(e) => {
-            let error_message = if e
-                .to_string()
-                .contains("duplicate key value violates unique constraint")
-            {
-                "Este título já existe para outro módulo"
-            } else {
-                "Ocorreu um erro ao atualizar o módulo"
-            };
-
-            // Redirecionar com mensagem de erro
-            let redirect_url = helpers::create_flash_url(
-                &format!("/permissao/modulo-form/{}", id),
-                error_message,
-                FlashStatus::Error,
-            );
-
-            debug!("Erro ao atualizar módulo: {}", e);
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
-}
-
-pub async fn delete_module(State(state): State<SharedState>, Path(id): Path<i32>) -> Response {
-    let query_result = sqlx::query!("DELETE FROM module WHERE id = $1", id)
-        .execute(&*state.db)
-        .await;
-
-    match query_result {
-        Ok(result) => {
-            if result.rows_affected() > 0 {
-                // Redirecionar com mensagem de sucesso
-                let redirect_url = helpers::create_flash_url(
-                    "/permissao/modulo",
-                    "Módulo excluído com sucesso!",
-                    FlashStatus::Success,
-                );
-                Redirect::to(&redirect_url).into_response()
-            } else {
-                // Módulo não encontrado
-                let redirect_url = helpers::create_flash_url(
-                    "/permissao/modulo",
-                    "Módulo não encontrado",
-                    FlashStatus::Error,
-                );
-                Redirect::to(&redirect_url).into_response()
-            }
-        }
-        Err(e) => {
-            debug!("Erro ao excluir módulo: {}", e);
-            let redirect_url = helpers::create_flash_url(
-                "/permissao/modulo",
-                "Erro ao excluir módulo",
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
+    helpers::render(&state.templates, "permissao/modulo.html", &context)
 }
 
 pub async fn list_permissions(
     Query(params): Query<ListParams>,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service = PermissionService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Usar o PermissionService para buscar dados paginados
     let permissions_result = service
         .get_paginated_with_module(
@@ -481,52 +149,22 @@ pub async fn list_permissions(
         )
         .await;
 
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-
-            match state
-                .templates
-                .get_template("permissao/permission_list.html")
-            {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar permissions: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/permission",
-                &format!("Erro ao carregar permissions: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
+    let paginated_response = permissions_result?;
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.unwrap_or_default(),
+    };
+
+    helpers::render(&state.templates, "permissao/permission_list.html", context)
 }
 
 pub async fn show_permission_form(
     State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, Response> {
+) -> Result<Html<String>, AppError> {
     // Buscar todos os módulos para o dropdown
     let modules = match sqlx::query_as::<_, Module>("SELECT * FROM module ORDER BY title")
         .fetch_all(&*state.db)
@@ -538,40 +176,11 @@ pub async fn show_permission_form(
         }
     };
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     let context = minijinja::context! {
         modules => modules,
-        flash_message => flash_message,
-        flash_status => flash_status,
     };
 
-    match state
-        .templates
-        .get_template("permissao/permission_form.html")
-    {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "permissao/permission_form.html", context)
 }
 
 pub async fn create_permission(
@@ -587,72 +196,33 @@ pub async fn create_permission(
     .fetch_one(&*state.db)
     .await
     {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/permission",
-                "Permissão criada com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/permission-form",
-                &format!("Erro ao criar permissão: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/permissao/permission",
+            "Permissão criada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/permissao/permission-form",
+            &format!("Erro ao criar permissão: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
 pub async fn get_permission(
     State(state): State<SharedState>,
     Path(id): Path<i32>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, Response> {
+) -> Result<Html<String>, AppError> {
     let service = PermissionService::new();
     let serv_module = ModuleService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Carregar o template
-    let template = match state
+    let template = state
         .templates
-        .get_template("permissao/permission_form.html")
-    {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
+        .get_template("permissao/permission_form.html")?;
 
     // Buscar a permissão
-    let permission = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar permissão: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/permissao/permission",
-                &format!("Permissão não encontrada: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
+    let permission = service.get_by_id(&state.db, id).await?;
 
     // Buscar o módulo associado
     let module = match serv_module.get_by_id(&state.db, permission.module_id).await {
@@ -667,19 +237,10 @@ pub async fn get_permission(
     let ctx = context! {
         row => permission,
         modulo => module,
-        flash_message => flash_message,
-        flash_status => flash_status,
     };
 
     // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(Html(template.render(&ctx)?))
 }
 
 pub async fn update_permission(
@@ -697,22 +258,16 @@ pub async fn update_permission(
     .fetch_one(&*state.db)
     .await;
     match query_result {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/permission"),
-                &format!("Permissão atualizada com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/permission-form/{}", id),
-                &format!("Erro ao atualizar permissão: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/permissao/permission",
+            "Permissão atualizada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            &format!("/permissao/permission-form/{}", id),
+            &format!("Erro ao atualizar permissão: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -723,36 +278,42 @@ pub async fn delete_permission(State(state): State<SharedState>, Path(id): Path<
     {
         Ok(result) => {
             if result.rows_affected() > 0 {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     "/permissao/permission",
                     "Permissão excluída com sucesso!",
                     FlashStatus::Success,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             } else {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     "/permissao/permission",
                     "Permissão não encontrada",
                     FlashStatus::Error,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             }
         }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/permission",
-                &format!("Erro ao excluir permissão: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Err(err) => flash_redirect(
+            "/permissao/permission",
+            &format!("Erro ao excluir permissão: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/permission-api",
+    tag = "permissao",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<Permission>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn permission_list_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<Permission>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<Permission>>, AppError> {
     let service = PermissionService::new();
     let res = service
         .get_paginated(
@@ -761,11 +322,7 @@ pub async fn permission_list_api(
             q.page.unwrap_or(1) as i32,
             q.page_size.unwrap_or(10) as i32,
         )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     Ok(Json(res))
 }
@@ -777,20 +334,9 @@ pub async fn permission_list_api(
 pub async fn list_perfil(
     Query(params): Query<ListParams>,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service = PerfilService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Usar o PermissionService para buscar dados paginados
     let permissions_result = service
         .get_paginated(
@@ -801,79 +347,25 @@ pub async fn list_perfil(
         )
         .await;
 
-    match permissions_result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-
-            match state.templates.get_template("permissao/perfil_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar permissions: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/perfil",
-                &format!("Erro ao carregar permissions: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
+    let paginated_response = permissions_result?;
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.unwrap_or_default(),
+    };
+
+    helpers::render(&state.templates, "permissao/perfil_list.html", context)
 }
 
 pub async fn show_perfil_form(
     State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, Response> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
+) -> Result<Html<String>, AppError> {
+    let context = minijinja::context! {};
 
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
-
-    match state.templates.get_template("permissao/perfil_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "permissao/perfil_form.html", context)
 }
 
 pub async fn create_perfil(
@@ -887,85 +379,38 @@ pub async fn create_perfil(
     .fetch_one(&*state.db)
     .await
     {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/perfil",
-                "Perfil criado com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/perfil-form",
-                &format!("Erro ao criar perfil: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/permissao/perfil",
+            "Perfil criado com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/permissao/perfil-form",
+            &format!("Erro ao criar perfil: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
 pub async fn get_perfil(
     State(state): State<SharedState>,
     Path(id): Path<i32>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, Response> {
+) -> Result<Html<String>, AppError> {
     let service = PerfilService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Carregar o template
-    let template = match state.templates.get_template("permissao/perfil_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
+    let template = state.templates.get_template("permissao/perfil_form.html")?;
 
     // Buscar o perfil
-    let perfil = match service.get_by_id(&state.db, id).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar perfil: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/permissao/perfil",
-                &format!("Perfil não encontrado: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
+    let perfil = service.get_by_id(&state.db, id).await?;
 
     // Preparar o contexto
     let ctx = context! {
         row => perfil,
-        flash_message => flash_message,
-        flash_status => flash_status,
     };
 
     // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(Html(template.render(&ctx)?))
 }
 
 pub async fn update_perfil(
@@ -981,22 +426,16 @@ pub async fn update_perfil(
     .fetch_one(&*state.db)
     .await;
     match query_result {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/perfil"),
-                &format!("Perfil atualizado com sucesso!"),
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/perfil-form/{}", id),
-                &format!("Erro ao atualizar perfil: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/permissao/perfil",
+            "Perfil atualizado com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            &format!("/permissao/perfil-form/{}", id),
+            &format!("Erro ao atualizar perfil: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1007,36 +446,42 @@ pub async fn delete_perfil(State(state): State<SharedState>, Path(id): Path<i32>
     {
         Ok(result) => {
             if result.rows_affected() > 0 {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     "/permissao/perfil",
                     "Perfil excluído com sucesso!",
                     FlashStatus::Success,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             } else {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     "/permissao/perfil",
                     "Perfil não encontrado",
                     FlashStatus::Error,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             }
         }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/perfil",
-                &format!("Erro ao excluir perfil: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Err(err) => flash_redirect(
+            "/permissao/perfil",
+            &format!("Erro ao excluir perfil: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/perfil-api",
+    tag = "permissao",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<Perfil>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn perfil_list_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<Perfil>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<Perfil>>, AppError> {
     let service = PerfilService::new();
     let res = service
         .get_paginated(
@@ -1045,11 +490,7 @@ pub async fn perfil_list_api(
             q.page.unwrap_or(1) as i32,
             q.page_size.unwrap_or(10) as i32,
         )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     Ok(Json(res))
 }
@@ -1061,20 +502,10 @@ pub async fn perfil_list_api(
 pub async fn list_user(
     Query(params): Query<ListParams>,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
+    Extension(current_user): Extension<middlewares::CurrentUser>,
+) -> Result<Html<String>, AppError> {
     let service = UserService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Usar o UserService para buscar dados paginados
     let result = service
         .get_paginated(
@@ -1085,79 +516,56 @@ pub async fn list_user(
         )
         .await;
 
-    match result {
-        Ok(paginated_response) => {
-            let context = minijinja::context! {
-                rows => paginated_response.data,
-                current_page => paginated_response.page,
-                total_pages => paginated_response.total_pages,
-                page_size => paginated_response.page_size,
-                total_records => paginated_response.total_records,
-                find => params.find.unwrap_or_default(),
-                flash_message => flash_message,
-                flash_status => flash_status,
-            };
-
-            match state.templates.get_template("permissao/user_list.html") {
-                Ok(template) => match template.render(context) {
-                    Ok(html) => Html(html).into_response(),
-                    Err(err) => {
-                        debug!("Erro ao renderizar template: {}", err);
-                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                    }
-                },
-                Err(err) => {
-                    debug!("Erro ao carregar template: {}", err);
-                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
-                }
-            }
-        }
-        Err(err) => {
-            debug!("Erro ao buscar users: {}", err);
-            let redirect_url = helpers::create_flash_url(
-                "/user",
-                &format!("Erro ao carregar users: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&redirect_url).into_response()
-        }
-    }
+    let paginated_response = result?;
+    let context = minijinja::context! {
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.unwrap_or_default(),
+        colunas_exportacao => user_export().options(),
+        pode_exportar => current_user.tem_permissao("admin"),
+    };
+
+    helpers::render(&state.templates, "permissao/user_list.html", context)
 }
 
-pub async fn show_user_form(
+/// Colunas da exportação de usuários; senha e OTP nunca são serializados
+fn user_export() -> ExportConfig {
+    ExportConfig::new("usuarios")
+        .column("id", "ID", ColumnKind::Int)
+        .column("username", "Usuário", ColumnKind::Text)
+        .column("full_name", "Nome", ColumnKind::Text)
+        .column("email", "E-mail", ColumnKind::Text)
+        .column("is_active", "Ativo", ColumnKind::Bool)
+        .column("is_staff", "Equipe", ColumnKind::Bool)
+        .column("is_superuser", "Superusuário", ColumnKind::Bool)
+        .column("last_login", "Último acesso", ColumnKind::DateTime)
+        .column("created_at", "Criado em", ColumnKind::DateTime)
+}
+
+pub async fn export_user(
     State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, Response> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
+    Query(params): Query<ListParams>,
+    Query(pares): Query<Vec<(String, String)>>,
+) -> Result<Response, AppError> {
+    let export = ExportParams::parse(&pares)?;
+
+    let db = state.db.clone();
+    export::exportar(&user_export(), &export, params.list_query(), move |query| {
+        let db = db.clone();
+        async move { UserService::new().list(&db, &query).await }
+    })
+    .await
+}
 
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
+pub async fn show_user_form(
+    State(state): State<SharedState>,
+) -> Result<Html<String>, AppError> {
+    let context = minijinja::context! {};
 
-    match state.templates.get_template("permissao/user_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "permissao/user_form.html", context)
 }
 
 pub async fn create_user(
@@ -1167,109 +575,63 @@ pub async fn create_user(
     let service = UserService::new();
 
     /* service.validate_email(&body.email).await.map_err(|err| {
-        let flash_url = helpers::create_flash_url(
+        flash_redirect(
             "/permissao/user-form",
-            &format!("Erro ao validar email: {}", err),
+            &format!("Erro ao validar email: {}", mensagem_erro(err)),
             FlashStatus::Error,
-        );
-        Redirect::to(&flash_url).into_response()
+        )
     })?; */
 
     match service.create(&state.db, body).await {
-        Ok(user) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/user-form/{}", user.id),
-                "User criado com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/user-form",
-                &format!("Erro ao criar user: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(user) => flash_redirect(
+            &format!("/permissao/user-form/{}", user.id),
+            "User criado com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/permissao/user-form",
+            &format!("Erro ao criar user: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
 pub async fn get_user(
     State(state): State<SharedState>,
     Path(id): Path<i32>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, Response> {
+) -> Result<Html<String>, AppError> {
     let service = UserService::new();
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     // Carregar o template
-    let template = match state.templates.get_template("permissao/user_form.html") {
-        Ok(t) => t,
-        Err(err) => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Falha ao carregar template: {}", err),
-            )
-                .into_response());
-        }
-    };
+    let template = state.templates.get_template("permissao/user_form.html")?;
 
     // Buscar o perfil
-    let perfil = match service.get_by_id(&state.db, id as i64).await {
-        Ok(p) => p,
-        Err(e) => {
-            debug!("Erro ao buscar perfil: {}", e);
-            let flash_url = helpers::create_flash_url(
-                "/permissao/perfil",
-                &format!("Perfil não encontrado: {}", e),
-                FlashStatus::Error,
-            );
-            return Err(Redirect::to(&flash_url).into_response());
-        }
-    };
+    let perfil = service.get_by_id(&state.db, id as i64).await?;
 
-    let otp_url = match &perfil.otp_base32 {
-        Some(secret) => UserService::get_otp_url(&perfil.full_name, secret),
+    let otp_url = match perfil
+        .otp_base32
+        .as_deref()
+        .and_then(|secret| UserService::get_otp_url(&perfil.full_name, secret))
+    {
+        Some(url) => url,
         None => {
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Usuário não possui OTP configurado".to_string(),
-            )
-                .into_response());
+            return Err(AppError::Unprocessable(
+                "usuário não possui OTP configurado".to_string(),
+            ));
         }
     };
 
     // gera o QR Code em Base64 a partir da URL
-    let qrcode = get_qr_code_base64(&otp_url).unwrap();
+    let qrcode = get_qr_code_base64(&otp_url)?;
 
     // Preparar o contexto
     let ctx = context! {
         row => perfil,
         qrcode => qrcode,
-        flash_message => flash_message,
-        flash_status => flash_status,
     };
 
     // Renderizar o template
-    match template.render(&ctx) {
-        Ok(html) => Ok(Html(html)),
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Falha ao renderizar template: {}", err),
-        )
-            .into_response()),
-    }
+    Ok(Html(template.render(&ctx)?))
 }
 
 /*
@@ -1282,41 +644,35 @@ pub async fn update_user(
     Form(body): Form<UserUpdateSchema>,
 ) -> Response {
     if !current_user.current_user.is_superuser {
-        let flash_url = helpers::create_flash_url(
+        return flash_redirect(
             &format!("/permissao/user-form/{}", id),
             &"Você não tem permissão para atualizar este usuário".to_string(),
             FlashStatus::Error,
         );
-        return Redirect::to(&flash_url).into_response();
     }
 
     let service = UserService::new();
     match service.update(&state.db, id, body).await {
         Ok(result) => {
             if result.id > 0 {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     &format!("/permissao/user-form/{}", result.id),
                     "Usuário atualizado com sucesso!",
                     FlashStatus::Success,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             } else {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     "/permissao/user-form",
                     "Usuário não atualizado",
                     FlashStatus::Error,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             }
         }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/user-form/{}", id),
-                &format!("Erro ao atualizar usuário: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Err(err) => flash_redirect(
+            &format!("/permissao/user-form/{}", id),
+            &format!("Erro ao atualizar usuário: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1329,40 +685,34 @@ pub async fn update_user_otp(
     Path(id): Path<i64>,
 ) -> Response {
     if !current_user.current_user.is_superuser {
-        let flash_url = helpers::create_flash_url(
+        return flash_redirect(
             &format!("/permissao/user-form/{}", id),
             &"Você não tem permissão para atualizar este usuário".to_string(),
             FlashStatus::Error,
         );
-        return Redirect::to(&flash_url).into_response();
     }
 
     match UserService::update_otp(&state.db, id).await {
         Ok(result) => {
             if result.id > 0 {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     &format!("/permissao/user-form/{}", result.id),
                     "OTP atualizado com sucesso!",
                     FlashStatus::Success,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             } else {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     "/permissao/user-form",
                     "OTP não atualizado",
                     FlashStatus::Error,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             }
         }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/user-form/{}", id),
-                &format!("Erro ao atualizar OTP: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Err(err) => flash_redirect(
+            &format!("/permissao/user-form/{}", id),
+            &format!("Erro ao atualizar OTP: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1377,67 +727,34 @@ pub async fn update_senha_user(
     match UserService::update_password(&state.db, id, body).await {
         Ok(result) => {
             if result.id > 0 {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     &format!("/permissao/user-form/{}", result.id),
                     "Senha atualizada com sucesso!",
                     FlashStatus::Success,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             } else {
-                let flash_url = helpers::create_flash_url(
+                flash_redirect(
                     "/permissao/user-form",
                     "Senha não atualizada",
                     FlashStatus::Error,
-                );
-                Redirect::to(&flash_url).into_response()
+                )
             }
         }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/user-form",
-                &format!("Erro ao atualizar senha: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Err(err) => flash_redirect(
+            "/permissao/user-form",
+            &format!("Erro ao atualizar senha: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
 /* troca de usuario pelo proprio usuario */
 pub async fn user_update_senha_local_form(
     State(state): State<SharedState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Html<String>, Response> {
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .get("msg")
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.get("status").and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
-    let context = minijinja::context! {
-        flash_message => flash_message,
-        flash_status => flash_status,
-    };
+) -> Result<Html<String>, AppError> {
+    let context = minijinja::context! {};
 
-    match state.templates.get_template("settings/senha_form.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "settings/senha_form.html", context)
 }
 
 /* troca de usuario pelo proprio usuario */
@@ -1445,61 +762,69 @@ pub async fn user_update_senha_local(
     State(state): State<SharedState>,
     Extension(current_user): Extension<middlewares::CurrentUser>,
     Form(form): Form<UserLocalPasswordUpdateSchema>,
-) -> Response {
+) -> Result<Response, AppError> {
     //validar senha atual
-    if !UserService::verify_password(&form.password, &current_user.current_user.password).unwrap() {
-        let flash_url = helpers::create_flash_url(
+    let senha_confere =
+        UserService::verify_password(&form.password, &current_user.current_user.password).map_err(
+            |err| AppError::Internal(anyhow::anyhow!("hash de senha inválido: {}", err)),
+        )?;
+    if !senha_confere {
+        return Ok(flash_redirect(
             "/permissao/senha-form",
             "Senha atual incorreta",
             FlashStatus::Error,
-        );
-        return Redirect::to(&flash_url).into_response();
+        ));
     }
 
     // validar nova senha
     if let Err(errors) = form.validate() {
         //debug!("Erros de validação: {:?}", errors);
 
-        let flash_url = helpers::create_flash_url(
+        return Ok(flash_redirect(
             "/permissao/senha-form",
             &format!("Nova senha inválida: {:?}", errors),
             FlashStatus::Error,
-        );
-        return Redirect::to(&flash_url).into_response();
+        ));
     }
 
-    match UserService::update_password(
+    let resultado = UserService::update_password(
         &state.db,
         current_user.current_user.id,
         UserPasswordUpdateSchema {
             password: form.password,
         },
     )
-    .await
-    {
-        Ok(_user) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/senha-form"),
-                "Senha atualizada com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/senha-form",
-                &format!("Erro ao atualizar senha: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-    }
+    .await;
+
+    Ok(match resultado {
+        Ok(_user) => flash_redirect(
+            "/permissao/senha-form",
+            "Senha atualizada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/permissao/senha-form",
+            &format!("Erro ao atualizar senha: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
+    })
 }
 
+#[utoipa::path(
+    get,
+    path = "/user-api",
+    tag = "permissao",
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Página de resultados", body = PaginatedResponse<User>),
+        (status = 500, description = "Erro interno", body = ErrorResponse),
+    ),
+    security(("bearer" = []), ("cookie" = [])),
+)]
 pub async fn users_list_api(
     Query(q): Query<PaginationQuery>,
     State(state): State<SharedState>,
-) -> Result<Json<PaginatedResponse<User>>, StatusCode> {
+) -> Result<Json<PaginatedResponse<User>>, AppError> {
     let service = UserService::new();
     let res = service
         .get_paginated(
@@ -1508,11 +833,7 @@ pub async fn users_list_api(
             q.page.unwrap_or(1) as i32,
             q.page_size.unwrap_or(10) as i32,
         )
-        .await
-        .map_err(|err| {
-            debug!("error:{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     Ok(Json(res))
 }
@@ -1525,27 +846,16 @@ pub async fn get_user_gestao_perfil(
     Query(params): Query<ListParams>,
     Query(form): Query<UserParams>,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     debug!("parametro: {:?}", form.user_id);
     let service_user = UserService::new();
     let service_user_roles = UserRolesService::new();
 
     let mut context = minijinja::context! {};
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     match form.user_id {
         Some(user_id) => {
-            let user = Some(service_user.get_by_id(&*state.db, user_id).await.unwrap());
+            let user = Some(service_user.get_by_id(&state.db, user_id).await?);
             let result = service_user_roles
                 .get_paginated_with_roles(
                     &state.db,
@@ -1565,8 +875,6 @@ pub async fn get_user_gestao_perfil(
                         page_size => paginated_response.page_size,
                         total_records => paginated_response.total_records,
                         find => params.find.unwrap_or_default(),
-                        flash_message => flash_message,
-                        flash_status => flash_status,
                     };
                 }
                 Err(_err) => {}
@@ -1575,24 +883,7 @@ pub async fn get_user_gestao_perfil(
         None => (),
     }
 
-    match state
-        .templates
-        .get_template("permissao/perfil_usuario.html")
-    {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "permissao/perfil_usuario.html", context)
 }
 
 pub async fn create_user_gestao_perfil(
@@ -1604,25 +895,16 @@ pub async fn create_user_gestao_perfil(
     let user_id = body.user_id.clone();
 
     match service.create(&state.db, body).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!(
-                    "/permissao/user-gestao-perfil?user_id={}",
-                    user_id.to_string()
-                ),
-                "Perfil adicionado com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/user-gestao-perfil",
-                &format!("Erro ao adicionar perfil: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            &format!("/permissao/user-gestao-perfil?user_id={}", user_id),
+            "Perfil adicionado com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/permissao/user-gestao-perfil",
+            &format!("Erro ao adicionar perfil: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1633,22 +915,16 @@ pub async fn delete_user_gestao_perfil(
     let service = UserRolesService::new();
 
     match service.delete(&state.db, id).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/user-gestao-perfil",
-                "Perfil removido com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/user-gestao-perfil",
-                &format!("Erro ao remover perfil: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/permissao/user-gestao-perfil",
+            "Perfil removido com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/permissao/user-gestao-perfil",
+            &format!("Erro ao remover perfil: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1660,31 +936,15 @@ pub async fn get_gestao_perfil(
     Query(params): Query<ListParams>,
     Query(form): Query<IdParams>,
     State(state): State<SharedState>,
-) -> impl IntoResponse {
+) -> Result<Html<String>, AppError> {
     let service_perfil = PerfilService::new();
     let service = RolePermissionService::new();
 
     let mut context = minijinja::context! {};
 
-    // Extrair mensagens flash dos parâmetros da query
-    let flash_message = params
-        .msg
-        .as_ref()
-        .map(|msg| urlencoding::decode(msg).unwrap_or_default().to_string());
-    let flash_status = params.status.as_ref().and_then(|s| match s.as_str() {
-        "success" => Some("success"),
-        "error" => Some("error"),
-        _ => None,
-    });
-
     match form.id {
         Some(id) => {
-            let perfil = Some(
-                service_perfil
-                    .get_by_id(&*state.db, id as i32)
-                    .await
-                    .unwrap(),
-            );
+            let perfil = Some(service_perfil.get_by_id(&state.db, id as i32).await?);
             let result = service
                 .get_paginated_with_permission(
                     &state.db,
@@ -1693,19 +953,22 @@ pub async fn get_gestao_perfil(
                     params.page_size.unwrap_or(10) as i64,
                 )
                 .await;
+            let disponiveis = service
+                .permissoes_disponiveis(&state.db, id as i32)
+                .await
+                .unwrap_or_default();
 
             match result {
                 Ok(paginated_response) => {
                     context = minijinja::context! {
                         perfil => perfil,
+                        disponiveis => disponiveis,
                         rows => paginated_response.data,
                         current_page => paginated_response.page,
                         total_pages => paginated_response.total_pages,
                         page_size => paginated_response.page_size,
                         total_records => paginated_response.total_records,
                         find => params.find.unwrap_or_default(),
-                        flash_message => flash_message,
-                        flash_status => flash_status,
                     };
                 }
                 Err(_err) => {}
@@ -1714,21 +977,7 @@ pub async fn get_gestao_perfil(
         None => (),
     }
 
-    match state.templates.get_template("permissao/perfil_gestao.html") {
-        Ok(template) => match template.render(context) {
-            Ok(html) => Ok(Html(html)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Erro ao renderizar template: {}", err),
-            )
-                .into_response()),
-        },
-        Err(err) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Erro ao carregar template: {}", err),
-        )
-            .into_response()),
-    }
+    helpers::render(&state.templates, "permissao/perfil_gestao.html", context)
 }
 
 pub async fn create_gestao_perfil(
@@ -1740,22 +989,16 @@ pub async fn create_gestao_perfil(
     let role_id = body.role_id.clone();
 
     match service.create(&state.db, body).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                &format!("/permissao/gestao-perfil?id={}", role_id.to_string()),
-                "Permissão adicionada com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
-        Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/gestao-perfil",
-                &format!("Erro ao adicionar permissão: {}", err),
-                FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            &format!("/permissao/gestao-perfil?id={}", role_id),
+            "Permissão adicionada com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/permissao/gestao-perfil",
+            &format!("Erro ao adicionar permissão: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
     }
 }
 
@@ -1766,21 +1009,59 @@ pub async fn delete_gestao_perfil(
     let service = RolePermissionService::new();
 
     match service.delete(&state.db, id).await {
-        Ok(_) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/gestao-perfil",
-                "Perfil removido com sucesso!",
-                FlashStatus::Success,
-            );
-            Redirect::to(&flash_url).into_response()
-        }
+        Ok(_) => flash_redirect(
+            "/permissao/gestao-perfil",
+            "Perfil removido com sucesso!",
+            FlashStatus::Success,
+        ),
+        Err(err) => flash_redirect(
+            "/permissao/gestao-perfil",
+            &format!("Erro ao remover perfil: {}", mensagem_erro(err)),
+            FlashStatus::Error,
+        ),
+    }
+}
+
+/*
+inclusão/remoção em lote: selecionadas (ids) ou todas as permissões
+*/
+pub async fn lote_gestao_perfil(
+    State(state): State<SharedState>,
+    Form(body): Form<LoteForm>,
+) -> Response {
+    let service = RolePermissionService::new();
+
+    let role_id = body.owner_id;
+    let url = format!("/permissao/gestao-perfil?id={}", role_id);
+
+    let result = match body.ids() {
+        Ok(ids) => match body.acao {
+            AcaoLote::Adicionar => service.create_many(&state.db, role_id, &ids).await,
+            AcaoLote::Remover => service.delete_many(&state.db, role_id, &ids).await,
+            AcaoLote::AdicionarTodos => service.create_all(&state.db, role_id).await,
+            AcaoLote::RemoverTodos => service.delete_all(&state.db, role_id).await,
+        },
+        Err(err) => Err(err),
+    };
+
+    let verbo = match body.acao {
+        AcaoLote::Adicionar | AcaoLote::AdicionarTodos => "adicionada(s)",
+        AcaoLote::Remover | AcaoLote::RemoverTodos => "removida(s)",
+    };
+
+    match result {
+        Ok(total) => flash_redirect(
+            &url,
+            &format!("{} permissão(ões) {}", total, verbo),
+            FlashStatus::Success,
+        ),
         Err(err) => {
-            let flash_url = helpers::create_flash_url(
-                "/permissao/gestao-perfil",
-                &format!("Erro ao remover perfil: {}", err),
+            debug!("Erro na gestão em lote do perfil: {}", err);
+            flash_redirect(
+                &url,
+                &format!("Erro ao atualizar permissões: {}", mensagem_erro(err)),
                 FlashStatus::Error,
-            );
-            Redirect::to(&flash_url).into_response()
+            )
         }
     }
 }
@@ -0,0 +1,55 @@
+use axum::{
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+};
+use minijinja::context;
+use serde::Serialize;
+use shared::{ConflictError, SharedState};
+use tracing::debug;
+
+use crate::permissao::UserService;
+
+/*
+página de conflito (409) para um update com versão desatualizada:
+mostra quem alterou, quando, e a diferença entre o que foi enviado e o valor atual
+*/
+pub async fn conflito_response<E: Serialize>(
+    state: &SharedState,
+    conflito: &ConflictError,
+    enviado: &E,
+    voltar_url: &str,
+) -> Response {
+    let alterado_por = match conflito.campo("updated_by").and_then(|v| v.as_i64()) {
+        Some(user_id) => UserService::new()
+            .get_by_id(&state.db, user_id)
+            .await
+            .map(|u| u.full_name)
+            .ok(),
+        None => None,
+    };
+    let alterado_em = conflito
+        .campo("updated_at")
+        .or_else(|| conflito.campo("updatedAt"))
+        .cloned();
+
+    let ctx = context! {
+        alterado_por => alterado_por,
+        alterado_em => alterado_em,
+        campos => conflito.diff(enviado),
+        voltar_url => voltar_url,
+    };
+
+    match state.templates.get_template("shared/conflito.html") {
+        Ok(template) => match template.render(ctx) {
+            Ok(html) => (StatusCode::CONFLICT, Html(html)).into_response(),
+            Err(err) => {
+                debug!("Erro ao renderizar template: {}", err);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        },
+        Err(err) => {
+            debug!("Erro ao carregar template: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -2,6 +2,14 @@ use std::str::FromStr;
 
 use bigdecimal::BigDecimal;
 use serde::{Deserialize, Deserializer};
+/// checkbox do formulário chega como texto; o JSON da API manda booleano
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BoolOuTexto {
+    Bool(bool),
+    Texto(String),
+}
+
 /*
 utilizado nos shemas para converter checkbox em booleano
 */
@@ -9,11 +17,11 @@ pub fn bool_from_str<'de, D>(deserializer: D) -> Result<bool, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let opt = Option::<String>::deserialize(deserializer)?;
-    Ok(matches!(
-        opt.as_deref(),
-        Some("true") | Some("on") | Some("1") | Some("yes")
-    ))
+    Ok(match Option::<BoolOuTexto>::deserialize(deserializer)? {
+        Some(BoolOuTexto::Bool(valor)) => valor,
+        Some(BoolOuTexto::Texto(s)) => matches!(s.as_str(), "true" | "on" | "1" | "yes"),
+        None => false,
+    })
 }
 
 // versão para Option<bool>
@@ -25,16 +33,49 @@ where
     Ok(opt.map(|s| matches!(s.as_str(), "true" | "on" | "1" | "yes")))
 }
 
+/// número do JSON da API ou texto formatado do formulário
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumeroOuTexto {
+    Numero(serde_json::Number),
+    Texto(String),
+}
+
 /*
 utilizado no formulario html com formato que trata.
 20.000,00 → 20000.00.
+no JSON também aceita número (20000.5)
 */
 pub fn brl_to_bigdecimal<'de, D>(deserializer: D) -> Result<BigDecimal, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: String = String::deserialize(deserializer)?;
+    let s = match NumeroOuTexto::deserialize(deserializer)? {
+        NumeroOuTexto::Numero(numero) => {
+            return BigDecimal::from_str(&numero.to_string()).map_err(serde::de::Error::custom);
+        }
+        NumeroOuTexto::Texto(s) => s,
+    };
     // remove pontos de milhar e troca vírgula por ponto
     let normalized = s.replace(".", "").replace(",", ".");
     BigDecimal::from_str(&normalized).map_err(serde::de::Error::custom)
 }
+
+/*
+campo numérico opcional do formulário: vazio vira None
+no JSON também aceita número ou null
+*/
+pub fn option_i64_from_str<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<NumeroOuTexto>::deserialize(deserializer)? {
+        Some(NumeroOuTexto::Numero(numero)) => numero
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom("número inteiro inválido")),
+        Some(NumeroOuTexto::Texto(s)) if s.trim().is_empty() => Ok(None),
+        Some(NumeroOuTexto::Texto(s)) => s.trim().parse().map(Some).map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
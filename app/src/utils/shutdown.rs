@@ -0,0 +1,91 @@
+use std::{future::Future, time::Duration};
+
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tracing::{info, warn};
+
+/// Tempo padrão para concluir requisições e tarefas após o sinal de parada
+const TIMEOUT_PADRAO: Duration = Duration::from_secs(30);
+
+/// Lê `SHUTDOWN_TIMEOUT_SECS` (padrão 30s)
+pub fn shutdown_timeout() -> Duration {
+    std::env::var("SHUTDOWN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(TIMEOUT_PADRAO)
+}
+
+/// Aguarda SIGINT (Ctrl+C) ou SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("falha ao instalar handler de Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("falha ao instalar handler de SIGTERM")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("SIGINT recebido, encerrando"),
+        _ = terminate => info!("SIGTERM recebido, encerrando"),
+    }
+}
+
+/*
+tarefas em segundo plano do servidor
+cada tarefa recebe um token que é cancelado no desligamento
+e deve encerrar o laço assim que o token for cancelado
+*/
+#[derive(Clone, Default)]
+pub struct Workers {
+    token: CancellationToken,
+    tracker: TaskTracker,
+}
+
+impl Workers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Token cancelado quando o desligamento começa
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn spawn<F, Fut>(&self, nome: &'static str, tarefa: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let fut = tarefa(self.token.clone());
+        self.tracker.spawn(async move {
+            fut.await;
+            info!("tarefa '{}' encerrada", nome);
+        });
+    }
+
+    /// Cancela e aguarda as tarefas até o tempo limite
+    pub async fn shutdown(&self, timeout: Duration) {
+        self.token.cancel();
+        self.tracker.close();
+        if tokio::time::timeout(timeout, self.tracker.wait())
+            .await
+            .is_err()
+        {
+            warn!(
+                "{} tarefa(s) em segundo plano não encerraram em {:?}",
+                self.tracker.len(),
+                timeout
+            );
+        }
+    }
+}
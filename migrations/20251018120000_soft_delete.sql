@@ -0,0 +1,8 @@
+-- Exclusão lógica (lixeira) para linha, região e tipo de chamado
+ALTER TABLE linha ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE emprestimo_regiao ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE chamado_tipos_chamado ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX idx_linha_deleted_at ON linha(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_emprestimo_regiao_deleted_at ON emprestimo_regiao(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_chamado_tipos_chamado_deleted_at ON chamado_tipos_chamado(deleted_at) WHERE deleted_at IS NOT NULL;
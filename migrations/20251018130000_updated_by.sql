@@ -0,0 +1,4 @@
+-- Controle de concorrência otimista: updated_at é a versão do registro,
+-- updated_by guarda quem fez a última alteração (exibido na tela de conflito)
+ALTER TABLE contato ADD COLUMN updated_by BIGINT REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE chamado_gerenciamento_chamado ADD COLUMN updated_by BIGINT REFERENCES users(id) ON DELETE SET NULL;
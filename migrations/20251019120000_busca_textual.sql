@@ -0,0 +1,40 @@
+-- Busca textual em português, sem acento: "Joao" encontra "João"
+-- a configuração portuguese_unaccent tira o acento antes do radical (portuguese_stem)
+CREATE EXTENSION IF NOT EXISTS unaccent;
+
+CREATE TEXT SEARCH CONFIGURATION portuguese_unaccent (COPY = portuguese);
+ALTER TEXT SEARCH CONFIGURATION portuguese_unaccent
+    ALTER MAPPING FOR hword, hword_part, word WITH unaccent, portuguese_stem;
+
+-- Texto puro do documento EditorJS: texto, legendas, listas e tabelas, sem tags HTML
+CREATE OR REPLACE FUNCTION editorjs_texto(doc JSONB) RETURNS TEXT
+LANGUAGE sql IMMUTABLE PARALLEL SAFE AS $$
+    SELECT COALESCE(
+        regexp_replace(string_agg(v #>> '{}', ' '), '<[^>]*>|&nbsp;', ' ', 'g'),
+        ''
+    )
+    FROM unnest(ARRAY[
+        'strict $.blocks[*].data ? (exists(@.text)).text',
+        'strict $.blocks[*].data ? (exists(@.caption)).caption',
+        'strict $.blocks[*].data ? (exists(@.items)).items.**',
+        'strict $.blocks[*].data ? (exists(@.content)).content.**'
+    ]::jsonpath[]) AS caminho,
+    jsonb_path_query(doc, caminho, '{}', true) AS v
+    WHERE jsonb_typeof(v) = 'string'
+$$;
+
+-- As expressões precisam ser iguais às de search_vector() nos repositórios
+CREATE INDEX idx_contato_busca ON contato USING GIN ((
+    setweight(to_tsvector('portuguese_unaccent', nome), 'A')
+    || setweight(to_tsvector('portuguese_unaccent', regexp_replace(protocolo || ' ' || cpf_cnpj, '[^[:alnum:]]+', ' ', 'g')), 'A')
+));
+
+CREATE INDEX idx_chamado_chamados_busca ON chamado_chamados USING GIN ((
+    setweight(to_tsvector('portuguese_unaccent', titulo), 'A')
+    || setweight(to_tsvector('portuguese_unaccent', editorjs_texto(descricao)), 'B')
+));
+
+CREATE INDEX idx_users_busca ON users USING GIN ((
+    setweight(to_tsvector('portuguese_unaccent', full_name), 'A')
+    || setweight(to_tsvector('portuguese_unaccent', regexp_replace(username || ' ' || email, '[^[:alnum:]]+', ' ', 'g')), 'B')
+));
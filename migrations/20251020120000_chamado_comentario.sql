@@ -0,0 +1,68 @@
+-- Conversa do chamado: cada resposta vira um comentário, em vez de sobrescrever
+-- observacao_chamado / descricao do atendimento
+CREATE TABLE IF NOT EXISTS chamado_comentario (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    chamado_id BIGINT NOT NULL,
+    autor_id BIGINT NOT NULL,
+    corpo JSONB NOT NULL, -- documento EditorJS
+    interno BOOLEAN NOT NULL DEFAULT FALSE, -- visível só para quem atende (chamado_admin)
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_comentario_chamado FOREIGN KEY (chamado_id)
+        REFERENCES chamado_chamados(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_comentario_autor FOREIGN KEY (autor_id)
+        REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_comentario_chamado_created
+    ON chamado_comentario (chamado_id, created_at);
+
+CREATE TRIGGER set_updated_at
+BEFORE UPDATE ON chamado_comentario
+FOR EACH ROW
+EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE IF NOT EXISTS chamado_comentario_anexo (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    comentario_id BIGINT NOT NULL,
+    nome VARCHAR(255) NOT NULL, -- nome original do arquivo
+    url VARCHAR(500) NOT NULL,
+    tamanho BIGINT NOT NULL,
+    content_type VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_anexo_comentario FOREIGN KEY (comentario_id)
+        REFERENCES chamado_comentario(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_comentario_anexo_comentario
+    ON chamado_comentario_anexo (comentario_id);
+
+-- As observações já gravadas no atendimento viram o primeiro comentário da conversa
+CREATE OR REPLACE FUNCTION editorjs_paragrafo(texto TEXT)
+RETURNS JSONB AS $$
+    SELECT jsonb_build_object(
+        'time', 0,
+        'blocks', jsonb_build_array(jsonb_build_object(
+            'type', 'paragraph',
+            'data', jsonb_build_object(
+                'text', replace(replace(replace(texto, '&', '&amp;'), '<', '&lt;'), '>', '&gt;')
+            )
+        )),
+        'version', '2.31.0-rc.7'
+    );
+$$ LANGUAGE sql IMMUTABLE;
+
+INSERT INTO chamado_comentario (chamado_id, autor_id, corpo, interno, created_at, updated_at)
+SELECT chamado_id, COALESCE(updated_by, user_atend_id), editorjs_paragrafo(descricao), TRUE, updated_at, updated_at
+FROM chamado_gerenciamento_chamado
+WHERE COALESCE(btrim(descricao), '') <> '';
+
+INSERT INTO chamado_comentario (chamado_id, autor_id, corpo, interno, created_at, updated_at)
+SELECT chamado_id, COALESCE(updated_by, user_atend_id), editorjs_paragrafo(observacao_chamado), FALSE, updated_at, updated_at
+FROM chamado_gerenciamento_chamado
+WHERE COALESCE(btrim(observacao_chamado), '') <> '';
+
+DROP FUNCTION editorjs_paragrafo(TEXT);
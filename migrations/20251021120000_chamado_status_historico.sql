@@ -0,0 +1,45 @@
+-- Histórico de status do chamado: uma linha por transição (ver chamado::transicao)
+CREATE TABLE IF NOT EXISTS chamado_status_historico (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    chamado_id BIGINT NOT NULL,
+    status_de INTEGER, -- NULL na abertura do chamado
+    status_para INTEGER NOT NULL,
+    user_id BIGINT, -- NULL nas linhas reconstruídas abaixo
+    motivo TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_historico_chamado FOREIGN KEY (chamado_id)
+        REFERENCES chamado_chamados(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_historico_user FOREIGN KEY (user_id)
+        REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_status_historico_chamado_created
+    ON chamado_status_historico (chamado_id, created_at);
+
+-- Chamados já existentes: abertura e, se já saiu de Aberto, o status atual na última alteração
+INSERT INTO chamado_status_historico (chamado_id, status_de, status_para, user_id, created_at)
+SELECT id, NULL, 0, user_solic_id, created_at
+FROM chamado_chamados;
+
+INSERT INTO chamado_status_historico (chamado_id, status_de, status_para, motivo, created_at)
+SELECT id, 0, status, 'reconstruído na migração do histórico', updated_at
+FROM chamado_chamados
+WHERE COALESCE(status, 0) <> 0;
+
+-- Quanto tempo o chamado ficou em cada status; o trecho atual conta até agora,
+-- exceto Fechado (4), que encerra a contagem. Base para as métricas de tempo de resolução
+CREATE OR REPLACE VIEW chamado_tempo_status AS
+SELECT
+    h.id AS historico_id,
+    h.chamado_id,
+    h.status_para AS status,
+    h.created_at AS inicio,
+    LEAD(h.created_at) OVER w AS fim,
+    CASE
+        WHEN LEAD(h.created_at) OVER w IS NULL AND h.status_para = 4 THEN NULL
+        ELSE EXTRACT(EPOCH FROM COALESCE(LEAD(h.created_at) OVER w, NOW()) - h.created_at)::BIGINT
+    END AS segundos
+FROM chamado_status_historico h
+WINDOW w AS (PARTITION BY h.chamado_id ORDER BY h.created_at, h.id);
@@ -0,0 +1,49 @@
+-- Política de SLA: prazos por serviço, com a do tipo como padrão
+CREATE TABLE IF NOT EXISTS chamado_sla_politica (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    servico_id BIGINT UNIQUE,
+    tipo_id BIGINT UNIQUE,
+    prazo_resposta_min INTEGER NOT NULL, -- até o início do atendimento
+    prazo_resolucao_min INTEGER NOT NULL, -- até Resolvido, sem contar o tempo Pausado
+    alerta_pct INTEGER NOT NULL DEFAULT 80, -- % do prazo consumido que marca "em risco"
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_sla_politica_servico FOREIGN KEY (servico_id)
+        REFERENCES chamado_servico_chamado(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_sla_politica_tipo FOREIGN KEY (tipo_id)
+        REFERENCES chamado_tipos_chamado(id) ON DELETE CASCADE,
+
+    CONSTRAINT ck_sla_politica_alvo CHECK (num_nonnulls(servico_id, tipo_id) = 1),
+    CONSTRAINT ck_sla_politica_prazos CHECK (prazo_resposta_min > 0 AND prazo_resolucao_min > 0),
+    CONSTRAINT ck_sla_politica_alerta CHECK (alerta_pct BETWEEN 1 AND 100)
+);
+
+CREATE TRIGGER set_updated_at
+BEFORE UPDATE ON chamado_sla_politica
+FOR EACH ROW
+EXECUTE FUNCTION update_updated_at_column();
+
+-- Prazos e situação de cada chamado, recalculados pelo verificador de SLA
+CREATE TABLE IF NOT EXISTS chamado_sla (
+    chamado_id BIGINT PRIMARY KEY,
+    politica_id BIGINT,
+    prazo_resposta TIMESTAMPTZ NOT NULL,
+    prazo_resolucao TIMESTAMPTZ NOT NULL,
+    respondido_em TIMESTAMPTZ,
+    resolvido_em TIMESTAMPTZ,
+    situacao INTEGER NOT NULL DEFAULT 0, -- 0 no prazo, 1 em risco, 2 violado
+    violado_em TIMESTAMPTZ, -- quando o verificador viu a violação pela primeira vez
+    calculado_em TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_sla_chamado FOREIGN KEY (chamado_id)
+        REFERENCES chamado_chamados(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_sla_politica FOREIGN KEY (politica_id)
+        REFERENCES chamado_sla_politica(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_chamado_sla_situacao ON chamado_sla (situacao);
+CREATE INDEX IF NOT EXISTS idx_chamado_sla_violado_em ON chamado_sla (violado_em)
+    WHERE violado_em IS NOT NULL;
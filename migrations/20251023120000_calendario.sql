@@ -0,0 +1,50 @@
+-- Expediente: faixas de horário de trabalho por dia da semana, no fuso America/Sao_Paulo
+CREATE TABLE IF NOT EXISTS calendario_expediente (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    dia_semana INTEGER NOT NULL, -- ISO: 1 segunda ... 7 domingo
+    inicio TIME NOT NULL,
+    fim TIME NOT NULL,
+
+    CONSTRAINT ck_expediente_dia CHECK (dia_semana BETWEEN 1 AND 7),
+    CONSTRAINT ck_expediente_faixa CHECK (inicio < fim)
+);
+
+CREATE INDEX IF NOT EXISTS idx_calendario_expediente_dia ON calendario_expediente (dia_semana);
+
+-- segunda a sexta, 08:00-12:00 e 13:00-18:00
+INSERT INTO calendario_expediente (dia_semana, inicio, fim)
+SELECT d, f.inicio, f.fim
+FROM generate_series(1, 5) d
+CROSS JOIN (VALUES (TIME '08:00', TIME '12:00'), (TIME '13:00', TIME '18:00')) f(inicio, fim);
+
+-- Feriados: nacionais (sem uf/município), estaduais (uf_id) ou municipais (municipio_id)
+-- móveis (Carnaval, Sexta-feira Santa, Corpus Christi) são calculados em core::calendario
+CREATE TABLE IF NOT EXISTS calendario_feriado (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    nome VARCHAR(255) NOT NULL,
+    data DATE NOT NULL,
+    recorrente BOOLEAN NOT NULL DEFAULT FALSE, -- repete todo ano no mesmo dia e mês
+    uf_id BIGINT,
+    municipio_id BIGINT,
+
+    CONSTRAINT fk_feriado_uf FOREIGN KEY (uf_id)
+        REFERENCES uf(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_feriado_municipio FOREIGN KEY (municipio_id)
+        REFERENCES municipio(id) ON DELETE CASCADE,
+
+    CONSTRAINT ck_feriado_abrangencia CHECK (num_nonnulls(uf_id, municipio_id) <= 1)
+);
+
+CREATE INDEX IF NOT EXISTS idx_calendario_feriado_data ON calendario_feriado (data);
+
+INSERT INTO calendario_feriado (nome, data, recorrente) VALUES
+    ('Confraternização Universal', DATE '2000-01-01', TRUE),
+    ('Tiradentes', DATE '2000-04-21', TRUE),
+    ('Dia do Trabalho', DATE '2000-05-01', TRUE),
+    ('Independência do Brasil', DATE '2000-09-07', TRUE),
+    ('Nossa Senhora Aparecida', DATE '2000-10-12', TRUE),
+    ('Finados', DATE '2000-11-02', TRUE),
+    ('Proclamação da República', DATE '2000-11-15', TRUE),
+    ('Dia Nacional de Zumbi e da Consciência Negra', DATE '2000-11-20', TRUE),
+    ('Natal', DATE '2000-12-25', TRUE);
@@ -0,0 +1,100 @@
+-- Filas de atendimento: uma por serviço, com os atendentes membros
+CREATE TABLE IF NOT EXISTS chamado_fila (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    nome VARCHAR(255) NOT NULL,
+    servico_id BIGINT NOT NULL UNIQUE,
+    estrategia INTEGER NOT NULL DEFAULT 0, -- 0 manual, 1 rodízio, 2 menor carga, 3 habilidade (ver chamado::fila)
+    ativo BOOLEAN NOT NULL DEFAULT TRUE,
+    ultimo_user_id BIGINT, -- último atendente escolhido pelo rodízio
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_fila_servico FOREIGN KEY (servico_id)
+        REFERENCES chamado_servico_chamado(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_fila_ultimo_user FOREIGN KEY (ultimo_user_id)
+        REFERENCES users(id) ON DELETE SET NULL,
+
+    CONSTRAINT ck_fila_estrategia CHECK (estrategia BETWEEN 0 AND 3)
+);
+
+CREATE TRIGGER set_updated_at
+BEFORE UPDATE ON chamado_fila
+FOR EACH ROW
+EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE IF NOT EXISTS chamado_fila_membro (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    fila_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    ativo BOOLEAN NOT NULL DEFAULT TRUE, -- inativo (férias, afastamento) não recebe chamados
+
+    CONSTRAINT fk_fila_membro_fila FOREIGN KEY (fila_id)
+        REFERENCES chamado_fila(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_fila_membro_user FOREIGN KEY (user_id)
+        REFERENCES users(id) ON DELETE CASCADE,
+
+    CONSTRAINT uq_fila_membro UNIQUE (fila_id, user_id)
+);
+
+-- Tipos de chamado que o atendente domina (estratégia por habilidade)
+CREATE TABLE IF NOT EXISTS chamado_atendente_habilidade (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    tipo_id BIGINT NOT NULL,
+
+    CONSTRAINT fk_habilidade_user FOREIGN KEY (user_id)
+        REFERENCES users(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_habilidade_tipo FOREIGN KEY (tipo_id)
+        REFERENCES chamado_tipos_chamado(id) ON DELETE CASCADE,
+
+    CONSTRAINT uq_habilidade UNIQUE (user_id, tipo_id)
+);
+
+-- Atendente responsável pelo chamado; sem linha, o chamado aguarda na fila
+CREATE TABLE IF NOT EXISTS chamado_atribuicao (
+    chamado_id BIGINT PRIMARY KEY,
+    fila_id BIGINT,
+    user_id BIGINT NOT NULL,
+    atribuido_em TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_atribuicao_chamado FOREIGN KEY (chamado_id)
+        REFERENCES chamado_chamados(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_atribuicao_fila FOREIGN KEY (fila_id)
+        REFERENCES chamado_fila(id) ON DELETE SET NULL,
+
+    CONSTRAINT fk_atribuicao_user FOREIGN KEY (user_id)
+        REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_chamado_atribuicao_user ON chamado_atribuicao (user_id);
+
+-- Histórico de atribuições e transferências
+CREATE TABLE IF NOT EXISTS chamado_atribuicao_historico (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    chamado_id BIGINT NOT NULL,
+    user_de BIGINT, -- NULL na primeira atribuição
+    user_para BIGINT NOT NULL,
+    por_user_id BIGINT, -- NULL na distribuição automática
+    motivo TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_atribuicao_hist_chamado FOREIGN KEY (chamado_id)
+        REFERENCES chamado_chamados(id) ON DELETE CASCADE,
+
+    CONSTRAINT fk_atribuicao_hist_de FOREIGN KEY (user_de) REFERENCES users(id),
+    CONSTRAINT fk_atribuicao_hist_para FOREIGN KEY (user_para) REFERENCES users(id),
+    CONSTRAINT fk_atribuicao_hist_por FOREIGN KEY (por_user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_atribuicao_historico_chamado
+    ON chamado_atribuicao_historico (chamado_id, created_at);
+
+-- Atendimentos já iniciados: o atendente atual fica como responsável
+INSERT INTO chamado_atribuicao (chamado_id, user_id, atribuido_em)
+SELECT chamado_id, user_atend_id, created_at
+FROM chamado_gerenciamento_chamado
+ON CONFLICT (chamado_id) DO NOTHING;
@@ -0,0 +1,53 @@
+-- Notificações internas (sino da barra superior)
+CREATE TABLE IF NOT EXISTS notificacao (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    evento VARCHAR(50) NOT NULL, -- ver notificacao::Evento
+    titulo VARCHAR(255) NOT NULL,
+    mensagem TEXT,
+    url VARCHAR(500),
+    lida_em TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_notificacao_user FOREIGN KEY (user_id)
+        REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_notificacao_user
+    ON notificacao (user_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_notificacao_nao_lida
+    ON notificacao (user_id) WHERE lida_em IS NULL;
+
+-- Caixa de saída de e-mails; o corpo é renderizado na entrega com templates/email/<evento>.html
+CREATE TABLE IF NOT EXISTS notificacao_email (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    user_id BIGINT,
+    destinatario VARCHAR(254) NOT NULL,
+    evento VARCHAR(50) NOT NULL,
+    contexto JSONB NOT NULL DEFAULT '{}',
+    tentativas INTEGER NOT NULL DEFAULT 0,
+    ultimo_erro TEXT,
+    enviar_apos TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    enviado_em TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_notificacao_email_user FOREIGN KEY (user_id)
+        REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_notificacao_email_pendente
+    ON notificacao_email (enviar_apos) WHERE enviado_em IS NULL;
+
+-- Preferências por evento; sem linha, o usuário recebe pelos dois canais
+CREATE TABLE IF NOT EXISTS notificacao_preferencia (
+    user_id BIGINT NOT NULL,
+    evento VARCHAR(50) NOT NULL,
+    interna BOOLEAN NOT NULL DEFAULT TRUE,
+    email BOOLEAN NOT NULL DEFAULT TRUE,
+
+    PRIMARY KEY (user_id, evento),
+
+    CONSTRAINT fk_notificacao_preferencia_user FOREIGN KEY (user_id)
+        REFERENCES users(id) ON DELETE CASCADE
+);
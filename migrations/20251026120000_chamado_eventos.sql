@@ -0,0 +1,28 @@
+-- Avisa as instâncias da aplicação (LISTEN chamado_eventos) de cada mudança em chamado_chamados;
+-- o NOTIFY só é entregue no COMMIT, então transações desfeitas não geram evento
+CREATE OR REPLACE FUNCTION notificar_evento_chamado()
+RETURNS TRIGGER AS $$
+DECLARE
+    linha chamado_chamados%ROWTYPE;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        linha := OLD;
+    ELSE
+        linha := NEW;
+    END IF;
+
+    PERFORM pg_notify('chamado_eventos', json_build_object(
+        'tipo', CASE TG_OP WHEN 'INSERT' THEN 'criado' WHEN 'UPDATE' THEN 'atualizado' ELSE 'excluido' END,
+        'id', linha.id,
+        'user_solic_id', linha.user_solic_id,
+        'status', linha.status
+    )::text);
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notificar_evento_chamado
+AFTER INSERT OR UPDATE OR DELETE ON chamado_chamados
+FOR EACH ROW
+EXECUTE FUNCTION notificar_evento_chamado();
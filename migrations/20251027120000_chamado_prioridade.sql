@@ -0,0 +1,14 @@
+-- Impacto e urgência informados pelo solicitante (ajustáveis por quem atende);
+-- a prioridade vem da matriz em chamado::prioridade e é gravada para ordenar listas e filas
+ALTER TABLE chamado_chamados
+    ADD COLUMN IF NOT EXISTS impacto INTEGER NOT NULL DEFAULT 1, -- 0 baixo, 1 médio, 2 alto
+    ADD COLUMN IF NOT EXISTS urgencia INTEGER NOT NULL DEFAULT 1, -- 0 baixa, 1 média, 2 alta
+    ADD COLUMN IF NOT EXISTS prioridade INTEGER NOT NULL DEFAULT 1; -- 0 baixa, 1 média, 2 alta, 3 crítica
+
+ALTER TABLE chamado_chamados
+    ADD CONSTRAINT ck_chamado_impacto CHECK (impacto BETWEEN 0 AND 2),
+    ADD CONSTRAINT ck_chamado_urgencia CHECK (urgencia BETWEEN 0 AND 2),
+    ADD CONSTRAINT ck_chamado_prioridade CHECK (prioridade BETWEEN 0 AND 3);
+
+CREATE INDEX IF NOT EXISTS idx_chamado_chamados_prioridade
+    ON chamado_chamados (prioridade DESC, created_at);
@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use axum::{
+    Extension, Form, Router,
+    extract::{Path, Query, State, rejection::FormRejection},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+    routing::{delete, get, post},
+};
+use minijinja::context;
+use serde::{Serialize, de::DeserializeOwned};
+use sqlx::{Decode, Encode, FromRow, PgPool, Postgres, Type, postgres::PgRow};
+use tracing::debug;
+use validator::{Validate, ValidationErrors};
+
+use crate::error::{AppError, erros_validacao, mensagem_erro};
+use crate::flash::{FlashStatus, flash_redirect};
+use crate::generic_list::{FieldKind, FieldOption, FormField, ListConfig};
+use crate::helpers;
+use crate::repository::{ListParams, Repository};
+use crate::state::SharedState;
+
+/// Repositório utilizável pelo admin: inputs validáveis e serializáveis (para reexibir o form)
+pub trait AdminRepository<T, ID>:
+    Repository<T, ID, CreateInput: Validate + Serialize, UpdateInput: Validate + Serialize>
+    + Send
+    + Sync
+    + 'static
+where
+    T: AdminItem,
+    ID: AdminId,
+{
+}
+
+impl<R, T, ID> AdminRepository<T, ID> for R
+where
+    R: Repository<T, ID> + Send + Sync + 'static,
+    R::CreateInput: Validate + Serialize,
+    R::UpdateInput: Validate + Serialize,
+    T: AdminItem,
+    ID: AdminId,
+{
+}
+
+pub trait AdminItem: for<'r> FromRow<'r, PgRow> + Send + Unpin + Serialize + 'static {}
+
+impl<T> AdminItem for T where T: for<'r> FromRow<'r, PgRow> + Send + Unpin + Serialize + 'static {}
+
+pub trait AdminId:
+    Type<Postgres>
+    + for<'q> Encode<'q, Postgres>
+    + for<'r> Decode<'r, Postgres>
+    + Serialize
+    + DeserializeOwned
+    + Send
+    + Sync
+    + Display
+    + 'static
+{
+}
+
+impl<ID> AdminId for ID where
+    ID: Type<Postgres>
+        + for<'q> Encode<'q, Postgres>
+        + for<'r> Decode<'r, Postgres>
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + Display
+        + 'static
+{
+}
+
+/*
+CRUD de administração gerado a partir de um repositório:
+
+    AdminResource::new(TipoChamadoRepository, list_config, form_fields).router()
+
+rotas (relativas ao router do módulo, com entity_name = "tipo"):
+    GET    /tipo                         listagem com busca, ordenação e paginação
+    GET    /tipo-form                    formulário vazio
+    POST   /tipo-form                    create (CreateInput)
+    GET    /tipo-form/{id}               formulário preenchido
+    POST   /tipo-form/{id}               update (UpdateInput)
+    DELETE /tipo/{id}                    delete do repositório
+e, se o repositório tem `soft_delete_column()`:
+    GET    /tipo/lixeira, POST /tipo/{id}/restaurar, POST /tipo/{id}/excluir-definitivo
+
+CreateInput/UpdateInput passam por `Validate`; os erros voltam para o formulário
+com os valores enviados. Templates: shared/admin_list.html e shared/admin_form.html
+*/
+pub struct AdminResource<R, T, ID> {
+    pub repo: R,
+    pub list: ListConfig,
+    pub form: Vec<FormField>,
+    _marker: PhantomData<fn() -> (T, ID)>,
+}
+
+impl<R, T, ID> AdminResource<R, T, ID>
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    pub fn new(repo: R, list: ListConfig, form: Vec<FormField>) -> Self {
+        Self {
+            repo,
+            list,
+            form,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn router(self) -> Router<SharedState> {
+        let (leitura, escrita) = self.routers();
+        leitura.merge(escrita)
+    }
+
+    /*
+    (leitura, escrita) separados para proteger só a escrita, ex.:
+        let (leitura, escrita) = resource.routers();
+        leitura.merge(escrita.layer(middleware::from_fn(require_roles(vec!["..."]))))
+    leitura: listagem e lixeira; escrita: formulários, create/update/delete, restaurar e purge
+    */
+    pub fn routers(self) -> (Router<SharedState>, Router<SharedState>) {
+        let lixeira = self.repo.soft_delete_column().is_some();
+        let base = format!("/{}", self.list.entity_name);
+        let form = format!("/{}-form", self.list.entity_name);
+
+        let mut leitura = Router::new().route(&base, get(lista::<R, T, ID>));
+        let mut escrita = Router::new()
+            .route(&form, get(formulario::<R, T, ID>).post(criar::<R, T, ID>))
+            .route(
+                &format!("{}/{{id}}", form),
+                get(editar::<R, T, ID>).post(atualizar::<R, T, ID>),
+            )
+            .route(&format!("{}/{{id}}", base), delete(excluir::<R, T, ID>));
+
+        if lixeira {
+            leitura = leitura.route(&format!("{}/lixeira", base), get(lixeira_lista::<R, T, ID>));
+            escrita = escrita
+                .route(
+                    &format!("{}/{{id}}/restaurar", base),
+                    post(restaurar::<R, T, ID>),
+                )
+                .route(
+                    &format!("{}/{{id}}/excluir-definitivo", base),
+                    post(excluir_definitivo::<R, T, ID>),
+                );
+        }
+
+        let resource = Extension(Arc::new(self));
+        (leitura.layer(resource.clone()), escrita.layer(resource))
+    }
+
+    fn form_url(&self) -> String {
+        format!("{}-form", self.list.base_url)
+    }
+
+    /// Tipo de cada campo do formulário, usado pela listagem para formatar valores
+    fn kinds(&self) -> HashMap<&str, FieldKind> {
+        self.form
+            .iter()
+            .map(|f| (f.name.as_str(), f.kind))
+            .collect()
+    }
+
+    /// Opções fixas dos selects, para a listagem mostrar o rótulo no lugar do valor
+    fn options(&self) -> HashMap<&str, &[FieldOption]> {
+        self.form
+            .iter()
+            .filter(|f| f.kind == FieldKind::Select && f.options_query.is_none())
+            .map(|f| (f.name.as_str(), f.options.as_slice()))
+            .collect()
+    }
+
+    /// Campos do formulário com as opções dos selects carregadas do banco
+    async fn campos(&self, pool: &PgPool) -> Result<Vec<FormField>, AppError> {
+        let mut campos = self.form.clone();
+        for campo in campos.iter_mut() {
+            if let Some(query) = campo.options_query {
+                campo.options = sqlx::query_as::<_, (i64, String)>(query)
+                    .fetch_all(pool)
+                    .await?
+                    .into_iter()
+                    .map(|(value, label)| FieldOption { value, label })
+                    .collect();
+            }
+        }
+        Ok(campos)
+    }
+
+    fn sortable(&self) -> Vec<&str> {
+        self.repo
+            .sortable_columns()
+            .iter()
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    fn redirect(&self, url: &str, message: &str, status: FlashStatus) -> Response {
+        flash_redirect(url, message, status)
+    }
+
+    /// Formulário com os valores enviados e os erros de validação por campo
+    async fn form_invalido<V: Serialize>(
+        &self,
+        state: &SharedState,
+        action: String,
+        valores: &V,
+        errors: &ValidationErrors,
+    ) -> Result<Response, AppError> {
+        let ctx = context! {
+            config => &self.list,
+            campos => self.campos(&state.db).await?,
+            action => action,
+            row => valores,
+            erros => erros_validacao(errors),
+            flash_message => "Verifique os campos destacados",
+            flash_status => "error",
+        };
+        let html = helpers::render(&state.templates, "shared/admin_form.html", ctx)?;
+        Ok((StatusCode::UNPROCESSABLE_ENTITY, html).into_response())
+    }
+}
+
+type Resource<R, T, ID> = Extension<Arc<AdminResource<R, T, ID>>>;
+
+async fn lista<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+    Query(params): Query<ListParams>,
+) -> Result<Html<String>, AppError>
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    let mut list_query = params.list_query();
+    if params.page_size.is_none() {
+        list_query.page_size = r.list.items_per_page as i32;
+    }
+
+    let paginated_response = r.repo.list(&state.db, &list_query).await?;
+    let ctx = context! {
+        config => &r.list,
+        kinds => r.kinds(),
+        opcoes => r.options(),
+        sortable => r.sortable(),
+        lixeira => r.repo.soft_delete_column().is_some(),
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.clone().unwrap_or_default(),
+        ordem => context! {
+            sort => list_query.sort,
+            dir => list_query.dir,
+            query => list_query.base_query_string(),
+        },
+        sort_query => list_query.sort_query_string(),
+    };
+    helpers::render(&state.templates, "shared/admin_list.html", ctx)
+}
+
+async fn formulario<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+) -> Result<Html<String>, AppError>
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    let ctx = context! {
+        config => &r.list,
+        campos => r.campos(&state.db).await?,
+        action => r.form_url(),
+    };
+    helpers::render(&state.templates, "shared/admin_form.html", ctx)
+}
+
+async fn criar<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+    form: Result<Form<R::CreateInput>, FormRejection>,
+) -> Result<Response, AppError>
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    let input = match form {
+        Ok(Form(input)) => input,
+        Err(rejection) => {
+            debug!("Formulário inválido: {}", rejection);
+            return Ok(r.redirect(
+                &r.form_url(),
+                &format!("Dados inválidos: {}", rejection.body_text()),
+                FlashStatus::Error,
+            ));
+        }
+    };
+
+    if let Err(errors) = input.validate() {
+        return r.form_invalido(&state, r.form_url(), &input, &errors).await;
+    }
+
+    Ok(match r.repo.create(&*state.db, input).await {
+        Ok(_) => r.redirect(
+            &r.list.base_url,
+            &format!("{} criado com sucesso!", r.list.entity_label),
+            FlashStatus::Success,
+        ),
+        Err(err) => r.redirect(
+            &r.form_url(),
+            &format!(
+                "Erro ao criar {}: {}",
+                r.list.entity_label,
+                mensagem_erro(err)
+            ),
+            FlashStatus::Error,
+        ),
+    })
+}
+
+async fn editar<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+    Path(id): Path<ID>,
+) -> Result<Html<String>, AppError>
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    let action = format!("{}/{}", r.form_url(), id);
+    let row = r.repo.get_by_id(&*state.db, id).await?;
+    let ctx = context! {
+        config => &r.list,
+        campos => r.campos(&state.db).await?,
+        action => action,
+        row => row,
+    };
+    helpers::render(&state.templates, "shared/admin_form.html", ctx)
+}
+
+async fn atualizar<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+    Path(id): Path<ID>,
+    form: Result<Form<R::UpdateInput>, FormRejection>,
+) -> Result<Response, AppError>
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    let action = format!("{}/{}", r.form_url(), id);
+    let input = match form {
+        Ok(Form(input)) => input,
+        Err(rejection) => {
+            debug!("Formulário inválido: {}", rejection);
+            return Ok(r.redirect(
+                &action,
+                &format!("Dados inválidos: {}", rejection.body_text()),
+                FlashStatus::Error,
+            ));
+        }
+    };
+
+    if let Err(errors) = input.validate() {
+        return r.form_invalido(&state, action, &input, &errors).await;
+    }
+
+    Ok(match r.repo.update(&*state.db, id, input).await {
+        Ok(_) => r.redirect(
+            &r.list.base_url,
+            &format!("{} atualizado com sucesso!", r.list.entity_label),
+            FlashStatus::Success,
+        ),
+        Err(err) => r.redirect(
+            &action,
+            &format!(
+                "Erro ao atualizar {}: {}",
+                r.list.entity_label,
+                mensagem_erro(err)
+            ),
+            FlashStatus::Error,
+        ),
+    })
+}
+
+async fn excluir<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+    Path(id): Path<ID>,
+) -> Response
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    match r.repo.delete(&*state.db, id).await {
+        Ok(()) => r.redirect(
+            &r.list.base_url,
+            &format!("{} excluído com sucesso!", r.list.entity_label),
+            FlashStatus::Success,
+        ),
+        Err(err) => r.redirect(
+            &r.list.base_url,
+            &format!(
+                "Erro ao excluir {}: {}",
+                r.list.entity_label,
+                mensagem_erro(err)
+            ),
+            FlashStatus::Error,
+        ),
+    }
+}
+
+async fn lixeira_lista<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+    Query(params): Query<ListParams>,
+) -> Result<Html<String>, AppError>
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    let list_query = params.list_query().trash();
+    let paginated_response = r.repo.list(&state.db, &list_query).await?;
+    let ctx = context! {
+        titulo => &r.list.plural_label,
+        base_url => &r.list.base_url,
+        rows => paginated_response.data,
+        current_page => paginated_response.page,
+        total_pages => paginated_response.total_pages,
+        page_size => paginated_response.page_size,
+        total_records => paginated_response.total_records,
+        find => params.find.clone().unwrap_or_default(),
+        ordem => context! {
+            sort => list_query.sort,
+            dir => list_query.dir,
+            query => list_query.base_query_string(),
+        },
+        sort_query => list_query.sort_query_string(),
+    };
+    helpers::render(&state.templates, "shared/lixeira.html", ctx)
+}
+
+async fn restaurar<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+    Path(id): Path<ID>,
+) -> Response
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    let lixeira = format!("{}/lixeira", r.list.base_url);
+    match r.repo.restore(&*state.db, id).await {
+        Ok(()) => r.redirect(
+            &lixeira,
+            &format!("{} restaurado com sucesso!", r.list.entity_label),
+            FlashStatus::Success,
+        ),
+        Err(err) => r.redirect(
+            &lixeira,
+            &format!(
+                "Erro ao restaurar {}: {}",
+                r.list.entity_label,
+                mensagem_erro(err)
+            ),
+            FlashStatus::Error,
+        ),
+    }
+}
+
+async fn excluir_definitivo<R, T, ID>(
+    Extension(r): Resource<R, T, ID>,
+    State(state): State<SharedState>,
+    Path(id): Path<ID>,
+) -> Response
+where
+    R: AdminRepository<T, ID>,
+    T: AdminItem,
+    ID: AdminId,
+{
+    let lixeira = format!("{}/lixeira", r.list.base_url);
+    match r.repo.purge(&*state.db, id).await {
+        Ok(()) => r.redirect(
+            &lixeira,
+            &format!("{} excluído definitivamente!", r.list.entity_label),
+            FlashStatus::Success,
+        ),
+        Err(err) => r.redirect(
+            &lixeira,
+            &format!(
+                "Erro ao excluir {}: {}",
+                r.list.entity_label,
+                mensagem_erro(err)
+            ),
+            FlashStatus::Error,
+        ),
+    }
+}
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use sqlx::{Acquire, PgConnection, Postgres, Transaction};
+
+/*
+executor aceito pelos repositórios: o pool ou uma transação em andamento
+  repo.create(pool, input)                 -> &PgPool
+  repo.create(uow.conn(), input)           -> &mut PgConnection
+  repo.create(&mut *tx, input)             -> &mut PgConnection de uma Transaction
+
+atenção: um `async fn` comum (sem #[async_trait]) genérico sobre `Db`, chamado com
+`&mut PgConnection`, deixa o future do handler sem `Send` ("Acquire is not general
+enough"); helpers usados dentro de uma UnitOfWork recebem `&mut PgConnection` direto
+*/
+pub trait Db<'c>: Acquire<'c, Database = Postgres> + Send + 'c {}
+
+impl<'c, A> Db<'c> for A where A: Acquire<'c, Database = Postgres> + Send + 'c {}
+
+/*
+unidade de trabalho: uma transação compartilhada por vários repositórios
+
+  let mut uow = UnitOfWork::begin(pool).await?;
+  let contato = contato_repo.create(uow.conn(), input).await?;
+  doc_repo.create(uow.conn(), doc).await?;
+  uow.commit().await?;
+
+sem commit (ex.: retorno antecipado com `?`) a transação sofre rollback no drop
+aberta sobre uma transação existente vira um SAVEPOINT, então serviços que usam
+UnitOfWork podem ser chamados dentro de outra unidade de trabalho
+*/
+pub struct UnitOfWork<'c> {
+    tx: Transaction<'c, Postgres>,
+}
+
+impl<'c> UnitOfWork<'c> {
+    pub async fn begin<A: Db<'c>>(db: A) -> Result<Self> {
+        Ok(Self {
+            tx: db.begin().await?,
+        })
+    }
+
+    /// Conexão da transação, para passar aos repositórios
+    pub fn conn(&mut self) -> &mut PgConnection {
+        &mut self.tx
+    }
+
+    pub async fn commit(self) -> Result<()> {
+        self.tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn rollback(self) -> Result<()> {
+        self.tx.rollback().await?;
+        Ok(())
+    }
+}
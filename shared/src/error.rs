@@ -1,128 +1,431 @@
 use axum::{
     Json,
-    body::Body,
-    http::StatusCode,
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header::ACCEPT},
+    middleware::Next,
     response::{Html, IntoResponse, Response},
 };
+use minijinja::context;
 use serde::Serialize;
+use sqlx::error::ErrorKind;
+use std::collections::HashMap;
 use std::fmt;
+use tracing::{debug, error};
+use utoipa::ToSchema;
+use validator::ValidationErrors;
 
+use crate::state::SharedState;
+
+/*
+erro único dos handlers: `?` converte anyhow, sqlx, minijinja, sessão e validação
+a mensagem (Display) é sempre segura para o usuário; o detalhe de `Internal` só vai para o log
+a resposta é JSON ({"error": ...}, mais "campos" nos erros de validação); o middleware `negociar_erro` troca por 404.html,
+403.html ou 500.html quando o cliente é um navegador
+*/
 #[derive(Debug)]
 pub enum AppError {
+    /// 400 - requisição malformada
     InvalidInput(String),
+    /// 404 - registro ou página inexistente
     NotFound,
-    InternalServerError,
-    SessionError(String),
-    InvalidSecret,
-    VerificationFailed,
-    PermissionDenied,
+    /// 409 - registro duplicado ou alterado por outro usuário
+    Conflict(String),
+    /// 422 - dados recusados pela validação ou pelas constraints do banco
+    Unprocessable(String),
+    /// 422 - campos recusados pelo `validator`, detalhados em `campos` no JSON
+    Validation(ValidationErrors),
+    /// 401
     UserNotAuthenticated,
+    /// 403
+    PermissionDenied,
+    /// 500 - falha inesperada
+    Internal(anyhow::Error),
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Unprocessable(_) | AppError::Validation(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            AppError::UserNotAuthenticated => StatusCode::UNAUTHORIZED,
+            AppError::PermissionDenied => StatusCode::FORBIDDEN,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Registra o erro no log; falhas internas com o detalhe completo
+    pub fn registrar(&self) {
+        match self {
+            AppError::Internal(err) => error!("Erro interno: {:?}", err),
+            outro => debug!("{}: {}", outro.status(), outro),
+        }
+    }
 }
 
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            AppError::InvalidInput(msg) => write!(f, "Input inválido: {}", msg),
-            AppError::NotFound => write!(f, "Não encontrado"),
-            AppError::InternalServerError => write!(f, "Erro interno do servidor"),
-            AppError::SessionError(msg) => write!(f, "Erro de sessão: {}", msg),
-            AppError::InvalidSecret => write!(f, "Invalid base32 secret"),
-            AppError::VerificationFailed => write!(f, "OTP verification failed"),
-            AppError::PermissionDenied => write!(f, "You are not allowed to perform this action"),
-            AppError::UserNotAuthenticated => write!(f, "Authentication required. Please log in."),
+            AppError::InvalidInput(msg) => write!(f, "{}", msg),
+            AppError::NotFound => write!(f, "registro não encontrado"),
+            AppError::Conflict(msg) => write!(f, "{}", msg),
+            AppError::Unprocessable(msg) => write!(f, "{}", msg),
+            AppError::Validation(err) => write!(f, "{}", err),
+            AppError::UserNotAuthenticated => write!(f, "autenticação necessária"),
+            AppError::PermissionDenied => write!(f, "sem permissão para esta ação"),
+            AppError::Internal(_) => write!(f, "erro interno do servidor"),
         }
     }
 }
 
 impl std::error::Error for AppError {}
 
-#[derive(Serialize)]
-struct ErrorResponse {
-    error: String,
+/// Mensagem amigável para o flash; o detalhe interno fica no log
+pub fn mensagem_erro(err: impl Into<AppError>) -> String {
+    let erro = err.into();
+    erro.registrar();
+    erro.to_string()
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        match self {
-            AppError::InvalidInput(msg) => {
-                let body = Json(ErrorResponse { error: msg });
-                (StatusCode::BAD_REQUEST, body).into_response()
-            }
-            AppError::NotFound => {
-                let body = Json(ErrorResponse {
-                    error: "Not found".to_string(),
-                });
-                (StatusCode::NOT_FOUND, body).into_response()
-            }
-            AppError::InternalServerError => server_error("Internal server error".to_string()).1,
-            AppError::SessionError(msg) => server_error(format!("Session error: {}", msg)).1,
-            AppError::InvalidSecret => {
-                let body = Json(ErrorResponse {
-                    error: "".to_string(),
-                });
-                (StatusCode::NOT_FOUND, body).into_response()
-            }
-            AppError::VerificationFailed => {
-                let body = Json(ErrorResponse {
-                    error: "".to_string(),
-                });
-                (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        // violação de FK no insert/update aponta para registro inexistente; no delete, registro em uso
+        let (kind, inserindo) = match &err {
+            sqlx::Error::RowNotFound => return AppError::NotFound,
+            sqlx::Error::Database(db) => (db.kind(), db.message().starts_with("insert or update")),
+            _ => return AppError::Internal(err.into()),
+        };
+
+        match kind {
+            ErrorKind::UniqueViolation => AppError::Conflict("registro já existe".to_string()),
+            ErrorKind::ForeignKeyViolation if inserindo => {
+                AppError::Unprocessable("registro relacionado não encontrado".to_string())
             }
-            AppError::PermissionDenied => {
-                let body = Json(ErrorResponse {
-                    error: "You are not allowed to perform this action".to_string(),
-                });
-                (StatusCode::FORBIDDEN, body).into_response()
+            ErrorKind::ForeignKeyViolation => {
+                AppError::Unprocessable("registro em uso por outros cadastros".to_string())
             }
-            AppError::UserNotAuthenticated => {
-                let body = Json(ErrorResponse {
-                    error: "Authentication required. Please log in.".to_string(),
-                });
-                (StatusCode::UNAUTHORIZED, body).into_response()
+            ErrorKind::NotNullViolation | ErrorKind::CheckViolation => {
+                AppError::Unprocessable("dados inválidos ou incompletos".to_string())
             }
+            _ => AppError::Internal(err.into()),
+        }
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<AppError>() {
+            Ok(erro) => return erro,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<sqlx::Error>() {
+            Ok(erro) => return erro.into(),
+            Err(err) => err,
+        };
+        match err.downcast::<ConflictError>() {
+            Ok(conflito) => conflito.into(),
+            Err(err) => AppError::Internal(err),
         }
     }
 }
 
-// Implementação para converter erros de tower_sessions para AppError
+impl From<ConflictError> for AppError {
+    fn from(err: ConflictError) -> Self {
+        AppError::Conflict(err.to_string())
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(err: ValidationErrors) -> Self {
+        AppError::Validation(err)
+    }
+}
+
+impl From<minijinja::Error> for AppError {
+    fn from(err: minijinja::Error) -> Self {
+        AppError::Internal(err.into())
+    }
+}
+
 impl From<tower_sessions::session::Error> for AppError {
     fn from(err: tower_sessions::session::Error) -> Self {
-        AppError::SessionError(err.to_string())
-    }
-}
-
-fn server_error(e: String) -> (StatusCode, Response<Body>) {
-    eprintln!("Server error: {}", e);
-
-    let html_string = format!(
-        r#"<!DOCTYPE html>
-<html>
-<head>
-    <title>Erro Interno do Servidor</title>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <style>
-        body {{ font-family: Arial, sans-serif; text-align: center; padding: 50px; }}
-        .error-container {{ max-width: 600px; margin: 0 auto; }}
-        h1 {{ color: #d32f2f; }}
-        p {{ color: #666; }}
-        .error-code {{ font-size: 4em; font-weight: bold; color: #d32f2f; margin: 20px 0; }}
-    </style>
-</head>
-<body>
-    <div class="error-container">
-        <div class="error-code">500</div>
-        <h1>Erro Interno do Servidor</h1>
-        <p>Ocorreu um erro interno no servidor. Por favor, tente novamente mais tarde.</p>
-        <p>Se o problema persistir, entre em contato com o administrador do sistema.</p>
-        <a href="/">Voltar à página inicial</a>
-    </div>
-</body>
-</html>"#
-    );
-
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Html(html_string).into_response(),
-    )
+        AppError::Internal(err.into())
+    }
+}
+
+/// Corpo JSON das respostas de erro
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    /// campo -> mensagens, somente nos erros de validação (422)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub campos: Option<HashMap<String, Vec<String>>>,
+}
+
+/// campo -> mensagens de cada regra do `validator` que falhou
+pub fn erros_validacao(errors: &ValidationErrors) -> HashMap<String, Vec<String>> {
+    errors
+        .field_errors()
+        .into_iter()
+        .map(|(campo, erros)| {
+            let mensagens = erros
+                .iter()
+                .map(|e| match &e.message {
+                    Some(message) => message.to_string(),
+                    None => e.code.to_string(),
+                })
+                .collect();
+            (campo.to_string(), mensagens)
+        })
+        .collect()
+}
+
+/// marca a resposta como erro renderizável em HTML por `negociar_erro`
+#[derive(Clone)]
+struct PaginaErro {
+    mensagem: String,
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        self.registrar();
+        let mensagem = self.to_string();
+        let campos = match &self {
+            AppError::Validation(err) => Some(erros_validacao(err)),
+            _ => None,
+        };
+        let mut res = (
+            self.status(),
+            Json(ErrorResponse {
+                error: mensagem.clone(),
+                campos,
+            }),
+        )
+            .into_response();
+        res.extensions_mut().insert(PaginaErro { mensagem });
+        res
+    }
+}
+
+/// navegadores pedem text/html; fetch e clientes de API recebem JSON
+fn aceita_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/html"))
+}
+
+fn titulo(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "Requisição inválida",
+        StatusCode::NOT_FOUND => "Não encontrado",
+        StatusCode::CONFLICT => "Conflito",
+        StatusCode::UNPROCESSABLE_ENTITY => "Dados inválidos",
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => "Acesso negado",
+        _ => "Erro Interno",
+    }
+}
+
+/// Middleware: respostas de `AppError` viram página HTML para navegadores
+pub async fn negociar_erro(State(state): State<SharedState>, req: Request, next: Next) -> Response {
+    let html = aceita_html(req.headers());
+    let res = next.run(req).await;
+
+    let Some(erro) = res.extensions().get::<PaginaErro>().filter(|_| html) else {
+        return res;
+    };
+
+    let status = res.status();
+    let template = match status {
+        StatusCode::NOT_FOUND => "404.html",
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => "403.html",
+        _ => "500.html",
+    };
+    let ctx = context! {
+        status => status.as_u16(),
+        titulo => titulo(status),
+        mensagem => erro.mensagem,
+    };
+
+    match state
+        .templates
+        .get_template(template)
+        .and_then(|t| t.render(ctx))
+    {
+        Ok(pagina) => (status, Html(pagina)).into_response(),
+        Err(err) => {
+            error!("Erro ao renderizar {}: {}", template, err);
+            res
+        }
+    }
+}
+
+/*
+controle de concorrência otimista: o update não encontrou a versão (updated_at)
+que o formulário leu, ou seja, outro usuário alterou o registro nesse meio tempo
+`atual` é o registro como está no banco, serializado
+*/
+#[derive(Debug)]
+pub struct ConflictError {
+    pub tabela: String,
+    pub atual: serde_json::Value,
+}
+
+/// Campo cujo valor enviado difere do valor atual no banco
+#[derive(Debug, Serialize, PartialEq)]
+pub struct CampoAlterado {
+    pub campo: String,
+    pub enviado: String,
+    pub atual: String,
+}
+
+/// campos de controle que não entram na comparação
+const CAMPOS_IGNORADOS: &[&str] = &[
+    "id",
+    "created_at",
+    "updated_at",
+    "createdAt",
+    "updatedAt",
+    "updated_by",
+];
+
+impl ConflictError {
+    pub fn new<T: Serialize>(tabela: &str, atual: &T) -> Self {
+        Self {
+            tabela: tabela.to_string(),
+            atual: serde_json::to_value(atual).unwrap_or_default(),
+        }
+    }
+
+    /// Valor atual de um campo, ex.: `updated_by`
+    pub fn campo(&self, nome: &str) -> Option<&serde_json::Value> {
+        self.atual.get(nome).filter(|v| !v.is_null())
+    }
+
+    /// Compara os dados enviados pelo formulário com o registro atual
+    pub fn diff<E: Serialize>(&self, enviado: &E) -> Vec<CampoAlterado> {
+        let enviado = serde_json::to_value(enviado).unwrap_or_default();
+        let Some(campos) = enviado.as_object() else {
+            return Vec::new();
+        };
+
+        campos
+            .iter()
+            .filter(|(campo, _)| !CAMPOS_IGNORADOS.contains(&campo.as_str()))
+            .filter_map(|(campo, valor)| {
+                let atual = self.atual.get(campo)?;
+                let (enviado, atual) = (texto(valor), texto(atual));
+                (enviado != atual).then(|| CampoAlterado {
+                    campo: campo.clone(),
+                    enviado,
+                    atual,
+                })
+            })
+            .collect()
+    }
+}
+
+fn texto(valor: &serde_json::Value) -> String {
+    match valor {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        outro => outro.to_string(),
+    }
+}
+
+impl fmt::Display for ConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "O registro de {} foi alterado por outro usuário",
+            self.tabela
+        )
+    }
+}
+
+impl std::error::Error for ConflictError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppError, CampoAlterado, ConflictError, aceita_html, erros_validacao};
+    use axum::http::{HeaderMap, HeaderValue, StatusCode, header::ACCEPT};
+    use serde_json::json;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Entrada {
+        #[validate(length(min = 1, message = "Informe o nome"))]
+        nome: String,
+    }
+
+    #[test]
+    fn test_erros_validacao() {
+        let errors = Entrada {
+            nome: String::new(),
+        }
+        .validate()
+        .unwrap_err();
+        let erros = erros_validacao(&errors);
+        assert_eq!(erros["nome"], vec!["Informe o nome".to_string()]);
+
+        let erro = AppError::from(errors);
+        assert_eq!(erro.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[test]
+    fn test_status_app_error() {
+        let nao_encontrado = AppError::from(anyhow::Error::from(sqlx::Error::RowNotFound));
+        assert_eq!(nao_encontrado.status(), StatusCode::NOT_FOUND);
+
+        let conflito = AppError::from(anyhow::Error::from(ConflictError::new(
+            "contato",
+            &json!({}),
+        )));
+        assert_eq!(conflito.status(), StatusCode::CONFLICT);
+
+        // detalhe interno não aparece na mensagem
+        let interno = AppError::from(anyhow::anyhow!("relation \"x\" does not exist"));
+        assert_eq!(interno.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(interno.to_string(), "erro interno do servidor");
+    }
+
+    #[test]
+    fn test_aceita_html() {
+        let mut headers = HeaderMap::new();
+        assert!(!aceita_html(&headers));
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("text/html,application/xhtml+xml"),
+        );
+        assert!(aceita_html(&headers));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        assert!(!aceita_html(&headers));
+    }
+
+    #[test]
+    fn test_diff_conflito() {
+        let conflito = ConflictError::new(
+            "contato",
+            &json!({"id": 1, "nome": "Maria", "email": "m@x.com", "updated_at": "2025-10-18"}),
+        );
+        let diff = conflito.diff(&json!({
+            "id": 1,
+            "nome": "Maria Souza",
+            "email": "m@x.com",
+            "updated_at": "2025-10-17",
+            "status": 2
+        }));
+
+        assert_eq!(
+            diff,
+            vec![CampoAlterado {
+                campo: "nome".to_string(),
+                enviado: "Maria Souza".to_string(),
+                atual: "Maria".to_string(),
+            }]
+        );
+    }
 }
@@ -0,0 +1,549 @@
+use std::future::Future;
+
+use axum::{
+    body::{Body, Bytes},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use futures_util::{StreamExt, stream};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::error;
+
+use crate::error::AppError;
+use crate::repository::{ListQuery, PaginatedResponse};
+use crate::xlsx::{Celula, Estilo, XlsxWriter};
+
+/*
+exportação CSV/XLSX de qualquer listagem paginada
+o handler monta o mesmo ListQuery da tela (busca, filtros, ordenação) e uma função que
+busca uma página; `exportar` percorre todas as páginas pelo cursor e envia o arquivo
+em streaming, sem limite de linhas
+as colunas são declaradas no `ExportConfig`; a query pode escolher quais e em que ordem:
+  /externo/contato/exportar?formato=xlsx&colunas=protocolo,nome,val_solicitado&linha_id=8
+*/
+
+/// Registros buscados por vez
+const EXPORT_PAGE_SIZE: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+
+    fn extensao(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Formatação da coluna, igual aos filtros `currency` e `format_datetime` dos templates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnKind {
+    Text,
+    Int,
+    /// 1.234,56
+    Decimal,
+    /// R$ 1.234,56
+    Money,
+    /// 07/08/2025
+    Date,
+    /// 07/08/2025 00:37 (UTC)
+    DateTime,
+    /// Sim/Não
+    Bool,
+}
+
+/// Coluna exportável; `name` é o campo do registro serializado
+#[derive(Clone)]
+pub struct ExportColumn {
+    pub name: String,
+    pub label: String,
+    pub kind: ColumnKind,
+    /// converte o valor em texto (ex.: código do status -> rótulo)
+    pub map: Option<fn(&Value) -> String>,
+}
+
+#[derive(Clone)]
+pub struct ExportConfig {
+    /// prefixo do nome do arquivo ("contatos" -> contatos-20250807-0037.csv)
+    pub file_name: String,
+    pub columns: Vec<ExportColumn>,
+}
+
+impl ExportConfig {
+    pub fn new(file_name: &str) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            columns: Vec::new(),
+        }
+    }
+
+    pub fn column(mut self, name: &str, label: &str, kind: ColumnKind) -> Self {
+        self.columns.push(ExportColumn {
+            name: name.to_string(),
+            label: label.to_string(),
+            kind,
+            map: None,
+        });
+        self
+    }
+
+    /// Coluna de texto calculada a partir do valor do campo
+    pub fn column_map(mut self, name: &str, label: &str, map: fn(&Value) -> String) -> Self {
+        self.columns.push(ExportColumn {
+            name: name.to_string(),
+            label: label.to_string(),
+            kind: ColumnKind::Text,
+            map: Some(map),
+        });
+        self
+    }
+
+    /// (name, label) para o seletor de colunas dos templates (parts/exportar.html)
+    pub fn options(&self) -> Vec<(String, String)> {
+        self.columns
+            .iter()
+            .map(|c| (c.name.clone(), c.label.clone()))
+            .collect()
+    }
+
+    /// Colunas pedidas, na ordem pedida; nenhuma = todas
+    fn select(&self, nomes: &[String]) -> Result<Vec<ExportColumn>, AppError> {
+        if nomes.is_empty() {
+            return Ok(self.columns.clone());
+        }
+        nomes
+            .iter()
+            .map(|nome| {
+                self.columns
+                    .iter()
+                    .find(|c| &c.name == nome)
+                    .cloned()
+                    .ok_or_else(|| AppError::InvalidInput(format!("coluna inválida: {}", nome)))
+            })
+            .collect()
+    }
+}
+
+/*
+parâmetros da exportação, lidos de `Query<Vec<(String, String)>>` porque o seletor
+de colunas envia `colunas` repetido; também aceita "colunas=a,b"
+os demais parâmetros (find, sort, filtros) ficam para o ListParams/Filters do handler
+*/
+#[derive(Debug, Default)]
+pub struct ExportParams {
+    pub format: ExportFormat,
+    pub columns: Vec<String>,
+}
+
+impl ExportParams {
+    pub fn parse(query: &[(String, String)]) -> Result<Self, AppError> {
+        let mut params = ExportParams::default();
+        for (chave, valor) in query {
+            match chave.as_str() {
+                "formato" => {
+                    params.format = match valor.as_str() {
+                        "csv" | "" => ExportFormat::Csv,
+                        "xlsx" => ExportFormat::Xlsx,
+                        outro => {
+                            return Err(AppError::InvalidInput(format!(
+                                "formato de exportação inválido: {}",
+                                outro
+                            )));
+                        }
+                    }
+                }
+                "colunas" => params.columns.extend(
+                    valor
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|c| !c.is_empty())
+                        .map(str::to_string),
+                ),
+                _ => {}
+            }
+        }
+        Ok(params)
+    }
+}
+
+/// Saída em construção: CSV é texto direto, XLSX passa pelo zip
+enum Escritor {
+    Csv,
+    Xlsx(Box<XlsxWriter>),
+}
+
+impl Escritor {
+    fn iniciar(&mut self, colunas: &[ExportColumn]) -> std::io::Result<Vec<u8>> {
+        match self {
+            // BOM para o Excel reconhecer UTF-8
+            Escritor::Csv => {
+                let mut saida = "\u{feff}".as_bytes().to_vec();
+                saida.extend(linha_csv(colunas.iter().map(|c| c.label.clone())));
+                Ok(saida)
+            }
+            Escritor::Xlsx(xlsx) => xlsx.iniciar(),
+        }
+    }
+
+    fn linha(&mut self, colunas: &[ExportColumn], registro: &Value) -> std::io::Result<Vec<u8>> {
+        match self {
+            Escritor::Csv => Ok(linha_csv(
+                colunas
+                    .iter()
+                    .map(|c| texto_celula(c, campo(registro, &c.name))),
+            )),
+            Escritor::Xlsx(xlsx) => {
+                let celulas: Vec<Celula> = colunas
+                    .iter()
+                    .map(|c| celula_xlsx(c, campo(registro, &c.name)))
+                    .collect();
+                xlsx.linha(&celulas)
+            }
+        }
+    }
+
+    fn finalizar(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Escritor::Csv => Ok(Vec::new()),
+            Escritor::Xlsx(xlsx) => xlsx.finalizar(),
+        }
+    }
+}
+
+struct Exportacao<F, T> {
+    buscar: F,
+    query: ListQuery,
+    colunas: Vec<ExportColumn>,
+    escritor: Option<Escritor>,
+    pagina: Option<Vec<T>>,
+}
+
+/*
+gera a resposta de download
+`buscar` recebe o ListQuery já em modo cursor e devolve uma página; a primeira é buscada
+antes de responder, então filtro ou ordenação inválidos ainda viram AppError
+um erro no meio do streaming só pode ser registrado no log (o download é interrompido)
+*/
+pub async fn exportar<T, F, Fut>(
+    config: &ExportConfig,
+    params: &ExportParams,
+    query: ListQuery,
+    mut buscar: F,
+) -> Result<Response, AppError>
+where
+    T: Serialize + Send + 'static,
+    F: FnMut(ListQuery) -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<PaginatedResponse<T>>> + Send,
+{
+    let colunas = config.select(&params.columns)?;
+
+    let mut query = query.with_cursor(Some(""));
+    query.page_size = EXPORT_PAGE_SIZE;
+    let primeira = buscar(query.clone()).await?;
+    query.cursor = primeira.next_cursor;
+
+    let mut escritor = match params.format {
+        ExportFormat::Csv => Escritor::Csv,
+        ExportFormat::Xlsx => Escritor::Xlsx(Box::new(XlsxWriter::new(
+            colunas.iter().map(|c| c.label.clone()).collect(),
+        ))),
+    };
+    let inicio = escritor.iniciar(&colunas).map_err(anyhow::Error::from)?;
+
+    let exportacao = Exportacao {
+        buscar,
+        query,
+        colunas,
+        escritor: Some(escritor),
+        pagina: Some(primeira.data),
+    };
+
+    let corpo = stream::unfold(exportacao, |mut exp| async move {
+        exp.escritor.as_ref()?;
+
+        // página já buscada, ou a próxima pelo cursor
+        let registros = match exp.pagina.take() {
+            Some(registros) => registros,
+            None if exp.query.cursor.is_some() => match (exp.buscar)(exp.query.clone()).await {
+                Ok(pagina) => {
+                    exp.query.cursor = pagina.next_cursor;
+                    pagina.data
+                }
+                Err(err) => {
+                    error!("Erro na exportação: {:?}", err);
+                    exp.escritor = None;
+                    return Some((Err(std::io::Error::other(err)), exp));
+                }
+            },
+            None => {
+                let fim = exp.escritor.take()?.finalizar();
+                return Some((fim.map(Bytes::from), exp));
+            }
+        };
+
+        let escritor = exp.escritor.as_mut()?;
+        let mut bloco = Vec::new();
+        for registro in registros {
+            let linha = serde_json::to_value(&registro)
+                .map_err(std::io::Error::other)
+                .and_then(|registro| escritor.linha(&exp.colunas, &registro));
+            match linha {
+                Ok(linha) => bloco.extend(linha),
+                Err(err) => {
+                    error!("Erro na exportação: {:?}", err);
+                    exp.escritor = None;
+                    return Some((Err(err), exp));
+                }
+            }
+        }
+        Some((Ok(Bytes::from(bloco)), exp))
+    });
+
+    let corpo = stream::iter([Ok::<_, std::io::Error>(Bytes::from(inicio))]).chain(corpo);
+
+    let arquivo = format!(
+        "{}-{}.{}",
+        config.file_name,
+        Utc::now().format("%Y%m%d-%H%M"),
+        params.format.extensao()
+    );
+    Ok((
+        [
+            (CONTENT_TYPE, params.format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", arquivo),
+            ),
+        ],
+        Body::from_stream(corpo),
+    )
+        .into_response())
+}
+
+fn campo<'a>(registro: &'a Value, nome: &str) -> &'a Value {
+    registro.get(nome).unwrap_or(&Value::Null)
+}
+
+/// Linha CSV separada por ";" (padrão do Excel em pt-BR)
+fn linha_csv(valores: impl Iterator<Item = String>) -> Vec<u8> {
+    let mut linha = valores
+        .map(|v| escapar_csv(&v))
+        .collect::<Vec<_>>()
+        .join(";");
+    linha.push_str("\r\n");
+    linha.into_bytes()
+}
+
+/*
+aspas quando há separador, aspas ou quebra de linha
+texto começando com = + - @ ganha um apóstrofo para o Excel não executar como fórmula
+*/
+fn escapar_csv(valor: &str) -> String {
+    let valor = if valor.starts_with(['=', '+', '-', '@']) && valor.parse::<f64>().is_err() {
+        format!("'{}", valor)
+    } else {
+        valor.to_string()
+    };
+    if valor.contains([';', '"', '\n', '\r']) {
+        format!("\"{}\"", valor.replace('"', "\"\""))
+    } else {
+        valor
+    }
+}
+
+/// Valor formatado para o CSV
+fn texto_celula(coluna: &ExportColumn, valor: &Value) -> String {
+    if let Some(map) = coluna.map {
+        return map(valor);
+    }
+    if valor.is_null() {
+        return String::new();
+    }
+    let formatado = match coluna.kind {
+        ColumnKind::Text | ColumnKind::Int => None,
+        ColumnKind::Decimal => numero_texto(valor).and_then(|n| formatar_decimal_br(&n)),
+        ColumnKind::Money => numero_texto(valor)
+            .and_then(|n| formatar_decimal_br(&n))
+            .map(|n| format!("R$ {}", n)),
+        ColumnKind::Date => data_hora(valor).map(|d| d.format("%d/%m/%Y").to_string()),
+        ColumnKind::DateTime => data_hora(valor).map(|d| d.format("%d/%m/%Y %H:%M").to_string()),
+        ColumnKind::Bool => valor
+            .as_bool()
+            .map(|b| if b { "Sim" } else { "Não" }.to_string()),
+    };
+    formatado.unwrap_or_else(|| match valor {
+        Value::String(s) => s.clone(),
+        outro => outro.to_string(),
+    })
+}
+
+/// Célula tipada do XLSX: números e datas continuam somáveis/ordenáveis na planilha
+fn celula_xlsx(coluna: &ExportColumn, valor: &Value) -> Celula {
+    if coluna.map.is_none() && !valor.is_null() {
+        let numero = |estilo| {
+            numero_texto(valor)
+                .and_then(|n| n.parse::<f64>().ok())
+                .map(|n| Celula::Numero(n, estilo))
+        };
+        let celula = match coluna.kind {
+            ColumnKind::Int => numero(Estilo::Inteiro),
+            ColumnKind::Decimal => numero(Estilo::Decimal),
+            ColumnKind::Money => numero(Estilo::Moeda),
+            ColumnKind::Date => {
+                data_hora(valor).map(|d| Celula::Numero(serial_excel(d), Estilo::Data))
+            }
+            ColumnKind::DateTime => {
+                data_hora(valor).map(|d| Celula::Numero(serial_excel(d), Estilo::DataHora))
+            }
+            ColumnKind::Text | ColumnKind::Bool => None,
+        };
+        if let Some(celula) = celula {
+            return celula;
+        }
+    }
+    match texto_celula(coluna, valor) {
+        texto if texto.is_empty() => Celula::Vazia,
+        texto => Celula::Texto(texto),
+    }
+}
+
+/// BigDecimal chega serializado como string; inteiros e floats como número
+fn numero_texto(valor: &Value) -> Option<String> {
+    match valor {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) if s.parse::<f64>().is_ok() => Some(s.trim().to_string()),
+        _ => None,
+    }
+}
+
+/// "1234.5" -> "1.234,50", com arredondamento em 2 casas
+fn formatar_decimal_br(numero: &str) -> Option<String> {
+    let (negativo, numero) = match numero.strip_prefix('-') {
+        Some(resto) => (true, resto),
+        None => (false, numero),
+    };
+    let (inteiro, fracao) = numero.split_once('.').unwrap_or((numero, ""));
+    if inteiro.is_empty() || !inteiro.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if !fracao.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut centavos: u128 = format!("{}{:0<2}", inteiro, &fracao[..fracao.len().min(2)])
+        .parse()
+        .ok()?;
+    if fracao.as_bytes().get(2).is_some_and(|d| *d >= b'5') {
+        centavos += 1;
+    }
+
+    let inteiro = (centavos / 100).to_string();
+    let mut milhar = String::new();
+    for (i, c) in inteiro.chars().enumerate() {
+        if i > 0 && (inteiro.len() - i).is_multiple_of(3) {
+            milhar.push('.');
+        }
+        milhar.push(c);
+    }
+    let sinal = if negativo && centavos > 0 { "-" } else { "" };
+    Some(format!("{}{},{:02}", sinal, milhar, centavos % 100))
+}
+
+/// Aceita RFC 3339 (DateTime<Utc>), "AAAA-MM-DDTHH:MM:SS" e "AAAA-MM-DD"
+fn data_hora(valor: &Value) -> Option<NaiveDateTime> {
+    let texto = valor.as_str()?;
+    DateTime::parse_from_rfc3339(texto)
+        .map(|d| d.with_timezone(&Utc).naive_utc())
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(texto, "%Y-%m-%dT%H:%M:%S%.f").ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(texto, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+}
+
+/// Dias desde 30/12/1899, a base de datas do Excel
+fn serial_excel(data: NaiveDateTime) -> f64 {
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .expect("data base válida");
+    (data - base).num_seconds() as f64 / 86_400.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formatar_decimal_br() {
+        assert_eq!(
+            formatar_decimal_br("250000.01").as_deref(),
+            Some("250.000,01")
+        );
+        assert_eq!(formatar_decimal_br("1234.5").as_deref(), Some("1.234,50"));
+        assert_eq!(formatar_decimal_br("999.995").as_deref(), Some("1.000,00"));
+        assert_eq!(formatar_decimal_br("-12").as_deref(), Some("-12,00"));
+        assert_eq!(formatar_decimal_br("abc"), None);
+    }
+
+    #[test]
+    fn test_escapar_csv() {
+        assert_eq!(escapar_csv("simples"), "simples");
+        assert_eq!(escapar_csv("a;b"), "\"a;b\"");
+        assert_eq!(escapar_csv("diz \"oi\""), "\"diz \"\"oi\"\"\"");
+        assert_eq!(escapar_csv("=1+1"), "'=1+1");
+        assert_eq!(escapar_csv("-12"), "-12");
+    }
+
+    #[test]
+    fn test_export_params() {
+        let query = vec![
+            ("formato".to_string(), "xlsx".to_string()),
+            ("colunas".to_string(), "nome, email".to_string()),
+            ("colunas".to_string(), "id".to_string()),
+            ("find".to_string(), "maria".to_string()),
+        ];
+        let params = ExportParams::parse(&query).unwrap();
+        assert_eq!(params.format, ExportFormat::Xlsx);
+        assert_eq!(params.columns, vec!["nome", "email", "id"]);
+
+        let config = ExportConfig::new("teste").column("id", "ID", ColumnKind::Int);
+        assert!(config.select(&["senha".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_texto_celula() {
+        let data = ExportColumn {
+            name: "created_at".to_string(),
+            label: "Criado em".to_string(),
+            kind: ColumnKind::DateTime,
+            map: None,
+        };
+        let valor = Value::from("2025-08-07T00:37:10.123Z");
+        assert_eq!(texto_celula(&data, &valor), "07/08/2025 00:37");
+
+        let moeda = ExportColumn {
+            kind: ColumnKind::Money,
+            ..data
+        };
+        assert_eq!(texto_celula(&moeda, &Value::from("1500.00")), "R$ 1.500,00");
+    }
+}
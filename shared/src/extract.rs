@@ -0,0 +1,39 @@
+use axum::{
+    Json,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+    http::StatusCode,
+};
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+use crate::error::AppError;
+
+/*
+corpo JSON desserializado e validado com `validator` antes de chegar ao handler
+JSON malformado ou sem Content-Type vira 400; campo ausente ou de tipo errado, 422;
+regras do `validator` que falham, 422 com o detalhe por campo (`campos`)
+*/
+pub struct JsonValidado<T>(pub T);
+
+impl<T, S> FromRequest<S> for JsonValidado<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(valor) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(rejeicao)?;
+        valor.validate()?;
+        Ok(JsonValidado(valor))
+    }
+}
+
+fn rejeicao(err: JsonRejection) -> AppError {
+    match err.status() {
+        StatusCode::UNPROCESSABLE_ENTITY => AppError::Unprocessable(err.body_text()),
+        _ => AppError::InvalidInput(err.body_text()),
+    }
+}
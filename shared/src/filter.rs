@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use sqlx::{Postgres, QueryBuilder};
+
+/// Tipo do valor aceito por um filtro
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterKind {
+    Int,
+    Decimal,
+    Text,
+    Bool,
+    /// Data (`AAAA-MM-DD`); também filtra colunas `timestamptz` pelo dia inteiro
+    Date,
+}
+
+/*
+filtro declarado pelo repositorio
+name é o parametro da query string, column a coluna no SQL (ex.: "m.linha_id")
+somente campos declarados aqui podem ser filtrados
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct FilterField {
+    pub name: &'static str,
+    pub column: &'static str,
+    pub kind: FilterKind,
+}
+
+impl FilterField {
+    pub const fn new(name: &'static str, column: &'static str, kind: FilterKind) -> Self {
+        Self { name, column, kind }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Int(i64),
+    Decimal(f64),
+    Text(String),
+    Bool(bool),
+    Date(NaiveDate),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterOp {
+    Eq(FilterValue),
+    In(Vec<FilterValue>),
+    Gte(FilterValue),
+    Lte(FilterValue),
+    IsNull(bool),
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    pub field: FilterField,
+    pub op: FilterOp,
+}
+
+/*
+filtros lidos da query string:
+  campo=8              igualdade
+  campo=4,5            IN (somente inteiros; em decimais a vírgula é a casa decimal: valor=1,5)
+  campo__in=a,b        IN (listas de decimais usam ponto: valor__in=1.5,2.5)
+  campo__gte=valor     maior ou igual
+  campo__lte=valor     menor ou igual (datas incluem o dia inteiro)
+  campo__null=true     IS NULL / IS NOT NULL
+valores vazios são ignorados
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Filters {
+    items: Vec<Filter>,
+    raw: Vec<(String, String)>,
+}
+
+impl Filters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(fields: &[FilterField], params: &HashMap<String, String>) -> Result<Self> {
+        let mut filters = Filters::new();
+
+        // ordem estável para SQL e links de paginação
+        let mut chaves: Vec<&String> = params.keys().collect();
+        chaves.sort();
+
+        for chave in chaves {
+            let valor = params[chave].trim();
+            if valor.is_empty() {
+                continue;
+            }
+
+            let (nome, sufixo) = match chave.split_once("__") {
+                Some((nome, sufixo)) => (nome, Some(sufixo)),
+                None => (chave.as_str(), None),
+            };
+
+            let Some(field) = fields.iter().find(|f| f.name == nome) else {
+                continue;
+            };
+
+            let op = match sufixo {
+                None if valor.contains(',') && field.kind == FilterKind::Int => {
+                    FilterOp::In(parse_list(field, valor)?)
+                }
+                None => FilterOp::Eq(parse_value(field, valor)?),
+                Some("in") => FilterOp::In(parse_list(field, valor)?),
+                Some("gte") => FilterOp::Gte(parse_value(field, valor)?),
+                Some("lte") => FilterOp::Lte(parse_value(field, valor)?),
+                Some("null") => FilterOp::IsNull(parse_bool(field, valor)?),
+                Some(outro) => bail!("operador de filtro inválido: {}__{}", nome, outro),
+            };
+
+            filters.raw.push((chave.clone(), valor.to_string()));
+            filters.items.push(Filter { field: *field, op });
+        }
+
+        Ok(filters)
+    }
+
+    pub fn push(mut self, field: FilterField, op: FilterOp) -> Self {
+        self.items.push(Filter { field, op });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Filter> {
+        self.items.iter()
+    }
+
+    /// Valores recebidos, para repreencher o formulário de filtros
+    pub fn values(&self) -> HashMap<String, String> {
+        self.raw.iter().cloned().collect()
+    }
+
+    /// Query string (`&campo=valor...`) para manter os filtros na paginação
+    pub fn query_string(&self) -> String {
+        self.raw
+            .iter()
+            .map(|(k, v)| format!("&{}={}", k, urlencoding::encode(v)))
+            .collect()
+    }
+
+    /// Acrescenta `AND ...` para cada filtro com os valores como parâmetros
+    pub fn push_sql(&self, qb: &mut QueryBuilder<'_, Postgres>) {
+        for filter in &self.items {
+            let column = filter.field.column;
+            let data = filter.field.kind == FilterKind::Date;
+            qb.push(" AND ");
+
+            match &filter.op {
+                FilterOp::Eq(v) if data => {
+                    qb.push(format!("{}::date = ", column));
+                    push_value(qb, v);
+                }
+                FilterOp::Eq(v) => {
+                    qb.push(format!("{} = ", column));
+                    push_value(qb, v);
+                }
+                FilterOp::In(values) => {
+                    let column = if data {
+                        format!("{}::date", column)
+                    } else {
+                        column.to_string()
+                    };
+                    qb.push(format!("{} = ANY(", column));
+                    push_array(qb, values);
+                    qb.push(")");
+                }
+                FilterOp::Gte(v) => {
+                    qb.push(format!("{} >= ", column));
+                    push_value(qb, v);
+                }
+                FilterOp::Lte(FilterValue::Date(d)) => {
+                    // inclui o dia inteiro para colunas timestamptz
+                    qb.push(format!("{} < ", column));
+                    qb.push_bind(d.succ_opt().unwrap_or(*d));
+                }
+                FilterOp::Lte(v) => {
+                    qb.push(format!("{} <= ", column));
+                    push_value(qb, v);
+                }
+                FilterOp::IsNull(true) => {
+                    qb.push(format!("{} IS NULL", column));
+                }
+                FilterOp::IsNull(false) => {
+                    qb.push(format!("{} IS NOT NULL", column));
+                }
+            }
+        }
+    }
+}
+
+fn push_value(qb: &mut QueryBuilder<'_, Postgres>, value: &FilterValue) {
+    match value {
+        FilterValue::Int(v) => qb.push_bind(*v),
+        FilterValue::Decimal(v) => qb.push_bind(*v),
+        FilterValue::Text(v) => qb.push_bind(v.clone()),
+        FilterValue::Bool(v) => qb.push_bind(*v),
+        FilterValue::Date(v) => qb.push_bind(*v),
+    };
+}
+
+fn push_array(qb: &mut QueryBuilder<'_, Postgres>, values: &[FilterValue]) {
+    // parse_list garante que todos os valores são do mesmo tipo
+    match values.first() {
+        Some(FilterValue::Int(_)) => qb.push_bind(
+            values
+                .iter()
+                .filter_map(|v| match v {
+                    FilterValue::Int(i) => Some(*i),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Some(FilterValue::Decimal(_)) => qb.push_bind(
+            values
+                .iter()
+                .filter_map(|v| match v {
+                    FilterValue::Decimal(d) => Some(*d),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Some(FilterValue::Bool(_)) => qb.push_bind(
+            values
+                .iter()
+                .filter_map(|v| match v {
+                    FilterValue::Bool(b) => Some(*b),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Some(FilterValue::Date(_)) => qb.push_bind(
+            values
+                .iter()
+                .filter_map(|v| match v {
+                    FilterValue::Date(d) => Some(*d),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Some(FilterValue::Text(_)) | None => qb.push_bind(
+            values
+                .iter()
+                .filter_map(|v| match v {
+                    FilterValue::Text(t) => Some(t.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        ),
+    };
+}
+
+fn parse_list(field: &FilterField, valor: &str) -> Result<Vec<FilterValue>> {
+    let valores = valor
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| parse_value(field, v))
+        .collect::<Result<Vec<_>>>()?;
+    if valores.is_empty() {
+        bail!("lista vazia no filtro '{}'", field.name);
+    }
+    Ok(valores)
+}
+
+fn parse_value(field: &FilterField, valor: &str) -> Result<FilterValue> {
+    Ok(match field.kind {
+        FilterKind::Int => match valor.parse() {
+            Ok(v) => FilterValue::Int(v),
+            Err(_) => bail!(
+                "valor inteiro inválido no filtro '{}': {}",
+                field.name,
+                valor
+            ),
+        },
+        FilterKind::Decimal => match valor.replace(',', ".").parse() {
+            Ok(v) => FilterValue::Decimal(v),
+            Err(_) => bail!(
+                "valor numérico inválido no filtro '{}': {}",
+                field.name,
+                valor
+            ),
+        },
+        FilterKind::Text => FilterValue::Text(valor.to_string()),
+        FilterKind::Bool => FilterValue::Bool(parse_bool(field, valor)?),
+        FilterKind::Date => match NaiveDate::parse_from_str(valor, "%Y-%m-%d")
+            .or_else(|_| NaiveDate::parse_from_str(valor, "%d/%m/%Y"))
+        {
+            Ok(v) => FilterValue::Date(v),
+            Err(_) => bail!("data inválida no filtro '{}': {}", field.name, valor),
+        },
+    })
+}
+
+fn parse_bool(field: &FilterField, valor: &str) -> Result<bool> {
+    match valor.to_lowercase().as_str() {
+        "true" | "1" | "on" | "sim" => Ok(true),
+        "false" | "0" | "off" | "nao" | "não" => Ok(false),
+        _ => bail!(
+            "valor booleano inválido no filtro '{}': {}",
+            field.name,
+            valor
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAMPOS: &[FilterField] = &[
+        FilterField::new("status", "m.status", FilterKind::Int),
+        FilterField::new("nome", "m.nome", FilterKind::Text),
+        FilterField::new("created_at", "m.created_at", FilterKind::Date),
+        FilterField::new("valor", "m.valor", FilterKind::Decimal),
+    ];
+
+    fn params(v: &[(&str, &str)]) -> HashMap<String, String> {
+        v.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_filtros() {
+        let filtros = Filters::parse(
+            CAMPOS,
+            &params(&[
+                ("status", "4,5"),
+                ("created_at__gte", "2025-01-01"),
+                ("created_at__lte", "31/01/2025"),
+                ("page", "2"),
+                ("nome", ""),
+            ]),
+        )
+        .unwrap();
+
+        let ops: Vec<_> = filtros.iter().map(|f| f.op.clone()).collect();
+        assert_eq!(
+            ops,
+            vec![
+                FilterOp::Gte(FilterValue::Date(
+                    NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+                )),
+                FilterOp::Lte(FilterValue::Date(
+                    NaiveDate::from_ymd_opt(2025, 1, 31).unwrap()
+                )),
+                FilterOp::In(vec![FilterValue::Int(4), FilterValue::Int(5)]),
+            ]
+        );
+
+        let mut qb = QueryBuilder::<Postgres>::new("WHERE 1=1");
+        filtros.push_sql(&mut qb);
+        assert_eq!(
+            qb.sql(),
+            "WHERE 1=1 AND m.created_at >= $1 AND m.created_at < $2 AND m.status = ANY($3)"
+        );
+    }
+
+    #[test]
+    fn test_virgula_decimal() {
+        // vírgula sem sufixo em decimal é a casa decimal, não uma lista
+        let filtros = Filters::parse(CAMPOS, &params(&[("valor", "1,5")])).unwrap();
+        let ops: Vec<_> = filtros.iter().map(|f| f.op.clone()).collect();
+        assert_eq!(ops, vec![FilterOp::Eq(FilterValue::Decimal(1.5))]);
+
+        let filtros = Filters::parse(CAMPOS, &params(&[("valor__in", "1.5,2")])).unwrap();
+        let ops: Vec<_> = filtros.iter().map(|f| f.op.clone()).collect();
+        assert_eq!(
+            ops,
+            vec![FilterOp::In(vec![
+                FilterValue::Decimal(1.5),
+                FilterValue::Decimal(2.0)
+            ])]
+        );
+
+        // data com vírgula não vira lista
+        assert!(
+            Filters::parse(CAMPOS, &params(&[("created_at", "2025-01-01,2025-01-02")])).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_filtros_invalidos() {
+        assert!(Filters::parse(CAMPOS, &params(&[("status", "1; DROP")])).is_err());
+        assert!(Filters::parse(CAMPOS, &params(&[("status__like", "1")])).is_err());
+        assert!(
+            Filters::parse(CAMPOS, &params(&[("desconhecido", "1")]))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
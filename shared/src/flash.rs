@@ -0,0 +1,167 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::Request,
+    http::HeaderMap,
+    middleware::Next,
+    response::{IntoResponse, Redirect, Response},
+};
+use minijinja::Value;
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use tracing::error;
+
+/*
+mensagens flash guardadas na sessão (tower-sessions), e não mais na query string
+o handler devolve `flash_redirect`; o middleware `flash_middleware` grava a mensagem na sessão
+e, na requisição seguinte, expõe as pendentes para os templates pela função `flash_messages()`
+a mensagem só sai da sessão depois de renderizada por uma página, então redirects
+intermediários e chamadas de API não a consomem
+*/
+
+const FLASH_KEY: &str = "flash";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlashStatus {
+    Error,
+    Warning,
+    Info,
+    Success,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FlashMessage {
+    pub status: FlashStatus,
+    pub message: String,
+}
+
+/// mensagens adicionadas pelo handler, gravadas na sessão pelo middleware
+#[derive(Clone, Default)]
+struct FlashNovas(Vec<FlashMessage>);
+
+/// mensagens pendentes da requisição atual; `consumidas` quando algum template as exibiu
+#[derive(Default)]
+struct FlashAtual {
+    mensagens: Vec<FlashMessage>,
+    consumidas: bool,
+}
+
+tokio::task_local! {
+    static FLASH_ATUAL: Arc<Mutex<FlashAtual>>;
+}
+
+/// Anexa uma mensagem flash a qualquer resposta
+pub fn with_flash(res: impl IntoResponse, message: &str, status: FlashStatus) -> Response {
+    let mut res = res.into_response();
+    let novas = res.extensions_mut().get_or_insert_default::<FlashNovas>();
+    novas.0.push(FlashMessage {
+        status,
+        message: message.to_string(),
+    });
+    res
+}
+
+/// Redireciona exibindo a mensagem na página de destino
+pub fn flash_redirect(url: &str, message: &str, status: FlashStatus) -> Response {
+    with_flash(Redirect::to(url), message, status)
+}
+
+/// Função global dos templates: devolve as mensagens pendentes e as marca como exibidas
+pub fn flash_messages() -> Value {
+    FLASH_ATUAL
+        .try_with(|atual| {
+            let mut atual = atual.lock().unwrap();
+            atual.consumidas = true;
+            Value::from_serialize(&atual.mensagens)
+        })
+        .unwrap_or_else(|_| Value::from_serialize(Vec::<FlashMessage>::new()))
+}
+
+/// navegadores marcam requisições de fetch/XHR com `Sec-Fetch-Mode` diferente de `navigate`
+fn navegacao(headers: &HeaderMap) -> bool {
+    headers
+        .get("sec-fetch-mode")
+        .and_then(|v| v.to_str().ok())
+        .is_none_or(|modo| modo == "navigate")
+}
+
+/// Middleware: carrega as mensagens da sessão e grava as novas; precisa do `SessionManagerLayer`
+pub async fn flash_middleware(session: Session, req: Request, next: Next) -> Response {
+    // fetch() segue o redirect sozinho e descarta o HTML; a mensagem fica para a navegação seguinte
+    let pendentes: Vec<FlashMessage> = if navegacao(req.headers()) {
+        match session.get(FLASH_KEY).await {
+            Ok(pendentes) => pendentes.unwrap_or_default(),
+            Err(err) => {
+                error!("Erro ao ler mensagens flash da sessão: {}", err);
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    let atual = Arc::new(Mutex::new(FlashAtual {
+        mensagens: pendentes,
+        consumidas: false,
+    }));
+    let mut res = FLASH_ATUAL.scope(atual.clone(), next.run(req)).await;
+
+    let novas = res
+        .extensions_mut()
+        .remove::<FlashNovas>()
+        .unwrap_or_default()
+        .0;
+    let FlashAtual {
+        mut mensagens,
+        consumidas,
+    } = std::mem::take(&mut *atual.lock().unwrap());
+
+    // nada exibido e nada novo: a sessão fica como está
+    if novas.is_empty() && (mensagens.is_empty() || !consumidas) {
+        return res;
+    }
+    if consumidas {
+        mensagens.clear();
+    }
+    mensagens.extend(novas);
+
+    let gravado = if mensagens.is_empty() {
+        session
+            .remove::<Vec<FlashMessage>>(FLASH_KEY)
+            .await
+            .map(|_| ())
+    } else {
+        session.insert(FLASH_KEY, mensagens).await
+    };
+    if let Err(err) = gravado {
+        error!("Erro ao gravar mensagens flash na sessão: {}", err);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_flash_acumula_mensagens() {
+        let res = flash_redirect("/destino", "salvo", FlashStatus::Success);
+        let res = with_flash(res, "confira os dados", FlashStatus::Warning);
+
+        let novas = res.extensions().get::<FlashNovas>().unwrap();
+        assert_eq!(novas.0.len(), 2);
+        assert_eq!(novas.0[0].status, FlashStatus::Success);
+        assert_eq!(novas.0[1].message, "confira os dados");
+        assert_eq!(res.headers()["location"], "/destino");
+    }
+
+    #[test]
+    fn test_navegacao() {
+        let mut headers = HeaderMap::new();
+        assert!(navegacao(&headers));
+        headers.insert("sec-fetch-mode", "navigate".parse().unwrap());
+        assert!(navegacao(&headers));
+        headers.insert("sec-fetch-mode", "cors".parse().unwrap());
+        assert!(!navegacao(&headers));
+    }
+}
@@ -1,15 +1,11 @@
-use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
-
-#[derive(Deserialize)]
-pub struct GenericListParams {
-    pub page: Option<usize>,
-    pub search: Option<String>,
-    pub flash_message: Option<String>,
-    pub flash_status: Option<String>,
-}
+use serde::Serialize;
 
-#[derive(Serialize)]
+/*
+configuração da listagem do admin genérico (ver `admin::AdminResource`)
+entity_name é o segmento da rota ("tipo" -> /tipo, /tipo-form, /tipo/{id})
+base_url é a url completa da listagem, usada nos links e redirects ("/chamado/tipo")
+*/
+#[derive(Serialize, Clone, Debug)]
 pub struct ListConfig {
     pub entity_name: String,
     pub entity_label: String,
@@ -20,39 +16,150 @@ pub struct ListConfig {
     pub items_per_page: usize,
 }
 
-#[async_trait::async_trait]
-pub trait GenericListHandler {
-    type Item: serde::Serialize + Send + Sync;
-
-    async fn count_items(
-        &self,
-        pool: &PgPool,
-        params: &GenericListParams,
-    ) -> Result<usize, sqlx::Error>;
-
-    async fn fetch_items(
-        &self,
-        pool: &PgPool,
-        params: &GenericListParams,
-    ) -> Result<Vec<Self::Item>, sqlx::Error>;
+impl ListConfig {
+    pub fn new(entity_name: &str, entity_label: &str, plural_label: &str, base_url: &str) -> Self {
+        Self {
+            entity_name: entity_name.to_string(),
+            entity_label: entity_label.to_string(),
+            plural_label: plural_label.to_string(),
+            base_url: base_url.to_string(),
+            fields: Vec::new(),
+            searchable_fields: Vec::new(),
+            items_per_page: 10,
+        }
+    }
+
+    /// Coluna da tabela; se estiver em `sortable_columns()` do repositório o cabeçalho ordena
+    pub fn field(mut self, name: &str, label: &str) -> Self {
+        self.fields.push((name.to_string(), label.to_string()));
+        self
+    }
+
+    /// Rótulos do placeholder da busca; a busca usa `searchable_fields()` do repositório
+    pub fn searchable(mut self, fields: &[&str]) -> Self {
+        self.searchable_fields = fields.iter().map(|f| f.to_string()).collect();
+        self
+    }
+
+    pub fn items_per_page(mut self, items_per_page: usize) -> Self {
+        self.items_per_page = items_per_page;
+        self
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    Text,
+    TextArea,
+    Number,
+    /// valor em reais com máscara `moeda-br`
+    Money,
+    Checkbox,
+    /// `input type="date"` (AAAA-MM-DD)
+    Date,
+    /// `input type="time"` (HH:MM)
+    Time,
+    /// `select` com as opções de `FormField::options`
+    Select,
+}
+
+/// Opção de um campo select: valor enviado no formulário e texto exibido
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FieldOption {
+    pub value: i64,
+    pub label: String,
+}
+
+impl FieldOption {
+    pub fn new(value: i64, label: impl ToString) -> Self {
+        Self {
+            value,
+            label: label.to_string(),
+        }
+    }
+}
+
+/// Campo do formulário do admin genérico; `name` é o nome do campo no schema de create/update
+#[derive(Serialize, Clone, Debug)]
+pub struct FormField {
+    pub name: String,
+    pub label: String,
+    pub kind: FieldKind,
+    pub required: bool,
+    pub placeholder: Option<String>,
+    /// opções do select, fixas ou carregadas de `options_query` ao abrir o formulário
+    pub options: Vec<FieldOption>,
+    /// `SELECT valor, rótulo ...` das opções do select (valor bigint, rótulo text)
+    #[serde(skip)]
+    pub options_query: Option<&'static str>,
 }
 
-#[async_trait::async_trait]
-pub trait GenericCrudHandler: GenericListHandler {
-    type CreateData: for<'de> serde::Deserialize<'de> + Send;
-    type UpdateData: for<'de> serde::Deserialize<'de> + Send;
-
-    async fn create_item(
-        &self,
-        pool: &PgPool,
-        data: Self::CreateData,
-    ) -> Result<Self::Item, sqlx::Error>;
-    async fn update_item(
-        &self,
-        pool: &PgPool,
-        id: i32,
-        data: Self::UpdateData,
-    ) -> Result<Self::Item, sqlx::Error>;
-    async fn delete_item(&self, pool: &PgPool, id: i32) -> Result<u64, sqlx::Error>;
-    async fn get_item_by_id(&self, pool: &PgPool, id: i32) -> Result<Self::Item, sqlx::Error>;
+impl FormField {
+    pub fn new(name: &str, label: &str, kind: FieldKind) -> Self {
+        Self {
+            name: name.to_string(),
+            label: label.to_string(),
+            kind,
+            required: false,
+            placeholder: None,
+            options: Vec::new(),
+            options_query: None,
+        }
+    }
+
+    pub fn text(name: &str, label: &str) -> Self {
+        Self::new(name, label, FieldKind::Text)
+    }
+
+    pub fn textarea(name: &str, label: &str) -> Self {
+        Self::new(name, label, FieldKind::TextArea)
+    }
+
+    pub fn number(name: &str, label: &str) -> Self {
+        Self::new(name, label, FieldKind::Number)
+    }
+
+    pub fn money(name: &str, label: &str) -> Self {
+        Self::new(name, label, FieldKind::Money)
+    }
+
+    pub fn checkbox(name: &str, label: &str) -> Self {
+        Self::new(name, label, FieldKind::Checkbox)
+    }
+
+    pub fn date(name: &str, label: &str) -> Self {
+        Self::new(name, label, FieldKind::Date)
+    }
+
+    pub fn time(name: &str, label: &str) -> Self {
+        Self::new(name, label, FieldKind::Time)
+    }
+
+    /// Select com opções fixas (ex.: valores de um enum)
+    pub fn select(name: &str, label: &str, options: Vec<FieldOption>) -> Self {
+        let mut field = Self::new(name, label, FieldKind::Select);
+        field.options = options;
+        field
+    }
+
+    /*
+    select com opções lidas do banco a cada abertura do formulário
+    a consulta devolve (bigint, text), ex.: "SELECT id::bigint, nome FROM chamado_servico ORDER BY nome"
+    */
+    pub fn select_query(name: &str, label: &str, query: &'static str) -> Self {
+        let mut field = Self::new(name, label, FieldKind::Select);
+        field.options_query = Some(query);
+        field
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
 }
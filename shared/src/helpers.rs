@@ -1,29 +1,20 @@
+use axum::response::Html;
 use base64::{Engine as _, engine::general_purpose};
 use image::Luma;
+use minijinja::Environment;
 use qrcode::QrCode;
+use serde::Serialize;
 use std::io::Cursor;
 
-#[derive(Clone)]
-pub enum FlashStatus {
-    Error,
-    Info,
-    Success,
-}
-
-/// Cria uma URL com parâmetros de mensagem flash
-pub fn create_flash_url(base_url: &str, message: &str, status: FlashStatus) -> String {
-    let encoded_message = urlencoding::encode(message);
-    let status_str = match status {
-        FlashStatus::Success => "success",
-        FlashStatus::Error => "error",
-        FlashStatus::Info => "info",
-    };
+use crate::error::AppError;
 
-    if base_url.contains("?") {
-        format!("{}&msg={}&status={}", base_url, encoded_message, status_str)
-    } else {
-        format!("{}?msg={}&status={}", base_url, encoded_message, status_str)
-    }
+/// Renderiza um template; falha ao carregar ou renderizar vira `AppError::Internal`
+pub fn render(
+    templates: &Environment<'_>,
+    nome: &str,
+    ctx: impl Serialize,
+) -> Result<Html<String>, AppError> {
+    Ok(Html(templates.get_template(nome)?.render(ctx)?))
 }
 
 pub fn get_qr_code_base64(otp_url: &str) -> anyhow::Result<String> {
@@ -1,10 +1,27 @@
+pub mod admin;
+mod db;
 mod error;
+pub mod export;
+mod extract;
+mod filter;
+pub mod flash;
 pub mod generic_list;
 pub mod helpers;
+mod lote;
 mod repository;
 mod state;
+mod xlsx;
 
-pub use error::AppError;
-pub use helpers::FlashStatus;
-pub use repository::{ListParams, IdParams, PaginatedResponse, PaginationQuery, Repository};
+pub use db::{Db, UnitOfWork};
+pub use error::{
+    AppError, CampoAlterado, ConflictError, ErrorResponse, mensagem_erro, negociar_erro,
+};
+pub use extract::JsonValidado;
+pub use filter::{Filter, FilterField, FilterKind, FilterOp, FilterValue, Filters};
+pub use flash::{FlashStatus, flash_middleware, flash_redirect, with_flash};
+pub use lote::{AcaoLote, LoteForm, OpcaoLote, parse_ids};
+pub use repository::{
+    IdParams, ListParams, ListQuery, PaginatedResponse, PaginationQuery, Repository, SortDir,
+    limites_pagina,
+};
 pub use state::{AppState, MessageResponse, SharedState};
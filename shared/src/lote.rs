@@ -0,0 +1,62 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Ação do formulário de gestão em lote (valor do botão `acao`)
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AcaoLote {
+    Adicionar,
+    Remover,
+    AdicionarTodos,
+    RemoverTodos,
+}
+
+/*
+formulário das telas de gestão (regiao_gestao, perfil_gestao)
+`ids` vem separado por vírgula, montado no submit a partir dos checkboxes marcados
+*/
+#[derive(Debug, Deserialize)]
+pub struct LoteForm {
+    pub owner_id: i32,
+    pub acao: AcaoLote,
+    #[serde(default)]
+    pub ids: String,
+}
+
+impl LoteForm {
+    pub fn ids(&self) -> Result<Vec<i32>> {
+        parse_ids(&self.ids)
+    }
+}
+
+/// Item que ainda pode ser associado (lista de seleção múltipla)
+#[derive(Debug, Serialize)]
+pub struct OpcaoLote {
+    pub id: i32,
+    pub nome: String,
+}
+
+/// "1, 2,3" -> [1, 2, 3]; vazio -> []
+pub fn parse_ids(valor: &str) -> Result<Vec<i32>> {
+    valor
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| match v.parse() {
+            Ok(id) => Ok(id),
+            Err(_) => bail!("id inválido: {}", v),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_ids;
+
+    #[test]
+    fn test_parse_ids() {
+        assert_eq!(parse_ids("1, 2,3,").unwrap(), vec![1, 2, 3]);
+        assert!(parse_ids("").unwrap().is_empty());
+        assert!(parse_ids("1,x").is_err());
+    }
+}
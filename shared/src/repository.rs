@@ -3,8 +3,15 @@ use anyhow::Result;
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde::{Serialize, de::DeserializeOwned};
-use sqlx::{Encode, FromRow, PgPool, Postgres, Type, postgres::PgRow};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sqlx::postgres::{PgHasArrayType, PgRow};
+use sqlx::{Decode, Encode, FromRow, PgPool, Postgres, QueryBuilder, Row, Type};
 use std::fmt::Display;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::db::Db;
+use crate::error::ConflictError;
+use crate::filter::{FilterField, Filters};
 
 #[derive(Serialize, Deserialize, Debug)]
 struct EditorContent {
@@ -13,29 +20,101 @@ struct EditorContent {
     version: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
+    /// -1 no modo cursor (sem contagem)
     pub total_records: i64,
     pub page: i32,
     pub page_size: i32,
     pub total_pages: i32,
+    /// somente no modo cursor: valor de `cursor` para a próxima página
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Maior página aceita: `(page - 1) * page_size` não pode estourar i32
+const MAX_PAGINA: i32 = i32::MAX / 100;
+
+/// Página (1 a `MAX_PAGINA`) e tamanho (1 a 100) aceitos pelas listagens
+pub fn limites_pagina(page: i32, page_size: i32) -> (i32, i32) {
+    (page.clamp(1, MAX_PAGINA), page_size.clamp(1, 100))
+}
+
+impl<T> PaginatedResponse<T> {
+    /*
+       página já buscada no modo offset; consultas próprias (fora de `Repository::list`)
+       devem passar `page` e `page_size` por `limites_pagina` antes de consultar
+    */
+    pub fn offset(data: Vec<T>, total_records: i64, page: i32, page_size: i32) -> Self {
+        let (page, page_size) = limites_pagina(page, page_size);
+        let total_pages = if total_records == 0 {
+            1
+        } else {
+            ((total_records + page_size as i64 - 1) / page_size as i64) as i32
+        };
+
+        Self {
+            data,
+            total_records,
+            page,
+            page_size,
+            total_pages,
+            next_cursor: None,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PaginationQuery {
+    /// termo de busca
     pub find: Option<String>,
+    /// página, a partir de 1 (modo offset)
     pub page: Option<u32>,
+    /// itens por página, no máximo 100
     pub page_size: Option<u32>,
+    /// coluna de ordenação; valores fora da lista do recurso são ignorados
+    pub sort: Option<String>,
+    /// `asc` ou `desc` (padrão)
+    pub dir: Option<String>,
+    /// presente (mesmo vazio) ativa a paginação por cursor
+    pub cursor: Option<String>,
+}
+
+impl PaginationQuery {
+    pub fn list_query(&self) -> ListQuery {
+        ListQuery::new(
+            self.find.as_deref(),
+            self.page.unwrap_or(1) as i32,
+            self.page_size.unwrap_or(10) as i32,
+        )
+        .with_sort(self.sort.as_deref(), self.dir.as_deref())
+        .with_cursor(self.cursor.as_deref())
+    }
 }
 
 #[derive(Deserialize)]
 pub struct ListParams {
     pub page: Option<i32>,
     pub find: Option<String>,
-    pub msg: Option<String>,
-    pub status: Option<String>,
     pub page_size: Option<i32>,
+    pub sort: Option<String>,
+    pub dir: Option<String>,
+    /// presente (mesmo vazio) ativa a paginação por cursor
+    pub cursor: Option<String>,
+}
+
+impl ListParams {
+    pub fn list_query(&self) -> ListQuery {
+        ListQuery::new(
+            self.find.as_deref(),
+            self.page.unwrap_or(1),
+            self.page_size.unwrap_or(10),
+        )
+        .with_sort(self.sort.as_deref(), self.dir.as_deref())
+        .with_cursor(self.cursor.as_deref())
+    }
 }
 
 #[derive(Deserialize)]
@@ -43,6 +122,263 @@ pub struct IdParams {
     pub id: Option<i64>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDir {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDir {
+    pub fn parse(dir: Option<&str>) -> Self {
+        match dir.map(str::to_ascii_lowercase).as_deref() {
+            Some("asc") => SortDir::Asc,
+            _ => SortDir::Desc,
+        }
+    }
+
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortDir::Asc => "ASC",
+            SortDir::Desc => "DESC",
+        }
+    }
+}
+
+/// Parâmetros de uma listagem paginada
+#[derive(Debug, Clone, Default)]
+pub struct ListQuery {
+    pub find: Option<String>,
+    pub filters: Filters,
+    /// nome público da coluna, validado contra `sortable_columns()`
+    pub sort: Option<String>,
+    pub dir: SortDir,
+    pub page: i32,
+    pub page_size: i32,
+    /// Some("") = primeira página no modo cursor
+    pub cursor: Option<String>,
+    /// usa as cláusulas `*_view` do repositório
+    pub view: bool,
+    /// lista somente os registros na lixeira (ver `soft_delete_column`)
+    pub trash: bool,
+}
+
+impl ListQuery {
+    pub fn new(find: Option<&str>, page: i32, page_size: i32) -> Self {
+        Self {
+            find: find.filter(|f| !f.trim().is_empty()).map(str::to_string),
+            filters: Filters::new(),
+            sort: None,
+            dir: SortDir::default(),
+            page,
+            page_size,
+            cursor: None,
+            view: false,
+            trash: false,
+        }
+    }
+
+    pub fn trash(mut self) -> Self {
+        self.trash = true;
+        self
+    }
+
+    pub fn with_cursor(mut self, cursor: Option<&str>) -> Self {
+        self.cursor = cursor.map(str::to_string);
+        self
+    }
+
+    pub fn view(mut self) -> Self {
+        self.view = true;
+        self
+    }
+
+    pub fn with_sort(mut self, sort: Option<&str>, dir: Option<&str>) -> Self {
+        self.sort = sort.filter(|s| !s.is_empty()).map(str::to_string);
+        self.dir = SortDir::parse(dir);
+        self
+    }
+
+    /// Query string com busca e filtros (`&find=...&campo=...`), sem página e ordenação
+    pub fn base_query_string(&self) -> String {
+        let mut query = String::new();
+        if let Some(find) = &self.find {
+            query.push_str(&format!("&find={}", urlencoding::encode(find)));
+        }
+        query.push_str(&self.filters.query_string());
+        query
+    }
+
+    /// Query string (`&sort=...&dir=...`) para manter a ordenação na paginação
+    pub fn sort_query_string(&self) -> String {
+        match &self.sort {
+            Some(sort) => format!(
+                "&sort={}&dir={}",
+                urlencoding::encode(sort),
+                self.dir.as_sql().to_lowercase()
+            ),
+            None => String::new(),
+        }
+    }
+
+    pub fn with_filters(mut self, filters: Filters) -> Self {
+        self.filters = filters;
+        self
+    }
+}
+
+/*
+cursor opaco: id do último registro + ordenação em que foi gerado
+(base64 de um JSON), rejeitado se a ordenação mudar
+*/
+#[derive(Serialize, Deserialize)]
+struct Cursor<ID> {
+    id: ID,
+    sort: Option<String>,
+    dir: SortDir,
+}
+
+impl<ID: Serialize + DeserializeOwned> Cursor<ID> {
+    fn encode(id: &ID, sort: Option<&str>, dir: SortDir) -> Result<String> {
+        let json = serde_json::to_vec(&serde_json::json!({ "id": id, "sort": sort, "dir": dir }))?;
+        Ok(URL_SAFE_NO_PAD.encode(json))
+    }
+
+    /// `""` é a primeira página
+    fn decode(cursor: &str, sort: Option<&str>, dir: SortDir) -> Result<Option<ID>> {
+        if cursor.is_empty() {
+            return Ok(None);
+        }
+        let invalido = || anyhow::anyhow!("cursor inválido");
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalido())?;
+        let cursor: Cursor<ID> = serde_json::from_slice(&bytes).map_err(|_| invalido())?;
+        if cursor.sort.as_deref() != sort || cursor.dir != dir {
+            anyhow::bail!("cursor gerado para outra ordenação");
+        }
+        Ok(Some(cursor.id))
+    }
+}
+
+/*
+registros depois do cursor na ordem `coluna dir NULLS LAST, id dir`
+comparar tuplas com NULL dá NULL, então os nulos (sempre no fim) são tratados à parte:
+  cursor com valor: os de valor depois dele e todos os nulos
+  cursor nulo: só os nulos depois do id
+*/
+fn predicado_cursor(column: &str, id_column: &str, dir: SortDir) -> String {
+    let cmp = if dir == SortDir::Asc { ">" } else { "<" };
+    format!(
+        " AND (CASE WHEN __cursor.__cursor_valor IS NULL \
+         THEN {col} IS NULL AND {id} {cmp} __cursor.__cursor_ref \
+         ELSE {col} IS NULL OR ({col}, {id}) {cmp} (__cursor.__cursor_valor, __cursor.__cursor_ref) END)",
+        col = column,
+        id = id_column,
+        cmp = cmp
+    )
+}
+
+/// Coluna sem o alias da tabela ("m.deleted_at" -> "deleted_at"), para UPDATE/DELETE
+fn plain_column(column: &str) -> &str {
+    column.rsplit('.').next().unwrap_or(column)
+}
+
+/// configuração de busca textual criada na migration `busca_textual` (portuguese + unaccent)
+const TS_CONFIG: &str = "portuguese_unaccent";
+
+/*
+termo digitado -> tsquery com prefixo em todas as palavras ("joao sil" -> "joao:* & sil:*")
+pontuação separa palavras, exceto em números formatados ("123.456.789-00" -> "12345678900:*")
+nada que sobra tem significado para to_tsquery, então o termo nunca gera erro de sintaxe
+*/
+fn termo_tsquery(term: &str) -> Option<String> {
+    let mut palavras = Vec::new();
+    for token in term.split_whitespace() {
+        let numero = token.chars().any(|c| c.is_ascii_digit())
+            && token.chars().all(|c| c.is_ascii_digit() || ".-/".contains(c));
+        if numero {
+            palavras.push(token.chars().filter(char::is_ascii_digit).collect());
+        } else {
+            palavras.extend(
+                token
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|p| !p.is_empty())
+                    .map(str::to_lowercase),
+            );
+        }
+    }
+    if palavras.is_empty() {
+        return None;
+    }
+    Some(
+        palavras
+            .iter()
+            .map(|p| format!("{}:*", p))
+            .collect::<Vec<_>>()
+            .join(" & "),
+    )
+}
+
+/// Onde buscar o termo: tsvector do repositório ou ILIKE nos searchable_fields
+#[derive(Clone, Copy)]
+enum Busca<'a> {
+    Textual(&'a str),
+    Campos(&'a [&'a str]),
+}
+
+/*
+monta o WHERE: termo de busca (full-text ou ILIKE), extra_where,
+exclusão lógica e filtros
+todos os valores vão como parametros
+*/
+fn push_where(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    busca: Busca<'_>,
+    find: Option<&str>,
+    extra_where: Option<&str>,
+    soft_delete: Option<(&str, bool)>,
+    filters: &Filters,
+) {
+    qb.push(" WHERE 1=1");
+
+    if let Some((column, trash)) = soft_delete {
+        if trash {
+            qb.push(format!(" AND {} IS NOT NULL", column));
+        } else {
+            qb.push(format!(" AND {} IS NULL", column));
+        }
+    }
+
+    match (busca, find) {
+        (Busca::Textual(vector), Some(term)) => {
+            if let Some(tsquery) = termo_tsquery(term) {
+                qb.push(format!(" AND {} @@ to_tsquery('{}', ", vector, TS_CONFIG));
+                qb.push_bind(tsquery);
+                qb.push(")");
+            }
+        }
+        (Busca::Campos(searchable_fields), Some(term)) if !searchable_fields.is_empty() => {
+            qb.push(" AND (");
+            let pattern = format!("%{}%", term);
+            for (i, field) in searchable_fields.iter().enumerate() {
+                if i > 0 {
+                    qb.push(" OR ");
+                }
+                qb.push(format!("{} ILIKE ", field));
+                qb.push_bind(pattern.clone());
+            }
+            qb.push(")");
+        }
+        _ => {}
+    }
+
+    if let Some(extra) = extra_where {
+        qb.push(format!(" AND ({})", extra));
+    }
+
+    filters.push_sql(qb);
+}
+
 #[async_trait]
 pub trait Repository<T, ID>
 where
@@ -63,6 +399,16 @@ where
 
     fn table_name(&self) -> &str;
     fn searchable_fields(&self) -> &[&str];
+
+    /*
+    busca textual (opt-in): expressão tsvector com alias do from_clause, igual à do
+    índice GIN da tabela, ex.: "to_tsvector('portuguese_unaccent', m.nome)"
+    com Some(..) o termo `find` usa full-text (sem acento, com radical e prefixo) no lugar
+    do ILIKE em searchable_fields, e a listagem sem `sort` vem ordenada por relevância
+    */
+    fn search_vector(&self) -> Option<&str> {
+        None
+    }
     fn select_clause(&self) -> &str;
     fn from_clause(&self) -> &str;
     fn id_column(&self) -> &str {
@@ -84,6 +430,46 @@ where
         None
     }
 
+    /*
+    exclusão lógica (opt-in): coluna timestamptz com alias do from_clause, ex.: "m.deleted_at"
+    com Some(..) list/get_by_id ignoram registros excluídos, e
+    soft_delete/restore/purge passam a funcionar
+    */
+    fn soft_delete_column(&self) -> Option<&str> {
+        None
+    }
+
+    /*
+    tabelas de associação (opt-in): (coluna do dono, coluna do item) sem alias,
+    ex.: ("regiao_id", "municipio_id"); com Some(..) bulk_create/bulk_delete/bulk_delete_all
+    passam a funcionar. A tabela precisa de UNIQUE(dono, item)
+    */
+    fn association_columns(&self) -> Option<(&str, &str)> {
+        None
+    }
+
+    /// Campos que podem ser filtrados pela query string (ver `Filters`)
+    fn filterable_fields(&self) -> &[FilterField] {
+        &[]
+    }
+
+    /*
+    colunas que o cliente pode ordenar: (nome no parametro sort, coluna SQL)
+    qualquer outro valor cai na ordenação padrão
+    */
+    fn sortable_columns(&self) -> &[(&str, &str)] {
+        &[]
+    }
+
+    /// Coluna SQL da ordenação pedida, se estiver em `sortable_columns()`
+    fn sort_column(&self, query: &ListQuery) -> Option<&str> {
+        let sort = query.sort.as_deref()?;
+        self.sortable_columns()
+            .iter()
+            .find(|(name, _)| *name == sort)
+            .map(|(_, column)| *column)
+    }
+
     async fn get_paginated(
         &self,
         pool: &PgPool,
@@ -93,101 +479,366 @@ where
     ) -> Result<PaginatedResponse<T>>
     where
         T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+        ID: for<'r> Decode<'r, Postgres> + Serialize + DeserializeOwned,
     {
-        let page = page.max(1);
-        let page_size = page_size.min(100);
-        let offset = (page - 1) * page_size;
+        self.list(pool, &ListQuery::new(find, page, page_size)).await
+    }
 
-        // Construir WHERE clause com parâmetros seguros
-        let (where_clause, params) = if let Some(term) = find {
-            let search_fields = self.searchable_fields();
-            if !search_fields.is_empty() {
-                let mut field_parts = Vec::new();
-                for field in search_fields {
-                    field_parts.push(format!("{} ILIKE $1", field));
+    /*
+    busca paginada com termo de busca, filtros tipados e ordenação
+
+    modo offset (padrão): uma única consulta com COUNT(*) OVER()
+    modo cursor (query.cursor preenchido): keyset sobre (coluna ordenada, id),
+    sem contagem; next_cursor aponta para a próxima página.
+    colunas que aceitam NULL (inclusive de LEFT JOIN) ficam no fim, como no modo offset
+
+    query.view usa select_clause_view/from_clause_view/order_by_column
+    com search_vector e termo de busca, o modo offset sem `sort` ordena por ts_rank;
+    o modo cursor mantém a ordenação de sempre (keyset não pagina por relevância)
+    */
+    async fn list(&self, pool: &PgPool, query: &ListQuery) -> Result<PaginatedResponse<T>>
+    where
+        T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+        ID: for<'r> Decode<'r, Postgres> + Serialize + DeserializeOwned,
+    {
+        let (page, page_size) = limites_pagina(query.page, query.page_size);
+        let (select, from, default_column) = if query.view {
+            (
+                self.select_clause_view(),
+                self.from_clause_view(),
+                self.order_by_column(),
+            )
+        } else {
+            (self.select_clause(), self.from_clause(), self.id_column())
+        };
+        let id_column = self.id_column();
+        let sort_column = self.sort_column(query);
+
+        let busca = match self.search_vector() {
+            Some(vector) => Busca::Textual(vector),
+            None => Busca::Campos(self.searchable_fields()),
+        };
+        let push_filters = |qb: &mut QueryBuilder<'_, Postgres>| {
+            push_where(
+                qb,
+                busca,
+                query.find.as_deref(),
+                self.extra_where(),
+                self.soft_delete_column().map(|c| (c, query.trash)),
+                &query.filters,
+            )
+        };
+
+        // === CURSOR ===
+        if let Some(cursor) = &query.cursor {
+            let (column, dir) = match sort_column {
+                Some(column) => (column, query.dir),
+                None => (default_column, SortDir::Desc),
+            };
+            let after: Option<ID> = Cursor::decode(cursor, query.sort.as_deref(), dir)?;
+
+            let mut qb = QueryBuilder::<Postgres>::new(format!(
+                "SELECT {}, {} AS __cursor_id FROM {}",
+                select, id_column, from
+            ));
+            match after {
+                Some(after) if column == id_column => {
+                    push_filters(&mut qb);
+                    let cmp = if dir == SortDir::Asc { ">" } else { "<" };
+                    qb.push(format!(" AND {} {} ", id_column, cmp));
+                    qb.push_bind(after);
+                }
+                Some(after) => {
+                    // posição do último registro lida pelo id (pk indexada)
+                    qb.push(format!(
+                        " CROSS JOIN (SELECT {} AS __cursor_valor, {} AS __cursor_ref FROM {} WHERE {} = ",
+                        column, id_column, from, id_column
+                    ));
+                    qb.push_bind(after);
+                    qb.push(") __cursor");
+                    push_filters(&mut qb);
+                    qb.push(predicado_cursor(column, id_column, dir));
                 }
-                let where_str = format!("WHERE ({})", field_parts.join(" OR "));
-                (where_str, vec![format!("%{}%", term)])
+                None => push_filters(&mut qb),
+            }
+
+            if column == id_column {
+                qb.push(format!(" ORDER BY {} {}", id_column, dir.as_sql()));
             } else {
-                (String::new(), vec![])
+                // NULLS LAST nos dois sentidos, como no modo offset e em predicado_cursor
+                qb.push(format!(
+                    " ORDER BY {col} {dir} NULLS LAST, {id} {dir}",
+                    col = column,
+                    id = id_column,
+                    dir = dir.as_sql()
+                ));
             }
-        } else {
-            (String::new(), vec![])
+            qb.push(" LIMIT ");
+            qb.push_bind(page_size as i64 + 1);
+
+            let mut rows = qb.build().fetch_all(pool).await?;
+            let has_next = rows.len() > page_size as usize;
+            rows.truncate(page_size as usize);
+
+            let next_cursor = match (has_next, rows.last()) {
+                (true, Some(row)) => {
+                    let id: ID = row.try_get("__cursor_id")?;
+                    Some(Cursor::encode(&id, query.sort.as_deref(), dir)?)
+                }
+                _ => None,
+            };
+            let data = rows
+                .iter()
+                .map(T::from_row)
+                .collect::<std::result::Result<Vec<T>, _>>()?;
+
+            return Ok(PaginatedResponse {
+                data,
+                total_records: -1,
+                page: 1,
+                page_size,
+                total_pages: 0,
+                next_cursor,
+            });
+        }
+
+        // === OFFSET ===
+        let offset = (page - 1) * page_size;
+
+        // relevância só quando o cliente não escolheu a ordenação
+        let rank = match (self.search_vector(), query.find.as_deref()) {
+            (Some(vector), Some(term)) if sort_column.is_none() => {
+                termo_tsquery(term).map(|tsquery| (vector, tsquery))
+            }
+            _ => None,
         };
 
-        // === COUNT ===
-        let count_query = format!(
-            "SELECT COUNT(*) FROM {} {}",
-            self.from_clause(),
-            where_clause
-        );
+        let order_by = match sort_column {
+            Some(column) if column != id_column => format!(
+                "{} {} NULLS LAST, {} {}",
+                column,
+                query.dir.as_sql(),
+                id_column,
+                query.dir.as_sql()
+            ),
+            Some(column) => format!("{} {}", column, query.dir.as_sql()),
+            None => format!("{} DESC", default_column),
+        };
 
-        let total: (i64,) = if params.is_empty() {
-            sqlx::query_as(&count_query).fetch_one(pool).await?
-        } else {
-            sqlx::query_as(&count_query)
-                .bind(&params[0])
-                .fetch_one(pool)
-                .await?
+        let mut qb = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {}, COUNT(*) OVER() AS __total FROM {}",
+            select, from
+        ));
+        push_filters(&mut qb);
+        match rank {
+            Some((vector, tsquery)) => {
+                qb.push(format!(" ORDER BY ts_rank({}, to_tsquery('{}', ", vector, TS_CONFIG));
+                qb.push_bind(tsquery);
+                qb.push(format!(")) DESC, {} LIMIT ", order_by));
+            }
+            None => {
+                qb.push(format!(" ORDER BY {} LIMIT ", order_by));
+            }
+        }
+        qb.push_bind(page_size as i64);
+        qb.push(" OFFSET ");
+        qb.push_bind(offset as i64);
+
+        let rows = qb.build().fetch_all(pool).await?;
+
+        let total_records: i64 = match rows.first() {
+            Some(row) => row.try_get("__total")?,
+            // página além do fim: a janela não traz o total
+            None if page > 1 => {
+                let mut count =
+                    QueryBuilder::<Postgres>::new(format!("SELECT COUNT(*) FROM {}", from));
+                push_filters(&mut count);
+                count.build_query_scalar().fetch_one(pool).await?
+            }
+            None => 0,
         };
 
-        // === DATA ===
-        let data_query = format!(
-            "SELECT {} FROM {} {} ORDER BY {} DESC LIMIT ${} OFFSET ${}",
+        let data = rows
+            .iter()
+            .map(T::from_row)
+            .collect::<std::result::Result<Vec<T>, _>>()?;
+
+        Ok(PaginatedResponse::offset(data, total_records, page, page_size))
+    }
+
+    async fn get_by_id<'c, A: Db<'c>>(&self, db: A, id: ID) -> anyhow::Result<T> {
+        let deleted = match self.soft_delete_column() {
+            Some(column) => format!(" AND {} IS NULL", column),
+            None => String::new(),
+        };
+        let query = format!(
+            "SELECT {} FROM {} WHERE {} = $1{} LIMIT 1",
             self.select_clause(),
             self.from_clause(),
-            where_clause,
             self.id_column(),
-            if params.is_empty() { 1 } else { 2 },
-            if params.is_empty() { 2 } else { 3 }
+            deleted
         );
 
-        let data = if params.is_empty() {
-            sqlx::query_as::<_, T>(&data_query)
-                .bind(page_size as i64)
-                .bind(offset as i64)
-                .fetch_all(pool)
-                .await?
-        } else {
-            sqlx::query_as::<_, T>(&data_query)
-                .bind(&params[0])
-                .bind(page_size as i64)
-                .bind(offset as i64)
-                .fetch_all(pool)
-                .await?
-        };
+        let mut conn = db.acquire().await?;
+        Ok(sqlx::query_as(&query).bind(id).fetch_one(&mut *conn).await?)
+    }
 
-        let total_pages: i32 = if total.0 == 0 {
-            1
-        } else {
-            ((total.0 as f32) / (page_size as f32)).ceil() as i32
-        };
+    /// Move para a lixeira (`deleted_at = NOW()`)
+    async fn soft_delete<'c, A: Db<'c>>(&self, db: A, id: ID) -> Result<()> {
+        let column = self.soft_deletable()?;
+        let query = format!(
+            "UPDATE {} SET {} = NOW() WHERE {} = $1 AND {} IS NULL",
+            self.table_name(),
+            column,
+            plain_column(self.id_column()),
+            column
+        );
+        let mut conn = db.acquire().await?;
+        let result = sqlx::query(&query).bind(id).execute(&mut *conn).await?;
+        if result.rows_affected() == 0 {
+            anyhow::bail!("registro não encontrado");
+        }
+        Ok(())
+    }
 
-        Ok(PaginatedResponse {
-            data,
-            total_records: total.0,
-            page,
-            page_size,
-            total_pages,
-        })
+    /// Retira da lixeira
+    async fn restore<'c, A: Db<'c>>(&self, db: A, id: ID) -> Result<()> {
+        let column = self.soft_deletable()?;
+        let query = format!(
+            "UPDATE {} SET {} = NULL WHERE {} = $1 AND {} IS NOT NULL",
+            self.table_name(),
+            column,
+            plain_column(self.id_column()),
+            column
+        );
+        let mut conn = db.acquire().await?;
+        let result = sqlx::query(&query).bind(id).execute(&mut *conn).await?;
+        if result.rows_affected() == 0 {
+            anyhow::bail!("registro não está na lixeira");
+        }
+        Ok(())
     }
 
-    async fn get_by_id(&self, pool: &PgPool, id: ID) -> anyhow::Result<T> {
+    /// Exclui fisicamente um registro que já está na lixeira
+    async fn purge<'c, A: Db<'c>>(&self, db: A, id: ID) -> Result<()> {
+        let column = self.soft_deletable()?;
         let query = format!(
-            "SELECT {} FROM {} WHERE {} = $1 LIMIT 1",
-            self.select_clause(),
-            self.from_clause(),
-            self.id_column()
+            "DELETE FROM {} WHERE {} = $1 AND {} IS NOT NULL",
+            self.table_name(),
+            plain_column(self.id_column()),
+            column
+        );
+        let mut conn = db.acquire().await?;
+        let result = sqlx::query(&query).bind(id).execute(&mut *conn).await?;
+        if result.rows_affected() == 0 {
+            anyhow::bail!("registro não está na lixeira");
+        }
+        Ok(())
+    }
+
+    /*
+    erro para um update com checagem de versão (`... AND updated_at = $n`) que não
+    afetou nenhuma linha: ConflictError se o registro ainda existe, senão o erro do get_by_id
+    */
+    async fn stale_update<'c, A: Db<'c>>(&self, db: A, id: ID) -> anyhow::Error {
+        match self.get_by_id(db, id).await {
+            std::result::Result::Ok(atual) => ConflictError::new(self.table_name(), &atual).into(),
+            Err(err) => err,
+        }
+    }
+
+    /*
+    associa vários itens ao dono em uma única query (UNNEST), ignorando os que já existem
+    retorna quantas linhas foram inseridas
+    */
+    async fn bulk_create<'c, A, K>(&self, db: A, owner: K, items: &[K]) -> Result<u64>
+    where
+        A: Db<'c>,
+        K: Type<Postgres> + PgHasArrayType + for<'q> Encode<'q, Postgres> + Clone + Send + Sync,
+    {
+        let (owner_column, item_column) = self.association()?;
+        if items.is_empty() {
+            return Ok(0);
+        }
+        let query = format!(
+            "INSERT INTO {} ({}, {}) SELECT $1, item FROM UNNEST($2) AS u(item) \
+             ON CONFLICT DO NOTHING",
+            self.table_name(),
+            owner_column,
+            item_column
+        );
+        let mut conn = db.acquire().await?;
+        let result = sqlx::query(&query)
+            .bind(owner)
+            .bind(items.to_vec())
+            .execute(&mut *conn)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Remove do dono os itens informados; retorna quantas linhas foram excluídas
+    async fn bulk_delete<'c, A, K>(&self, db: A, owner: K, items: &[K]) -> Result<u64>
+    where
+        A: Db<'c>,
+        K: Type<Postgres> + PgHasArrayType + for<'q> Encode<'q, Postgres> + Clone + Send + Sync,
+    {
+        let (owner_column, item_column) = self.association()?;
+        if items.is_empty() {
+            return Ok(0);
+        }
+        let query = format!(
+            "DELETE FROM {} WHERE {} = $1 AND {} = ANY($2)",
+            self.table_name(),
+            owner_column,
+            item_column
         );
+        let mut conn = db.acquire().await?;
+        let result = sqlx::query(&query)
+            .bind(owner)
+            .bind(items.to_vec())
+            .execute(&mut *conn)
+            .await?;
+        Ok(result.rows_affected())
+    }
 
-        Ok(sqlx::query_as(&query).bind(id).fetch_one(pool).await?)
+    /// Remove todas as associações do dono
+    async fn bulk_delete_all<'c, A, K>(&self, db: A, owner: K) -> Result<u64>
+    where
+        A: Db<'c>,
+        K: Type<Postgres> + for<'q> Encode<'q, Postgres> + Send + Sync,
+    {
+        let (owner_column, _) = self.association()?;
+        let query = format!("DELETE FROM {} WHERE {} = $1", self.table_name(), owner_column);
+        let mut conn = db.acquire().await?;
+        let result = sqlx::query(&query).bind(owner).execute(&mut *conn).await?;
+        Ok(result.rows_affected())
     }
 
-    async fn create(&self, pool: &PgPool, input: Self::CreateInput) -> Result<T>;
+    /// Colunas de associação, ou erro se o repositório não é uma tabela de associação
+    fn association(&self) -> Result<(&str, &str)> {
+        match self.association_columns() {
+            Some(columns) => Ok(columns),
+            None => anyhow::bail!("{} não é uma tabela de associação", self.table_name()),
+        }
+    }
 
-    async fn update(&self, pool: &PgPool, id: ID, input: Self::UpdateInput) -> Result<T>;
+    /// Coluna de exclusão lógica sem alias, ou erro se o repositório não usa lixeira
+    fn soft_deletable(&self) -> Result<&str> {
+        match self.soft_delete_column() {
+            Some(column) => Ok(plain_column(column)),
+            None => anyhow::bail!("{} não possui exclusão lógica", self.table_name()),
+        }
+    }
 
-    async fn delete(&self, pool: &PgPool, id: ID) -> Result<()>;
+    /*
+    create/update/delete aceitam o pool ou uma transação (ver `Db` e `UnitOfWork`)
+    implementações com mais de uma query usam `let mut conn = db.acquire().await?`
+    */
+    async fn create<'c, A: Db<'c>>(&self, db: A, input: Self::CreateInput) -> Result<T>;
+
+    async fn update<'c, A: Db<'c>>(&self, db: A, id: ID, input: Self::UpdateInput) -> Result<T>;
+
+    async fn delete<'c, A: Db<'c>>(&self, db: A, id: ID) -> Result<()>;
 
     async fn get_paginated_view(
         &self,
@@ -198,82 +849,75 @@ where
     ) -> Result<PaginatedResponse<T>>
     where
         T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> + Send + Unpin,
+        ID: for<'r> Decode<'r, Postgres> + Serialize + DeserializeOwned,
     {
-        let page = page.max(1);
-        let page_size = page_size.min(100);
-        let offset = (page - 1) * page_size;
-
-        // Construir WHERE clause com parâmetros seguros
-        let (where_clause, params) = if let Some(term) = find {
-            let search_fields = self.searchable_fields();
-            if !search_fields.is_empty() {
-                let mut field_parts = Vec::new();
-                for field in search_fields {
-                    field_parts.push(format!("{} ILIKE $1", field));
-                }
-                let where_str = format!("WHERE ({})", field_parts.join(" OR "));
-                (where_str, vec![format!("%{}%", term)])
-            } else {
-                (String::new(), vec![])
-            }
-        } else {
-            (String::new(), vec![])
-        };
+        self.list(pool, &ListQuery::new(find, page, page_size).view()).await
+    }
+}
 
-        // === COUNT ===
-        let count_query = format!(
-            "SELECT COUNT(*) FROM {} {}",
-            self.from_clause(),
-            where_clause
-        );
+#[cfg(test)]
+mod tests {
+    use super::{
+        Cursor, PaginatedResponse, SortDir, limites_pagina, plain_column, predicado_cursor, termo_tsquery,
+    };
+
+    #[test]
+    fn test_limites_pagina() {
+        assert_eq!(limites_pagina(0, 0), (1, 1));
+        assert_eq!(limites_pagina(-3, -10), (1, 1));
+        assert_eq!(limites_pagina(2, 500), (2, 100));
+
+        let (page, page_size) = limites_pagina(i32::MAX, 100);
+        assert!((page - 1).checked_mul(page_size).is_some());
+
+        // page_size=0 na URL não pode dividir por zero
+        let pagina = PaginatedResponse::<i32>::offset(vec![], 5, 1, 0);
+        assert_eq!((pagina.page_size, pagina.total_pages), (1, 5));
+        assert_eq!(PaginatedResponse::<i32>::offset(vec![], 0, 1, 20).total_pages, 1);
+        assert_eq!(PaginatedResponse::<i32>::offset(vec![], 41, 1, 20).total_pages, 3);
+    }
 
-        let total: (i64,) = if params.is_empty() {
-            sqlx::query_as(&count_query).fetch_one(pool).await?
-        } else {
-            sqlx::query_as(&count_query)
-                .bind(&params[0])
-                .fetch_one(pool)
-                .await?
-        };
+    #[test]
+    fn test_cursor_ida_e_volta() {
+        let cursor = Cursor::encode(&42i64, Some("nome"), SortDir::Asc).unwrap();
+        let id: Option<i64> = Cursor::decode(&cursor, Some("nome"), SortDir::Asc).unwrap();
+        assert_eq!(id, Some(42));
+
+        // primeira página
+        assert_eq!(Cursor::<i64>::decode("", None, SortDir::Desc).unwrap(), None);
+        // ordenação diferente da que gerou o cursor
+        assert!(Cursor::<i64>::decode(&cursor, Some("nome"), SortDir::Desc).is_err());
+        assert!(Cursor::<i64>::decode("lixo", None, SortDir::Desc).is_err());
+    }
 
-        // === DATA ===
-        let data_query = format!(
-            "SELECT {} FROM {} {} ORDER BY {} DESC LIMIT ${} OFFSET ${}",
-            self.select_clause_view(),
-            self.from_clause_view(),
-            where_clause,
-            self.order_by_column(),
-            if params.is_empty() { 1 } else { 2 },
-            if params.is_empty() { 2 } else { 3 }
+    #[test]
+    fn test_predicado_cursor_com_nulos() {
+        let sql = predicado_cursor("c.nome", "m.id", SortDir::Asc);
+        assert_eq!(
+            sql,
+            " AND (CASE WHEN __cursor.__cursor_valor IS NULL \
+             THEN c.nome IS NULL AND m.id > __cursor.__cursor_ref \
+             ELSE c.nome IS NULL OR (c.nome, m.id) > (__cursor.__cursor_valor, __cursor.__cursor_ref) END)"
         );
+        assert!(predicado_cursor("c.nome", "m.id", SortDir::Desc).contains("(c.nome, m.id) < ("));
+    }
 
-        let data = if params.is_empty() {
-            sqlx::query_as::<_, T>(&data_query)
-                .bind(page_size as i64)
-                .bind(offset as i64)
-                .fetch_all(pool)
-                .await?
-        } else {
-            sqlx::query_as::<_, T>(&data_query)
-                .bind(&params[0])
-                .bind(page_size as i64)
-                .bind(offset as i64)
-                .fetch_all(pool)
-                .await?
-        };
-
-        let total_pages: i32 = if total.0 == 0 {
-            1
-        } else {
-            ((total.0 as f32) / (page_size as f32)).ceil() as i32
-        };
+    #[test]
+    fn test_plain_column() {
+        assert_eq!(plain_column("m.deleted_at"), "deleted_at");
+        assert_eq!(plain_column("deleted_at"), "deleted_at");
+    }
 
-        Ok(PaginatedResponse {
-            data,
-            total_records: total.0,
-            page,
-            page_size,
-            total_pages,
-        })
+    #[test]
+    fn test_termo_tsquery() {
+        assert_eq!(termo_tsquery("João  Sil").as_deref(), Some("joão:* & sil:*"));
+        assert_eq!(termo_tsquery("123.456.789-00").as_deref(), Some("12345678900:*"));
+        assert_eq!(
+            termo_tsquery("fulano@x.com").as_deref(),
+            Some("fulano:* & x:* & com:*")
+        );
+        // operadores do tsquery não passam
+        assert_eq!(termo_tsquery("a & !b | (c)").as_deref(), Some("a:* & b:* & c:*"));
+        assert_eq!(termo_tsquery(" !& ").as_deref(), None);
     }
 }
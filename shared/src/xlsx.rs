@@ -0,0 +1,383 @@
+use std::io::Write;
+
+use chrono::{Datelike, Timelike, Utc};
+use crc32fast::Hasher;
+use flate2::{Compression, write::DeflateEncoder};
+
+/*
+escrita incremental de .xlsx (zip com deflate), usada pela exportação
+cada planilha é uma entrada do zip gravada aos poucos: o cabeçalho local vai sem
+crc/tamanhos (bit 3) e o data descriptor fecha a entrada; os arquivos fixos
+(workbook, estilos, content types) vão no final, quando o número de planilhas é conhecido
+sem ZIP64: o arquivo fica limitado a 4 GB
+*/
+
+/// Linhas por planilha (limite do Excel, já contando o cabeçalho)
+pub(crate) const MAX_LINHAS_PLANILHA: usize = 1_048_576;
+
+/// Índices de estilo (cellXfs) definidos em `STYLES`
+#[derive(Clone, Copy)]
+pub(crate) enum Estilo {
+    Inteiro = 0,
+    Decimal = 1,
+    Moeda = 2,
+    Data = 3,
+    DataHora = 4,
+    Cabecalho = 5,
+}
+
+pub(crate) enum Celula {
+    Vazia,
+    Texto(String),
+    Numero(f64, Estilo),
+}
+
+struct EntradaCentral {
+    nome: String,
+    crc: u32,
+    comprimido: u32,
+    tamanho: u32,
+    offset: u32,
+}
+
+/// Entrada do zip sendo comprimida
+struct EntradaAberta {
+    nome: String,
+    offset: u32,
+    encoder: DeflateEncoder<Vec<u8>>,
+    hasher: Hasher,
+    tamanho: u64,
+    comprimido: u64,
+}
+
+pub(crate) struct XlsxWriter {
+    entradas: Vec<EntradaCentral>,
+    aberta: Option<EntradaAberta>,
+    offset: u64,
+    planilhas: usize,
+    linhas_planilha: usize,
+    cabecalho: Vec<String>,
+    data_dos: u16,
+    hora_dos: u16,
+}
+
+impl XlsxWriter {
+    pub(crate) fn new(cabecalho: Vec<String>) -> Self {
+        let agora = Utc::now().naive_utc();
+        Self {
+            entradas: Vec::new(),
+            aberta: None,
+            offset: 0,
+            planilhas: 0,
+            linhas_planilha: 0,
+            cabecalho,
+            data_dos: (((agora.year() - 1980).max(0) as u16) << 9)
+                | ((agora.month() as u16) << 5)
+                | agora.day() as u16,
+            hora_dos: ((agora.hour() as u16) << 11)
+                | ((agora.minute() as u16) << 5)
+                | (agora.second() as u16 / 2),
+        }
+    }
+
+    /// Início do arquivo: abre a primeira planilha com a linha de cabeçalho
+    pub(crate) fn iniciar(&mut self) -> std::io::Result<Vec<u8>> {
+        self.nova_planilha()
+    }
+
+    pub(crate) fn linha(&mut self, celulas: &[Celula]) -> std::io::Result<Vec<u8>> {
+        let mut saida = Vec::new();
+        if self.linhas_planilha >= MAX_LINHAS_PLANILHA {
+            saida.extend(self.fechar_planilha()?);
+            saida.extend(self.nova_planilha()?);
+        }
+        self.linhas_planilha += 1;
+        let xml = linha_xml(self.linhas_planilha, celulas);
+        self.escrever(xml.as_bytes())?;
+        saida.extend(self.drenar());
+        Ok(saida)
+    }
+
+    /// Fecha a planilha atual e grava os arquivos fixos e o diretório central
+    pub(crate) fn finalizar(mut self) -> std::io::Result<Vec<u8>> {
+        let mut saida = self.fechar_planilha()?;
+
+        let planilhas = self.planilhas;
+        saida.extend(self.arquivo("[Content_Types].xml", &content_types(planilhas))?);
+        saida.extend(self.arquivo("_rels/.rels", RELS)?);
+        saida.extend(self.arquivo("xl/workbook.xml", &workbook(planilhas))?);
+        saida.extend(self.arquivo("xl/_rels/workbook.xml.rels", &workbook_rels(planilhas))?);
+        saida.extend(self.arquivo("xl/styles.xml", STYLES)?);
+
+        let inicio = self.offset;
+        let mut central = Vec::new();
+        for e in &self.entradas {
+            central.extend(0x02014b50u32.to_le_bytes());
+            central.extend(20u16.to_le_bytes()); // versão que criou
+            central.extend(20u16.to_le_bytes()); // versão necessária
+            central.extend(0x0808u16.to_le_bytes()); // data descriptor + nomes UTF-8
+            central.extend(8u16.to_le_bytes()); // deflate
+            central.extend(self.hora_dos.to_le_bytes());
+            central.extend(self.data_dos.to_le_bytes());
+            central.extend(e.crc.to_le_bytes());
+            central.extend(e.comprimido.to_le_bytes());
+            central.extend(e.tamanho.to_le_bytes());
+            central.extend((e.nome.len() as u16).to_le_bytes());
+            central.extend([0u8; 12]); // extra, comentário, disco, atributos internos/externos
+            central.extend(e.offset.to_le_bytes());
+            central.extend(e.nome.as_bytes());
+        }
+        let tamanho_central = central.len() as u32;
+
+        central.extend(0x06054b50u32.to_le_bytes());
+        central.extend([0u8; 4]); // disco atual e disco do diretório
+        central.extend((self.entradas.len() as u16).to_le_bytes());
+        central.extend((self.entradas.len() as u16).to_le_bytes());
+        central.extend(tamanho_central.to_le_bytes());
+        central.extend(u32_zip(inicio)?.to_le_bytes());
+        central.extend(0u16.to_le_bytes()); // comentário
+
+        saida.extend(central);
+        Ok(saida)
+    }
+
+    fn nova_planilha(&mut self) -> std::io::Result<Vec<u8>> {
+        self.planilhas += 1;
+        self.linhas_planilha = 1;
+        let mut saida = self.abrir(format!("xl/worksheets/sheet{}.xml", self.planilhas))?;
+
+        let cabecalho: Vec<Celula> = self
+            .cabecalho
+            .iter()
+            .map(|c| Celula::Texto(c.clone()))
+            .collect();
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+             <sheetViews><sheetView workbookViewId=\"0\"><pane ySplit=\"1\" topLeftCell=\"A2\" \
+             activePane=\"bottomLeft\" state=\"frozen\"/></sheetView></sheetViews><sheetData>",
+        );
+        xml.push_str(&linha_xml_estilo(1, &cabecalho, Some(Estilo::Cabecalho)));
+        self.escrever(xml.as_bytes())?;
+        saida.extend(self.drenar());
+        Ok(saida)
+    }
+
+    fn fechar_planilha(&mut self) -> std::io::Result<Vec<u8>> {
+        self.escrever(b"</sheetData></worksheet>")?;
+        self.fechar()
+    }
+
+    /// Grava o cabeçalho local e passa a comprimir os dados da entrada
+    fn abrir(&mut self, nome: String) -> std::io::Result<Vec<u8>> {
+        let mut cabecalho = Vec::new();
+        cabecalho.extend(0x04034b50u32.to_le_bytes());
+        cabecalho.extend(20u16.to_le_bytes());
+        cabecalho.extend(0x0808u16.to_le_bytes());
+        cabecalho.extend(8u16.to_le_bytes());
+        cabecalho.extend(self.hora_dos.to_le_bytes());
+        cabecalho.extend(self.data_dos.to_le_bytes());
+        cabecalho.extend([0u8; 12]); // crc e tamanhos vão no data descriptor
+        cabecalho.extend((nome.len() as u16).to_le_bytes());
+        cabecalho.extend(0u16.to_le_bytes());
+        cabecalho.extend(nome.as_bytes());
+
+        let offset = u32_zip(self.offset)?;
+        self.offset += cabecalho.len() as u64;
+        self.aberta = Some(EntradaAberta {
+            nome,
+            offset,
+            encoder: DeflateEncoder::new(Vec::new(), Compression::default()),
+            hasher: Hasher::new(),
+            tamanho: 0,
+            comprimido: 0,
+        });
+        Ok(cabecalho)
+    }
+
+    fn escrever(&mut self, dados: &[u8]) -> std::io::Result<()> {
+        let entrada = self.aberta.as_mut().expect("entrada do zip aberta");
+        entrada.hasher.update(dados);
+        entrada.tamanho += dados.len() as u64;
+        entrada.encoder.write_all(dados)
+    }
+
+    /// Bytes já comprimidos da entrada aberta
+    fn drenar(&mut self) -> Vec<u8> {
+        let entrada = self.aberta.as_mut().expect("entrada do zip aberta");
+        let dados = std::mem::take(entrada.encoder.get_mut());
+        entrada.comprimido += dados.len() as u64;
+        self.offset += dados.len() as u64;
+        dados
+    }
+
+    /// Finaliza a compressão e grava o data descriptor
+    fn fechar(&mut self) -> std::io::Result<Vec<u8>> {
+        let entrada = self.aberta.take().expect("entrada do zip aberta");
+        let restante = entrada.encoder.finish()?;
+        let comprimido = u32_zip(entrada.comprimido + restante.len() as u64)?;
+        let tamanho = u32_zip(entrada.tamanho)?;
+        let crc = entrada.hasher.finalize();
+
+        let mut saida = restante;
+        saida.extend(0x08074b50u32.to_le_bytes());
+        saida.extend(crc.to_le_bytes());
+        saida.extend(comprimido.to_le_bytes());
+        saida.extend(tamanho.to_le_bytes());
+        self.offset += saida.len() as u64;
+
+        self.entradas.push(EntradaCentral {
+            nome: entrada.nome,
+            crc,
+            comprimido,
+            tamanho,
+            offset: entrada.offset,
+        });
+        Ok(saida)
+    }
+
+    fn arquivo(&mut self, nome: &str, conteudo: &str) -> std::io::Result<Vec<u8>> {
+        let mut saida = self.abrir(nome.to_string())?;
+        self.escrever(conteudo.as_bytes())?;
+        saida.extend(self.drenar());
+        saida.extend(self.fechar()?);
+        Ok(saida)
+    }
+}
+
+fn u32_zip(valor: u64) -> std::io::Result<u32> {
+    u32::try_from(valor).map_err(|_| std::io::Error::other("arquivo xlsx maior que 4 GB"))
+}
+
+fn linha_xml(numero: usize, celulas: &[Celula]) -> String {
+    linha_xml_estilo(numero, celulas, None)
+}
+
+fn linha_xml_estilo(numero: usize, celulas: &[Celula], estilo: Option<Estilo>) -> String {
+    let mut xml = format!("<row r=\"{}\">", numero);
+    for celula in celulas {
+        match celula {
+            Celula::Vazia => xml.push_str("<c/>"),
+            Celula::Texto(texto) => {
+                xml.push_str("<c t=\"inlineStr\"");
+                if let Some(estilo) = estilo {
+                    xml.push_str(&format!(" s=\"{}\"", estilo as u8));
+                }
+                xml.push_str("><is><t xml:space=\"preserve\">");
+                xml.push_str(&escapar_xml(texto));
+                xml.push_str("</t></is></c>");
+            }
+            Celula::Numero(valor, estilo) => {
+                xml.push_str(&format!("<c s=\"{}\"><v>{}</v></c>", *estilo as u8, valor));
+            }
+        }
+    }
+    xml.push_str("</row>");
+    xml
+}
+
+/// Escapa texto para XML, descartando caracteres de controle inválidos
+fn escapar_xml(texto: &str) -> String {
+    let mut saida = String::with_capacity(texto.len());
+    for c in texto.chars() {
+        match c {
+            '&' => saida.push_str("&amp;"),
+            '<' => saida.push_str("&lt;"),
+            '>' => saida.push_str("&gt;"),
+            '"' => saida.push_str("&quot;"),
+            '\t' | '\n' | '\r' => saida.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => saida.push(c),
+        }
+    }
+    saida
+}
+
+fn content_types(planilhas: usize) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+         <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+         <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+         <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+         <Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>",
+    );
+    for i in 1..=planilhas {
+        xml.push_str(&format!(
+            "<Override PartName=\"/xl/worksheets/sheet{}.xml\" \
+             ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+            i
+        ));
+    }
+    xml.push_str("</Types>");
+    xml
+}
+
+fn workbook(planilhas: usize) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+         xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>",
+    );
+    for i in 1..=planilhas {
+        xml.push_str(&format!(
+            "<sheet name=\"Planilha{i}\" sheetId=\"{i}\" r:id=\"rId{i}\"/>"
+        ));
+    }
+    xml.push_str("</sheets></workbook>");
+    xml
+}
+
+fn workbook_rels(planilhas: usize) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    );
+    for i in 1..=planilhas {
+        xml.push_str(&format!(
+            "<Relationship Id=\"rId{i}\" \
+             Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" \
+             Target=\"worksheets/sheet{i}.xml\"/>"
+        ));
+    }
+    xml.push_str(&format!(
+        "<Relationship Id=\"rId{}\" \
+         Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" \
+         Target=\"styles.xml\"/></Relationships>",
+        planilhas + 1
+    ));
+    xml
+}
+
+const RELS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+</Relationships>";
+
+/*
+formatos na ordem de `Estilo`; o Excel troca os separadores pelos do idioma do usuário,
+então "#,##0.00" aparece como 1.234,56 em pt-BR
+*/
+const STYLES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+<numFmts count=\"3\">\
+<numFmt numFmtId=\"164\" formatCode=\"&quot;R$ &quot;#,##0.00\"/>\
+<numFmt numFmtId=\"165\" formatCode=\"dd/mm/yyyy\"/>\
+<numFmt numFmtId=\"166\" formatCode=\"dd/mm/yyyy hh:mm\"/>\
+</numFmts>\
+<fonts count=\"2\"><font><sz val=\"11\"/><name val=\"Calibri\"/></font>\
+<font><b/><sz val=\"11\"/><name val=\"Calibri\"/></font></fonts>\
+<fills count=\"2\"><fill><patternFill patternType=\"none\"/></fill>\
+<fill><patternFill patternType=\"gray125\"/></fill></fills>\
+<borders count=\"1\"><border><left/><right/><top/><bottom/><diagonal/></border></borders>\
+<cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>\
+<cellXfs count=\"6\">\
+<xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>\
+<xf numFmtId=\"4\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+<xf numFmtId=\"164\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+<xf numFmtId=\"165\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+<xf numFmtId=\"166\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+<xf numFmtId=\"0\" fontId=\"1\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/>\
+</cellXfs>\
+<cellStyles count=\"1\"><cellStyle name=\"Normal\" xfId=\"0\" builtinId=\"0\"/></cellStyles>\
+</styleSheet>";